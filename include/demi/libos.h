@@ -87,6 +87,44 @@ extern "C"
      */
     extern int demi_close(int qd);
 
+    /**
+     * @brief Sets an option on a socket I/O queue. Only SO_SNDBUF at the SOL_SOCKET level is supported, and it sets the
+     * maximum number of bytes queued for sending on a TCP connection.
+     *
+     * @param qd      Target I/O queue descriptor.
+     * @param level   Protocol level at which the option resides.
+     * @param optname Option name.
+     * @param optval  Option value.
+     * @param optlen  Length of the option value.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_setsockopt(int qd, int level, int optname, const void *optval, socklen_t optlen);
+
+    /**
+     * @brief Gets an option of a socket I/O queue. Only SO_SNDBUF at the SOL_SOCKET level is supported.
+     *
+     * @param qd      Target I/O queue descriptor.
+     * @param level   Protocol level at which the option resides.
+     * @param optname Option name.
+     * @param optval  Store location for the option value.
+     * @param optlen  Length of @p optval on input, and length of the option value on output.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_getsockopt(int qd, int level, int optname, void *optval, socklen_t *optlen);
+
+    /**
+     * @brief Changes which log messages are emitted, without restarting the application. The specification has the
+     * same syntax as the RUST_LOG environment variable, and may filter by module (e.g. "warn,
+     * demikernel::inetstack::protocols::tcp=debug").
+     *
+     * @param spec Log specification.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_set_log_spec(const char *spec);
+
     /**
      * @brief Asynchronously pushes a scatter-gather array to an I/O queue.
      *
@@ -122,6 +160,16 @@ extern "C"
      */
     extern int demi_pop(demi_qtoken_t *qt_out, int qd);
 
+    /**
+     * @brief Asynchronously peeks a scatter-gather array from an I/O queue, without consuming the data.
+     *
+     * @param qt_out Store location for I/O queue token.
+     * @param qd     Target I/O queue descriptor.
+     *
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     */
+    extern int demi_peek(demi_qtoken_t *qt_out, int qd);
+
 #ifdef __cplusplus
 }
 #endif
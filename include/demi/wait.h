@@ -29,10 +29,24 @@ extern "C"
      * @param qt      I/O queue token of the target operation to wait for completion.
      * @param abstime Absolute timeout in seconds and nanoseconds since Epoch.
      *
-     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead.
+     * @return On successful completion, zero is returned. On failure, a positive error code is returned instead. If
+     * the timeout expires, ETIMEDOUT is returned. On Catnap, Catnip, and Catpowder, the target operation is then
+     * cancelled and @p qt is no longer valid, so waiting on it again fails with EINVAL. On Catcollar, the target
+     * operation is left pending, because io_uring may still access its buffers, and it may be waited on again.
      */
     extern int demi_timedwait(demi_qresult_t *qr_out, demi_qtoken_t qt, const struct timespec *abstime);
 
+    /**
+     * @brief Cancels a pending asynchronous I/O operation.
+     *
+     * @param qt I/O queue token of the target operation to cancel.
+     *
+     * @return On successful completion, zero is returned and the I/O queue token is no longer valid. On failure, a
+     * positive error code is returned instead. If the target operation has already completed, EALREADY is returned.
+     * Catcollar does not support cancellation and returns ENOTSUP.
+     */
+    extern int demi_cancel(demi_qtoken_t qt);
+
     /**
      * @brief Waits for the first asynchronous I/O operation in a list to complete.
      *
@@ -45,6 +59,24 @@ extern "C"
      */
     extern int demi_wait_any(demi_qresult_t *qr_out, int *ready_offset, const demi_qtoken_t qts[], int num_qts);
 
+    /**
+     * @brief Waits for asynchronous I/O operations in a list to complete, and returns as many completed operations as
+     * are ready, up to a limit.
+     *
+     * @param qrs_out       Store location for the results of the completed I/O operations.
+     * @param ready_offsets Store location for the offsets in the list of I/O queue tokens of the completed operations.
+     * @param num_ready     Store location for the number of completed I/O operations.
+     * @param max_results   Capacity of @p qrs_out and @p ready_offsets.
+     * @param qts           List of I/O queue tokens to wait for completion.
+     * @param num_qts       Length of the list of I/O queue tokens to wait for completion.
+     * @param abstime       Absolute timeout in seconds and nanoseconds since Epoch, or NULL to wait indefinitely.
+     *
+     * @return On successful completion, zero is returned and at least one result is stored. On failure, a positive
+     * error code is returned instead. If the timeout expires before any operation completes, ETIMEDOUT is returned.
+     */
+    extern int demi_wait_many(demi_qresult_t *qrs_out, int *ready_offsets, int *num_ready, int max_results,
+                              const demi_qtoken_t qts[], int num_qts, const struct timespec *abstime);
+
 #ifdef __cplusplus
 }
 #endif
@@ -59,6 +59,14 @@ DRIVER ?= $(shell [ ! -z "`lspci | grep -E "ConnectX-[4,5]"`" ] && echo mlx5 ||
 CARGO_FEATURES += --features=$(DRIVER)
 endif
 
+# Switch for memory poisoning. This is on by default for debug builds of catnip.
+ifeq ($(LIBOS)$(BUILD),catnipdev)
+export MEMPOOL_DEBUG ?= yes
+endif
+ifeq ($(MEMPOOL_DEBUG),yes)
+CARGO_FEATURES += --features=mempool-debug
+endif
+
 # Switch for profiler.
 export PROFILER=no
 ifeq ($(PROFILER),yes)
@@ -170,8 +178,6 @@ clean: clean-examples clean-tests
 #=======================================================================================================================
 
 export CONFIG_PATH ?= $(HOME)/config.yaml
-export MTU ?= 1500
-export MSS ?= 1500
 export PEER ?= server
 export TEST ?= udp_push_pop
 export TIMEOUT ?= 30
@@ -193,3 +199,10 @@ test-unit-c: $(BINDIR)/syscalls.elf
 # Rust unit tests.
 test-unit-rust:
 	$(CARGO) test --lib $(CARGO_FLAGS) $(CARGO_FEATURES) -- --nocapture $(UNIT_TEST)
+
+# Runs the datapath conformance suite against the selected LibOS.
+test-conformance: test-conformance-rust
+
+# Rust conformance tests.
+test-conformance-rust:
+	$(CARGO) test --test conformance $(CARGO_FLAGS) $(CARGO_FEATURES) --features=conformance -- --nocapture
@@ -143,6 +143,10 @@ impl SchedulerFuture for Operation {
         self
     }
 
+    fn as_any_ref(&self) -> &dyn Any {
+        self
+    }
+
     fn get_future(&self) -> &dyn Future<Output = ()> {
         todo!()
     }
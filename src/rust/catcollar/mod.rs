@@ -36,6 +36,7 @@ use crate::{
             DataBuffer,
             MemoryRuntime,
         },
+        network::topology::Topology,
         queue::IoQueueTable,
         types::{
             demi_accept_result_t,
@@ -48,7 +49,10 @@ use crate::{
         QToken,
         QType,
     },
-    scheduler::SchedulerHandle,
+    scheduler::{
+        Scheduler,
+        SchedulerHandle,
+    },
 };
 use ::libc::c_int;
 use ::nix::{
@@ -116,6 +120,28 @@ impl CatcollarLibOS {
         }
     }
 
+    /// Returns a description of the datapath instantiated by the target LibOS. Sockets are handled by the kernel, so
+    /// there is no device-specific information to report.
+    pub fn topology(&self) -> Topology {
+        Topology::new("catcollar")
+    }
+
+    /// Attaches an application context to an I/O queue descriptor. The context is dropped when the I/O queue
+    /// descriptor is closed.
+    pub fn set_context(&mut self, qd: QDesc, context: Box<dyn Any>) -> Result<(), Fail> {
+        self.qtable.set_context(qd, context)
+    }
+
+    /// Gets the application context attached to an I/O queue descriptor.
+    pub fn get_context(&self, qd: QDesc) -> Result<Option<&dyn Any>, Fail> {
+        self.qtable.get_context(qd)
+    }
+
+    /// Gets a mutable reference to the application context attached to an I/O queue descriptor.
+    pub fn get_context_mut(&mut self, qd: QDesc) -> Result<Option<&mut dyn Any>, Fail> {
+        self.qtable.get_context_mut(qd)
+    }
+
     /// Creates a socket.
     pub fn socket(&mut self, domain: c_int, typ: c_int, _protocol: c_int) -> Result<QDesc, Fail> {
         trace!("socket() domain={:?}, type={:?}, protocol={:?}", domain, typ, _protocol);
@@ -234,7 +260,10 @@ impl CatcollarLibOS {
         trace!("close() qd={:?}", qd);
         match self.sockets.get(&qd) {
             Some(&fd) => match unistd::close(fd) {
-                Ok(_) => Ok(()),
+                Ok(_) => {
+                    self.qtable.take_context(qd)?;
+                    Ok(())
+                },
                 _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
             },
             _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
@@ -286,6 +315,19 @@ impl CatcollarLibOS {
         self.do_push(qd, buf)
     }
 
+    /// Pushes data that is scattered across several slices to a socket. The slices are gathered into a single buffer.
+    pub fn push_slices(&mut self, qd: QDesc, slices: &[&[u8]]) -> Result<QToken, Fail> {
+        trace!("push_slices() qd={:?}, nslices={:?}", qd, slices.len());
+
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slices(slices));
+        if buf.len() == 0 {
+            return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
+        }
+
+        // Issue push operation.
+        self.do_push(qd, buf)
+    }
+
     /// Handles a pushto operation.
     fn do_pushto(&mut self, qd: QDesc, buf: Buffer, remote: SocketAddrV4) -> Result<QToken, Fail> {
         match self.sockets.get(&qd) {
@@ -399,6 +441,13 @@ impl CatcollarLibOS {
         Ok(pack_result(&self.runtime, result, qd, qt.into()))
     }
 
+    /// Cancels a pending operation. This is not supported, because the kernel may still access the buffers of an
+    /// operation that has been submitted to io_uring.
+    pub fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        trace!("cancel() qt={:?}", qt);
+        Err(Fail::new(libc::ENOTSUP, "cannot cancel in-flight io_uring operations"))
+    }
+
     /// Waits for an operation to complete.
     pub fn wait2(&mut self, qt: QToken) -> Result<(QDesc, OperationResult), Fail> {
         #[cfg(feature = "profiler")]
@@ -432,6 +481,24 @@ impl CatcollarLibOS {
         Ok((i, pack_result(&self.runtime, r, qd, qts[i].into())))
     }
 
+    /// Waits for up to `max_results` operations to complete.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catcollar::wait_many");
+        trace!("wait_many(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let results: Vec<(usize, QDesc, OperationResult)> = self.wait_many2(qts, max_results, abstime)?;
+        Ok(results
+            .into_iter()
+            .map(|(i, qd, r)| (i, pack_result(&self.runtime, r, qd, qts[i].into())))
+            .collect())
+    }
+
     /// Waits for any operation to complete.
     pub fn wait_any2(&mut self, qts: &[QToken]) -> Result<(usize, QDesc, OperationResult), Fail> {
         #[cfg(feature = "profiler")]
@@ -463,6 +530,31 @@ impl CatcollarLibOS {
         }
     }
 
+    /// Waits for up to `max_results` operations to complete and returns all of them at once, along with their offset
+    /// in `qts`. If `abstime` is set and no operation completes before it, ETIMEDOUT is returned. If `qts` is empty,
+    /// `abstime` must be set, or EINVAL is returned. Operations that are still pending are left untouched.
+    pub fn wait_many2(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, QDesc, OperationResult)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catcollar::wait_many2");
+        trace!("wait_many2(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let scheduler: Scheduler = self.runtime.scheduler.clone();
+        let results: Vec<(usize, (QDesc, OperationResult))> = scheduler.wait_many(
+            self,
+            qts,
+            max_results,
+            abstime,
+            |libos: &mut Self| libos.runtime.scheduler.poll(),
+            Self::take_result,
+        )?;
+        Ok(results.into_iter().map(|(i, (qd, r))| (i, qd, r)).collect())
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         trace!("sgalloc() size={:?}", size);
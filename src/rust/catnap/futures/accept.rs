@@ -17,9 +17,11 @@ use ::nix::{
     sys::socket,
 };
 use ::std::{
+    cell::Cell,
     future::Future,
     os::unix::prelude::RawFd,
     pin::Pin,
+    rc::Rc,
     task::{
         Context,
         Poll,
@@ -38,6 +40,8 @@ pub struct AcceptFuture {
     fd: RawFd,
     /// Queue descriptor of incoming connection.
     new_qd: QDesc,
+    /// Set once the associated socket is closed.
+    closed: Rc<Cell<bool>>,
 }
 
 //==============================================================================
@@ -47,8 +51,8 @@ pub struct AcceptFuture {
 /// Associate Functions for Accept Operation Descriptors
 impl AcceptFuture {
     /// Creates a descriptor for an accept operation.
-    pub fn new(qd: QDesc, fd: RawFd, new_qd: QDesc) -> Self {
-        Self { qd, fd, new_qd }
+    pub fn new(qd: QDesc, fd: RawFd, new_qd: QDesc, closed: Rc<Cell<bool>>) -> Self {
+        Self { qd, fd, new_qd, closed }
     }
 
     /// Returns the queue descriptor associated to the target [AcceptFuture].
@@ -73,6 +77,10 @@ impl Future for AcceptFuture {
     /// Polls the target [AcceptFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &AcceptFuture = self.get_mut();
+        // The socket was closed while this operation was pending.
+        if self_.closed.get() {
+            return Poll::Ready(Err(Fail::new(libc::ECANCELED, "socket closed")));
+        }
         match socket::accept(self_.fd as i32) {
             // Operation completed.
             Ok(new_fd) => {
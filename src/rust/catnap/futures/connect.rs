@@ -17,9 +17,11 @@ use ::nix::{
     },
 };
 use ::std::{
+    cell::Cell,
     future::Future,
     os::unix::prelude::RawFd,
     pin::Pin,
+    rc::Rc,
     task::{
         Context,
         Poll,
@@ -38,6 +40,8 @@ pub struct ConnectFuture {
     fd: RawFd,
     /// Destination address.
     addr: SockaddrStorage,
+    /// Set once the associated socket is closed.
+    closed: Rc<Cell<bool>>,
 }
 
 //==============================================================================
@@ -47,8 +51,8 @@ pub struct ConnectFuture {
 /// Associate Functions for Connect Operation Descriptors
 impl ConnectFuture {
     /// Creates a descriptor for a connect operation.
-    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage) -> Self {
-        Self { qd, fd, addr }
+    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage, closed: Rc<Cell<bool>>) -> Self {
+        Self { qd, fd, addr, closed }
     }
 
     /// Returns the queue descriptor associated to the target [ConnectFuture].
@@ -68,6 +72,10 @@ impl Future for ConnectFuture {
     /// Polls the target [ConnectFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut ConnectFuture = self.get_mut();
+        // The socket was closed while this operation was pending.
+        if self_.closed.get() {
+            return Poll::Ready(Err(Fail::new(libc::ECANCELED, "socket closed")));
+        }
         match socket::connect(self_.fd as i32, &self_.addr) {
             // Operation completed.
             Ok(_) => {
@@ -10,6 +10,7 @@ pub mod connect;
 pub mod pop;
 pub mod push;
 pub mod pushto;
+pub mod sendqueue;
 
 //==============================================================================
 // Imports
@@ -131,6 +132,14 @@ impl Operation {
             _ => panic!("future not ready"),
         }
     }
+
+    /// Returns the queue descriptor reserved for the connection of an accept operation.
+    pub fn get_new_qd(&self) -> Option<QDesc> {
+        match self {
+            Operation::Accept(FutureResult { future, .. }) => Some(future.get_new_qd()),
+            _ => None,
+        }
+    }
 }
 
 //==============================================================================
@@ -143,6 +152,10 @@ impl SchedulerFuture for Operation {
         self
     }
 
+    fn as_any_ref(&self) -> &dyn Any {
+        self
+    }
+
     fn get_future(&self) -> &dyn Future<Output = ()> {
         todo!()
     }
@@ -17,10 +17,14 @@ use ::nix::{
     errno::Errno,
     sys::{
         socket,
-        socket::SockaddrStorage,
+        socket::{
+            MsgFlags,
+            SockaddrStorage,
+        },
     },
 };
 use ::std::{
+    cell::Cell,
     future::Future,
     net::{
         Ipv4Addr,
@@ -28,6 +32,7 @@ use ::std::{
     },
     os::unix::prelude::RawFd,
     pin::Pin,
+    rc::Rc,
     task::{
         Context,
         Poll,
@@ -51,6 +56,10 @@ pub struct PopFuture {
     qd: QDesc,
     /// Underlying file descriptor.
     fd: RawFd,
+    /// Leave the received data in the socket?
+    peek: bool,
+    /// Set once the associated socket is closed.
+    closed: Rc<Cell<bool>>,
 }
 
 //==============================================================================
@@ -60,8 +69,8 @@ pub struct PopFuture {
 /// Associate Functions for Pop Operation Descriptors
 impl PopFuture {
     /// Creates a descriptor for a pop operation.
-    pub fn new(qd: QDesc, fd: RawFd) -> Self {
-        Self { qd, fd }
+    pub fn new(qd: QDesc, fd: RawFd, peek: bool, closed: Rc<Cell<bool>>) -> Self {
+        Self { qd, fd, peek, closed }
     }
 
     /// Returns the queue descriptor associated to the target [PopFuture].
@@ -81,8 +90,17 @@ impl Future for PopFuture {
     /// Polls the target [PopFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PopFuture = self.get_mut();
+        // The socket was closed while this operation was pending.
+        if self_.closed.get() {
+            return Poll::Ready(Err(Fail::new(libc::ECANCELED, "socket closed")));
+        }
         let mut bytes: [u8; POP_SIZE] = [0; POP_SIZE];
-        match socket::recvfrom::<SockaddrStorage>(self_.fd, &mut bytes[..]) {
+        let result: Result<(usize, Option<SockaddrStorage>), Errno> = if self_.peek {
+            socket::recv(self_.fd, &mut bytes[..], MsgFlags::MSG_PEEK).map(|nbytes| (nbytes, None))
+        } else {
+            socket::recvfrom::<SockaddrStorage>(self_.fd, &mut bytes[..])
+        };
+        match result {
             // Operation completed.
             Ok((nbytes, socketaddr)) => {
                 trace!("data received ({:?}/{:?} bytes)", nbytes, POP_SIZE);
@@ -5,6 +5,7 @@
 // Imports
 //==============================================================================
 
+use super::sendqueue::SendQueue;
 use crate::runtime::{
     fail::Fail,
     memory::Buffer,
@@ -15,9 +16,11 @@ use ::nix::{
     sys::socket,
 };
 use ::std::{
+    cell::Cell,
     future::Future,
     os::unix::prelude::RawFd,
     pin::Pin,
+    rc::Rc,
     task::{
         Context,
         Poll,
@@ -36,6 +39,14 @@ pub struct PushFuture {
     fd: RawFd,
     /// Buffer to send.
     buf: Buffer,
+    /// Number of bytes of the buffer that were already sent.
+    sent: usize,
+    /// Send queue of the associated queue descriptor.
+    queue: SendQueue,
+    /// Position of this operation in the send queue.
+    ticket: Option<u64>,
+    /// Set once the associated socket is closed.
+    closed: Rc<Cell<bool>>,
 }
 
 //==============================================================================
@@ -45,8 +56,17 @@ pub struct PushFuture {
 /// Associate Functions for Push Operation Descriptors
 impl PushFuture {
     /// Creates a descriptor for a push operation.
-    pub fn new(qd: QDesc, fd: RawFd, buf: Buffer) -> Self {
-        Self { qd, fd, buf }
+    pub fn new(qd: QDesc, fd: RawFd, buf: Buffer, queue: SendQueue, closed: Rc<Cell<bool>>) -> Self {
+        let ticket: Option<u64> = Some(queue.enqueue());
+        Self {
+            qd,
+            fd,
+            buf,
+            sent: 0,
+            queue,
+            ticket,
+            closed,
+        }
     }
 
     /// Returns the queue descriptor associated to the target [PushFuture].
@@ -66,12 +86,36 @@ impl Future for PushFuture {
     /// Polls the target [PushFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushFuture = self.get_mut();
-        match socket::send(self_.fd, &self_.buf[..], socket::MsgFlags::empty()) {
+        let ticket: u64 = self_.ticket.expect("polled a completed push operation");
+
+        // The socket was closed while this operation was pending.
+        if self_.closed.get() {
+            self_.queue.dequeue(ticket);
+            self_.ticket = None;
+            return Poll::Ready(Err(Fail::new(libc::ECANCELED, "socket closed")));
+        }
+
+        // Wait until all push operations that were issued before this one are done.
+        if !self_.queue.is_head(ticket) {
+            ctx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        match socket::send(self_.fd, &self_.buf[self_.sent..], socket::MsgFlags::empty()) {
             // Operation completed.
-            Ok(nbytes) => {
+            Ok(nbytes) if self_.sent + nbytes == self_.buf.len() => {
                 trace!("data pushed ({:?}/{:?} bytes)", nbytes, self_.buf.len());
+                self_.queue.dequeue(ticket);
+                self_.ticket = None;
                 Poll::Ready(Ok(()))
             },
+            // Partial write, so hold on to the head of the send queue.
+            Ok(nbytes) => {
+                trace!("data partially pushed ({:?}/{:?} bytes)", nbytes, self_.buf.len());
+                self_.sent += nbytes;
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            },
             // Operation in progress.
             Err(e) if e == Errno::EWOULDBLOCK || e == Errno::EAGAIN => {
                 ctx.waker().wake_by_ref();
@@ -80,8 +124,152 @@ impl Future for PushFuture {
             // Error.
             Err(e) => {
                 warn!("push failed ({:?})", e);
+                self_.queue.dequeue(ticket);
+                self_.ticket = None;
                 Poll::Ready(Err(Fail::new(e as i32, "operation failed")))
             },
         }
     }
 }
+
+/// Drop Trait Implementation for Push Operation Descriptors
+impl Drop for PushFuture {
+    /// Releases the position of the target [PushFuture] in the send queue, if it still holds one.
+    fn drop(&mut self) {
+        if let Some(ticket) = self.ticket.take() {
+            self.queue.dequeue(ticket);
+        }
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::PushFuture;
+    use crate::{
+        catnap::futures::sendqueue::SendQueue,
+        runtime::{
+            memory::{
+                Buffer,
+                DataBuffer,
+            },
+            QDesc,
+        },
+    };
+    use ::futures::task::noop_waker_ref;
+    use ::nix::{
+        sys::socket,
+        unistd,
+    };
+    use ::std::{
+        cell::Cell,
+        future::Future,
+        pin::Pin,
+        rc::Rc,
+        task::{
+            Context,
+            Poll,
+        },
+    };
+
+    #[test]
+    fn push_interleaved() {
+        let mut ctx = Context::from_waker(noop_waker_ref());
+        let (tx, rx) = socket::socketpair(
+            socket::AddressFamily::Unix,
+            socket::SockType::Stream,
+            None,
+            socket::SockFlag::SOCK_NONBLOCK,
+        )
+        .unwrap();
+
+        // Issue several push operations on the same queue descriptor.
+        let qd: QDesc = QDesc::from(0_usize);
+        let queue: SendQueue = SendQueue::new();
+        let closed: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+        let mut first: PushFuture = PushFuture::new(
+            qd,
+            tx,
+            Buffer::Heap(DataBuffer::from_slice(b"abc")),
+            queue.clone(),
+            closed.clone(),
+        );
+        let mut second: PushFuture = PushFuture::new(
+            qd,
+            tx,
+            Buffer::Heap(DataBuffer::from_slice(b"def")),
+            queue.clone(),
+            closed.clone(),
+        );
+        let mut third: PushFuture = PushFuture::new(
+            qd,
+            tx,
+            Buffer::Heap(DataBuffer::from_slice(b"ghi")),
+            queue.clone(),
+            closed.clone(),
+        );
+
+        // Poll push operations in reverse order.
+        assert!(Future::poll(Pin::new(&mut third), &mut ctx).is_pending());
+        assert!(Future::poll(Pin::new(&mut second), &mut ctx).is_pending());
+        assert!(Future::poll(Pin::new(&mut first), &mut ctx).is_ready());
+        assert!(Future::poll(Pin::new(&mut third), &mut ctx).is_pending());
+        assert!(Future::poll(Pin::new(&mut second), &mut ctx).is_ready());
+        assert!(Future::poll(Pin::new(&mut third), &mut ctx).is_ready());
+        assert!(queue.is_empty());
+
+        // Data must come out in issue order.
+        let mut buf: [u8; 16] = [0; 16];
+        let nbytes: usize = socket::recv(rx, &mut buf, socket::MsgFlags::empty()).unwrap();
+        assert_eq!(&buf[..nbytes], b"abcdefghi");
+
+        unistd::close(tx).unwrap();
+        unistd::close(rx).unwrap();
+    }
+
+    #[test]
+    fn push_cancelled_on_close() {
+        let mut ctx = Context::from_waker(noop_waker_ref());
+        let (tx, rx) = socket::socketpair(
+            socket::AddressFamily::Unix,
+            socket::SockType::Stream,
+            None,
+            socket::SockFlag::SOCK_NONBLOCK,
+        )
+        .unwrap();
+
+        // Issue two push operations, and close the socket before the second one gets to run.
+        let qd: QDesc = QDesc::from(0_usize);
+        let queue: SendQueue = SendQueue::new();
+        let closed: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+        let mut first: PushFuture = PushFuture::new(
+            qd,
+            tx,
+            Buffer::Heap(DataBuffer::from_slice(b"abc")),
+            queue.clone(),
+            closed.clone(),
+        );
+        let mut second: PushFuture = PushFuture::new(
+            qd,
+            tx,
+            Buffer::Heap(DataBuffer::from_slice(b"def")),
+            queue.clone(),
+            closed.clone(),
+        );
+        assert!(Future::poll(Pin::new(&mut first), &mut ctx).is_ready());
+        closed.set(true);
+
+        // The pending push operation completes with ECANCELED and leaves the send queue.
+        match Future::poll(Pin::new(&mut second), &mut ctx) {
+            Poll::Ready(Err(e)) => assert_eq!(e.errno, libc::ECANCELED),
+            _ => panic!("push operation should have been cancelled"),
+        }
+        assert!(queue.is_empty());
+
+        unistd::close(tx).unwrap();
+        unistd::close(rx).unwrap();
+    }
+}
@@ -19,9 +19,11 @@ use ::nix::{
     },
 };
 use ::std::{
+    cell::Cell,
     future::Future,
     os::unix::prelude::RawFd,
     pin::Pin,
+    rc::Rc,
     task::{
         Context,
         Poll,
@@ -42,6 +44,8 @@ pub struct PushtoFuture {
     fd: RawFd,
     /// Buffer to send.
     buf: Buffer,
+    /// Set once the associated socket is closed.
+    closed: Rc<Cell<bool>>,
 }
 
 //==============================================================================
@@ -51,8 +55,14 @@ pub struct PushtoFuture {
 /// Associate Functions for Pushto Operation Descriptors
 impl PushtoFuture {
     /// Creates a descriptor for a pushto operation.
-    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage, buf: Buffer) -> Self {
-        Self { qd, addr, fd, buf }
+    pub fn new(qd: QDesc, fd: RawFd, addr: SockaddrStorage, buf: Buffer, closed: Rc<Cell<bool>>) -> Self {
+        Self {
+            qd,
+            addr,
+            fd,
+            buf,
+            closed,
+        }
     }
 
     /// Returns the queue descriptor associated to the target [PushtoFuture].
@@ -72,6 +82,10 @@ impl Future for PushtoFuture {
     /// Polls the target [PushtoFuture].
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let self_: &mut PushtoFuture = self.get_mut();
+        // The socket was closed while this operation was pending.
+        if self_.closed.get() {
+            return Poll::Ready(Err(Fail::new(libc::ECANCELED, "socket closed")));
+        }
         match socket::sendto(self_.fd, &self_.buf[..], &self_.addr, MsgFlags::empty()) {
             // Operation completed.
             Ok(nbytes) => {
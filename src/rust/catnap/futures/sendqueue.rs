@@ -0,0 +1,147 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use ::std::{
+    cell::RefCell,
+    collections::HashSet,
+    rc::Rc,
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Inner State of a Send Queue
+struct SendQueueInner {
+    /// Ticket to be handed out to the next push operation.
+    next: u64,
+    /// Ticket of the push operation that is allowed to write to the socket.
+    head: u64,
+    /// Tickets of push operations that were dropped before reaching the head.
+    abandoned: HashSet<u64>,
+}
+
+/// Ordered Send Queue
+///
+/// Push operations that target the same queue descriptor are scheduled as independent co-routines, and the scheduler
+/// makes no promise on the order in which they are polled. A send queue hands out tickets in submission order and only
+/// lets the push operation that holds the head ticket write to the underlying socket, so that the byte stream matches
+/// the order in which pushes were issued.
+#[derive(Clone)]
+pub struct SendQueue(Rc<RefCell<SendQueueInner>>);
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Send Queues
+impl SendQueue {
+    /// Creates an empty send queue.
+    pub fn new() -> Self {
+        Self(Rc::new(RefCell::new(SendQueueInner {
+            next: 0,
+            head: 0,
+            abandoned: HashSet::new(),
+        })))
+    }
+
+    /// Reserves a slot at the tail of the target send queue.
+    pub fn enqueue(&self) -> u64 {
+        let mut inner = self.0.borrow_mut();
+        let ticket: u64 = inner.next;
+        inner.next += 1;
+        ticket
+    }
+
+    /// Checks if `ticket` is at the head of the target send queue.
+    pub fn is_head(&self, ticket: u64) -> bool {
+        self.0.borrow().head == ticket
+    }
+
+    /// Releases `ticket` from the target send queue, letting the next push operation go.
+    pub fn dequeue(&self, ticket: u64) {
+        let mut inner = self.0.borrow_mut();
+        if inner.head == ticket {
+            inner.head += 1;
+            // Skip over push operations that have been dropped while waiting.
+            loop {
+                let head: u64 = inner.head;
+                if !inner.abandoned.remove(&head) {
+                    break;
+                }
+                inner.head += 1;
+            }
+        } else {
+            debug_assert!(ticket > inner.head && ticket < inner.next);
+            inner.abandoned.insert(ticket);
+        }
+    }
+
+    /// Checks if there are no push operations left in the target send queue.
+    pub fn is_empty(&self) -> bool {
+        let inner = self.0.borrow();
+        inner.next - inner.head == inner.abandoned.len() as u64
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Default Trait Implementation for Send Queues
+impl Default for SendQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::SendQueue;
+
+    #[test]
+    fn send_queue_in_order() {
+        let queue: SendQueue = SendQueue::new();
+        let a: u64 = queue.enqueue();
+        let b: u64 = queue.enqueue();
+        let c: u64 = queue.enqueue();
+        assert!(!queue.is_empty());
+
+        assert!(queue.is_head(a));
+        assert!(!queue.is_head(b));
+        assert!(!queue.is_head(c));
+
+        queue.dequeue(a);
+        assert!(queue.is_head(b));
+        queue.dequeue(b);
+        assert!(queue.is_head(c));
+        queue.dequeue(c);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn send_queue_abandoned() {
+        let queue: SendQueue = SendQueue::new();
+        let a: u64 = queue.enqueue();
+        let b: u64 = queue.enqueue();
+        let c: u64 = queue.enqueue();
+
+        // Drop a push operation that is not at the head.
+        queue.dequeue(b);
+        assert!(queue.is_head(a));
+
+        // The abandoned ticket is skipped once the head moves on.
+        queue.dequeue(a);
+        assert!(queue.is_head(c));
+        queue.dequeue(c);
+        assert!(queue.is_empty());
+    }
+}
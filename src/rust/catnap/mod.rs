@@ -20,6 +20,7 @@ use self::futures::{
     pop::PopFuture,
     push::PushFuture,
     pushto::PushtoFuture,
+    sendqueue::SendQueue,
     Operation,
 };
 use crate::{
@@ -32,6 +33,7 @@ use crate::{
             DataBuffer,
             MemoryRuntime,
         },
+        network::topology::Topology,
         queue::IoQueueTable,
         types::{
             demi_accept_result_t,
@@ -44,7 +46,10 @@ use crate::{
         QToken,
         QType,
     },
-    scheduler::SchedulerHandle,
+    scheduler::{
+        Scheduler,
+        SchedulerHandle,
+    },
 };
 use ::libc::{
     c_int,
@@ -70,6 +75,7 @@ use ::nix::{
 };
 use ::std::{
     any::Any,
+    cell::Cell,
     collections::HashMap,
     mem,
     net::{
@@ -77,6 +83,7 @@ use ::std::{
         SocketAddrV4,
     },
     os::unix::prelude::RawFd,
+    rc::Rc,
     time::SystemTime,
 };
 
@@ -93,6 +100,10 @@ pub struct CatnapLibOS {
     qtable: IoQueueTable, // TODO: Move this to Demikernel module.
     /// Established sockets.
     sockets: HashMap<QDesc, RawFd>,
+    /// Ordered send queues of established sockets.
+    send_queues: HashMap<QDesc, SendQueue>,
+    /// Flags that tell pending operations of established sockets that their socket was closed.
+    closed: HashMap<QDesc, Rc<Cell<bool>>>,
     /// Underlying runtime.
     runtime: PosixRuntime,
 }
@@ -107,14 +118,40 @@ impl CatnapLibOS {
     pub fn new(_config: &Config) -> Self {
         let qtable: IoQueueTable = IoQueueTable::new();
         let sockets: HashMap<QDesc, RawFd> = HashMap::new();
+        let send_queues: HashMap<QDesc, SendQueue> = HashMap::new();
+        let closed: HashMap<QDesc, Rc<Cell<bool>>> = HashMap::new();
         let runtime: PosixRuntime = PosixRuntime::new();
         Self {
             qtable,
             sockets,
+            send_queues,
+            closed,
             runtime,
         }
     }
 
+    /// Returns a description of the datapath instantiated by the target LibOS. Sockets are handled by the kernel, so
+    /// there is no device-specific information to report.
+    pub fn topology(&self) -> Topology {
+        Topology::new("catnap")
+    }
+
+    /// Attaches an application context to an I/O queue descriptor. The context is dropped when the I/O queue
+    /// descriptor is closed.
+    pub fn set_context(&mut self, qd: QDesc, context: Box<dyn Any>) -> Result<(), Fail> {
+        self.qtable.set_context(qd, context)
+    }
+
+    /// Gets the application context attached to an I/O queue descriptor.
+    pub fn get_context(&self, qd: QDesc) -> Result<Option<&dyn Any>, Fail> {
+        self.qtable.get_context(qd)
+    }
+
+    /// Gets a mutable reference to the application context attached to an I/O queue descriptor.
+    pub fn get_context_mut(&mut self, qd: QDesc) -> Result<Option<&mut dyn Any>, Fail> {
+        self.qtable.get_context_mut(qd)
+    }
+
     /// Creates a socket.
     pub fn socket(&mut self, domain: c_int, typ: c_int, _protocol: c_int) -> Result<QDesc, Fail> {
         trace!("socket() domain={:?}, type={:?}, protocol={:?}", domain, typ, _protocol);
@@ -152,6 +189,8 @@ impl CatnapLibOS {
                 }
                 let qd: QDesc = self.qtable.alloc(qtype.into());
                 assert_eq!(self.sockets.insert(qd, fd).is_none(), true);
+                assert_eq!(self.send_queues.insert(qd, SendQueue::new()).is_none(), true);
+                assert_eq!(self.closed.insert(qd, Rc::new(Cell::new(false))).is_none(), true);
                 Ok(qd)
             },
             Err(err) => Err(Fail::new(err as i32, "failed to create socket")),
@@ -192,10 +231,10 @@ impl CatnapLibOS {
         trace!("accept(): qd={:?}", qd);
 
         // Issue accept operation.
-        match self.sockets.get(&qd) {
-            Some(&fd) => {
+        match (self.sockets.get(&qd), self.closed.get(&qd)) {
+            (Some(&fd), Some(closed)) => {
                 let new_qd: QDesc = self.qtable.alloc(QType::TcpSocket.into());
-                let future: Operation = Operation::from(AcceptFuture::new(qd, fd, new_qd));
+                let future: Operation = Operation::from(AcceptFuture::new(qd, fd, new_qd, closed.clone()));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => {
@@ -214,10 +253,10 @@ impl CatnapLibOS {
         trace!("connect() qd={:?}, remote={:?}", qd, remote);
 
         // Issue connect operation.
-        match self.sockets.get(&qd) {
-            Some(&fd) => {
+        match (self.sockets.get(&qd), self.closed.get(&qd)) {
+            (Some(&fd), Some(closed)) => {
                 let addr: SockaddrStorage = parse_addr(remote);
-                let future: Operation = Operation::from(ConnectFuture::new(qd, fd, addr));
+                let future: Operation = Operation::from(ConnectFuture::new(qd, fd, addr, closed.clone()));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -228,23 +267,33 @@ impl CatnapLibOS {
         }
     }
 
-    /// Closes a socket.
+    /// Closes a socket. The queue descriptor is released right away, and operations on it that are still pending
+    /// (accepts, connects, pushes, and pops) complete with `ECANCELED` the next time they are polled. Their queue
+    /// tokens remain valid until they are waited on.
     pub fn close(&mut self, qd: QDesc) -> Result<(), Fail> {
         trace!("close() qd={:?}", qd);
-        match self.sockets.get(&qd) {
-            Some(&fd) => match unistd::close(fd) {
-                Ok(_) => Ok(()),
-                _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        match self.sockets.remove(&qd) {
+            Some(fd) => {
+                if let Some(closed) = self.closed.remove(&qd) {
+                    closed.set(true);
+                }
+                self.send_queues.remove(&qd);
+                self.qtable.free(qd);
+                match unistd::close(fd) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(Fail::new(e as i32, "failed to close socket")),
+                }
             },
-            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+            None => Err(Fail::new(EBADF, "invalid queue descriptor")),
         }
     }
 
     // Handles a push operation.
     fn do_push(&mut self, qd: QDesc, buf: Buffer) -> Result<QToken, Fail> {
-        match self.sockets.get(&qd) {
-            Some(&fd) => {
-                let future: Operation = Operation::from(PushFuture::new(qd, fd, buf));
+        match (self.sockets.get(&qd), self.send_queues.get(&qd), self.closed.get(&qd)) {
+            (Some(&fd), Some(queue), Some(closed)) => {
+                // Push operations are queued in issue order, so that they hit the wire in that same order.
+                let future: Operation = Operation::from(PushFuture::new(qd, fd, buf, queue.clone(), closed.clone()));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -285,12 +334,25 @@ impl CatnapLibOS {
         self.do_push(qd, buf)
     }
 
+    /// Pushes data that is scattered across several slices to a socket. The slices are gathered into a single buffer.
+    pub fn push_slices(&mut self, qd: QDesc, slices: &[&[u8]]) -> Result<QToken, Fail> {
+        trace!("push_slices() qd={:?}, nslices={:?}", qd, slices.len());
+
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slices(slices));
+        if buf.len() == 0 {
+            return Err(Fail::new(EINVAL, "zero-length buffer"));
+        }
+
+        // Issue push operation.
+        self.do_push(qd, buf)
+    }
+
     /// Handles a pushto operation.
     fn do_pushto(&mut self, qd: QDesc, buf: Buffer, remote: SocketAddrV4) -> Result<QToken, Fail> {
-        match self.sockets.get(&qd) {
-            Some(&fd) => {
+        match (self.sockets.get(&qd), self.closed.get(&qd)) {
+            (Some(&fd), Some(closed)) => {
                 let addr: SockaddrStorage = parse_addr(remote);
-                let future: Operation = Operation::from(PushtoFuture::new(qd, fd, addr, buf));
+                let future: Operation = Operation::from(PushtoFuture::new(qd, fd, addr, buf, closed.clone()));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -336,9 +398,28 @@ impl CatnapLibOS {
         trace!("pop() qd={:?}", qd);
 
         // Issue pop operation.
-        match self.sockets.get(&qd) {
-            Some(&fd) => {
-                let future: Operation = Operation::from(PopFuture::new(qd, fd));
+        match (self.sockets.get(&qd), self.closed.get(&qd)) {
+            (Some(&fd), Some(closed)) => {
+                let future: Operation = Operation::from(PopFuture::new(qd, fd, false, closed.clone()));
+                let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
+                    Some(handle) => handle,
+                    None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+                };
+                let qt: QToken = handle.into_raw().into();
+                Ok(qt)
+            },
+            _ => Err(Fail::new(EBADF, "invalid queue descriptor")),
+        }
+    }
+
+    /// Peeks data from a socket, without consuming it.
+    pub fn peek(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        trace!("peek() qd={:?}", qd);
+
+        // Issue peek operation.
+        match (self.sockets.get(&qd), self.closed.get(&qd)) {
+            (Some(&fd), Some(closed)) => {
+                let future: Operation = Operation::from(PopFuture::new(qd, fd, true, closed.clone()));
                 let handle: SchedulerHandle = match self.runtime.scheduler.insert(future) {
                     Some(handle) => handle,
                     None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
@@ -381,17 +462,49 @@ impl CatnapLibOS {
                 break self.take_result(handle);
             }
 
-            if abstime.is_none() || SystemTime::now() >= abstime.unwrap() {
-                // Return this operation to the scheduling queue by removing the associated key
+            match abstime {
+                // No timeout was set, so return this operation to the scheduling queue by removing the associated key
                 // (which would otherwise cause the operation to be freed).
-                handle.take_key();
-                return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                None => {
+                    handle.take_key();
+                    return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                },
+                // The deadline has passed, so abort the operation.
+                Some(abstime) if SystemTime::now() >= abstime => {
+                    self.cancel_operation(handle);
+                    return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                },
+                Some(_) => (),
             }
         };
 
         Ok(pack_result(&self.runtime, result, qd, qt.into()))
     }
 
+    /// Cancels a pending operation. The queue token is invalidated and the resources held by the operation are
+    /// released. Operations that have already completed are left untouched, so that their result can be waited on.
+    pub fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnap::cancel");
+        trace!("cancel() qt={:?}", qt);
+
+        // Retrieve associated schedule handle.
+        let mut handle: SchedulerHandle = match self.runtime.scheduler.from_raw_handle(qt.into()) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EINVAL, "invalid queue token")),
+        };
+
+        if handle.has_completed() {
+            // Return this operation to the scheduling queue by removing the associated key
+            // (which would otherwise cause the operation to be freed).
+            handle.take_key();
+            return Err(Fail::new(libc::EALREADY, "operation has already completed"));
+        }
+
+        self.cancel_operation(handle);
+        Ok(())
+    }
+
     /// Waits for an operation to complete.
     pub fn wait2(&mut self, qt: QToken) -> Result<(QDesc, OperationResult), Fail> {
         #[cfg(feature = "profiler")]
@@ -425,6 +538,24 @@ impl CatnapLibOS {
         Ok((i, pack_result(&self.runtime, r, qd, qts[i].into())))
     }
 
+    /// Waits for up to `max_results` operations to complete.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnap::wait_many");
+        trace!("wait_many(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let results: Vec<(usize, QDesc, OperationResult)> = self.wait_many2(qts, max_results, abstime)?;
+        Ok(results
+            .into_iter()
+            .map(|(i, qd, r)| (i, pack_result(&self.runtime, r, qd, qts[i].into())))
+            .collect())
+    }
+
     /// Waits for any operation to complete.
     pub fn wait_any2(&mut self, qts: &[QToken]) -> Result<(usize, QDesc, OperationResult), Fail> {
         #[cfg(feature = "profiler")]
@@ -456,6 +587,31 @@ impl CatnapLibOS {
         }
     }
 
+    /// Waits for up to `max_results` operations to complete and returns all of them at once, along with their offset
+    /// in `qts`. If `abstime` is set and no operation completes before it, ETIMEDOUT is returned. If `qts` is empty,
+    /// `abstime` must be set, or EINVAL is returned. Operations that are still pending are left untouched.
+    pub fn wait_many2(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, QDesc, OperationResult)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnap::wait_many2");
+        trace!("wait_many2(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let scheduler: Scheduler = self.runtime.scheduler.clone();
+        let results: Vec<(usize, (QDesc, OperationResult))> = scheduler.wait_many(
+            self,
+            qts,
+            max_results,
+            abstime,
+            |libos: &mut Self| libos.runtime.scheduler.poll(),
+            Self::take_result,
+        )?;
+        Ok(results.into_iter().map(|(i, (qd, r))| (i, qd, r)).collect())
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         trace!("sgalloc() size={:?}", size);
@@ -491,6 +647,8 @@ impl CatnapLibOS {
             // Associate raw file descriptor with queue descriptor.
             if let Some(new_fd) = new_fd {
                 assert!(self.sockets.insert(new_qd, new_fd).is_none());
+                assert!(self.send_queues.insert(new_qd, SendQueue::new()).is_none());
+                assert!(self.closed.insert(new_qd, Rc::new(Cell::new(false))).is_none());
             } else {
                 // Release entry in queue table.
                 self.qtable.free(new_qd);
@@ -499,6 +657,17 @@ impl CatnapLibOS {
 
         (qd, qr)
     }
+
+    /// Removes a pending operation from the scheduler and releases the resources that it holds.
+    fn cancel_operation(&mut self, handle: SchedulerHandle) {
+        let boxed_future: Box<dyn Any> = self.runtime.scheduler.take(handle).as_any();
+        let boxed_concrete_type: Operation = *boxed_future.downcast::<Operation>().expect("Wrong type!");
+
+        // Release entry in queue table reserved by an accept operation.
+        if let Some(new_qd) = boxed_concrete_type.get_new_qd() {
+            self.qtable.free(new_qd);
+        }
+    }
 }
 
 //==============================================================================
@@ -588,3 +757,98 @@ fn pack_result(rt: &PosixRuntime, result: OperationResult, qd: QDesc, qt: u64) -
         },
     }
 }
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::CatnapLibOS;
+    use crate::{
+        demikernel::config::Config,
+        inetstack::operations::OperationResult,
+        runtime::{
+            QDesc,
+            QToken,
+        },
+    };
+    use ::std::net::{
+        Ipv4Addr,
+        SocketAddrV4,
+    };
+    use ::yaml_rust::Yaml;
+
+    /// Waits for an operation and checks that it was cancelled.
+    fn expect_cancelled(libos: &mut CatnapLibOS, qt: QToken) {
+        match libos.wait2(qt).unwrap() {
+            (_, OperationResult::Failed(e)) => assert_eq!(e.errno, libc::ECANCELED),
+            _ => panic!("operation should have been cancelled"),
+        }
+    }
+
+    /// Creates a TCP socket that listens on the loopback address.
+    fn listen(libos: &mut CatnapLibOS, port: u16) -> QDesc {
+        let local: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
+        let qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0).unwrap();
+        libos.bind(qd, local).unwrap();
+        libos.listen(qd, 8).unwrap();
+        qd
+    }
+
+    #[test]
+    fn close_cancels_accept() {
+        let mut libos: CatnapLibOS = CatnapLibOS::new(&Config(Yaml::Null));
+        let qd: QDesc = listen(&mut libos, 24301);
+
+        // No connection is coming in, so the accept operation is still pending when the socket is closed.
+        let qt: QToken = libos.accept(qd).unwrap();
+        libos.runtime.scheduler.poll();
+        libos.close(qd).unwrap();
+        expect_cancelled(&mut libos, qt);
+    }
+
+    #[test]
+    fn close_cancels_connect() {
+        let mut libos: CatnapLibOS = CatnapLibOS::new(&Config(Yaml::Null));
+        let listen_qd: QDesc = listen(&mut libos, 24302);
+
+        let qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0).unwrap();
+        let qt: QToken = libos
+            .connect(qd, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 24302))
+            .unwrap();
+        libos.close(qd).unwrap();
+        expect_cancelled(&mut libos, qt);
+        libos.close(listen_qd).unwrap();
+    }
+
+    #[test]
+    fn close_cancels_pop() {
+        let mut libos: CatnapLibOS = CatnapLibOS::new(&Config(Yaml::Null));
+        let listen_qd: QDesc = listen(&mut libos, 24303);
+
+        // Establish a connection.
+        let accept_qt: QToken = libos.accept(listen_qd).unwrap();
+        let qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0).unwrap();
+        let connect_qt: QToken = libos
+            .connect(qd, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 24303))
+            .unwrap();
+        let server_qd: QDesc = match libos.wait2(accept_qt).unwrap() {
+            (_, OperationResult::Accept(server_qd)) => server_qd,
+            _ => panic!("accept should have succeeded"),
+        };
+        match libos.wait2(connect_qt).unwrap() {
+            (_, OperationResult::Connect) => (),
+            _ => panic!("connect should have succeeded"),
+        }
+
+        // Nothing is sent, so the pop operation is still pending when the socket is closed.
+        let qt: QToken = libos.pop(qd).unwrap();
+        libos.runtime.scheduler.poll();
+        libos.close(qd).unwrap();
+        expect_cancelled(&mut libos, qt);
+
+        libos.close(server_qd).unwrap();
+        libos.close(listen_qd).unwrap();
+    }
+}
@@ -5,18 +5,63 @@
 // Imports
 //======================================================================================================================
 
+use super::runtime::memory::{
+    consts::{
+        DEFAULT_CACHE_SIZE,
+        DEFAULT_CONTROL_POOL_SIZE,
+        DEFAULT_INLINE_BODY_SIZE,
+    },
+    MemoryConfig,
+};
 use crate::{
     demikernel::config::Config,
-    runtime::network::types::MacAddress,
+    runtime::{
+        fail::Fail,
+        network::{
+            config::{
+                Route,
+                RoutingTable,
+            },
+            consts::{
+                DEFAULT_MSS,
+                DEFAULT_RECEIVE_BATCH_SIZE,
+                MAX_MSS,
+                MAX_TRANSMIT_BATCH_SIZE,
+                MIN_MSS,
+                RECEIVE_BATCH_SIZE,
+            },
+            types::MacAddress,
+        },
+    },
 };
-use ::anyhow::Error;
 use ::std::{
     collections::HashMap,
+    env,
     ffi::CString,
+    fs,
     net::Ipv4Addr,
+    path::Path,
+    str::FromStr,
+    time::Duration,
 };
 use ::yaml_rust::Yaml;
 
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Directory where the kernel lists network interfaces.
+const SYSFS_NET_DIR: &str = "/sys/class/net";
+
+/// Default MTU.
+const DEFAULT_MTU: u16 = 1500;
+
+/// Maximum MTU when jumbo frames are enabled.
+const MAX_JUMBO_MTU: u16 = 9000;
+
+/// Size of IPv4 and TCP headers without options (in bytes).
+const IPV4_TCP_HEADERS_SIZE: usize = 40;
+
 //======================================================================================================================
 // Associated Functions
 //======================================================================================================================
@@ -46,22 +91,91 @@ impl Config {
         arp_table
     }
 
-    /// Reads the "DPDK EAL" parameter from the underlying configuration file.
-    pub fn eal_init_args(&self) -> Vec<CString> {
-        // FIXME: this function should return a Result.
+    /// Reads the "routes" parameter from the underlying configuration file. It maps destination prefixes, in CIDR
+    /// notation, to gateway addresses.
+    pub fn routing_table(&self) -> Result<RoutingTable, Fail> {
+        let mut routes: Vec<Route> = Vec::new();
+        if let Some(routes_obj) = self.0["catnip"]["routes"].as_hash() {
+            for (k, v) in routes_obj {
+                match (k.as_str(), v.as_str()) {
+                    (Some(destination), Some(gateway)) => routes.push(Route::parse(destination, gateway)?),
+                    _ => return Err(Fail::new(libc::EINVAL, "invalid route in config")),
+                }
+            }
+        }
+        Ok(RoutingTable::new(routes))
+    }
 
-        match self.0["dpdk"]["eal_init"] {
+    /// Reads the "DPDK EAL" parameter from the underlying configuration file.
+    pub fn eal_init_args(&self) -> Result<Vec<CString>, Fail> {
+        let mut args: Vec<CString> = match self.0["dpdk"]["eal_init"] {
             Yaml::Array(ref arr) => arr
                 .iter()
-                .map(|a| {
-                    a.as_str()
-                        .ok_or_else(|| anyhow::format_err!("Non string argument"))
-                        .and_then(|s| CString::new(s).map_err(|e| e.into()))
+                .map(|a| match a.as_str().map(CString::new) {
+                    Some(Ok(arg)) => Ok(arg),
+                    _ => Err(Fail::new(libc::EINVAL, "invalid eal_init argument in config")),
                 })
-                .collect::<Result<Vec<_>, Error>>()
-                .unwrap(),
-            _ => panic!("Malformed YAML config"),
+                .collect::<Result<Vec<CString>, Fail>>()?,
+            _ => return Err(Fail::new(libc::EINVAL, "malformed eal_init in config")),
+        };
+
+        // Processes that share a NIC need their own hugepage files.
+        if let Some(file_prefix) = self.file_prefix() {
+            args.push(CString::new(format!("--file-prefix={}", file_prefix)).unwrap());
+        }
+
+        // Restrict this process to the selected device (e.g. an SR-IOV virtual function), unless the EAL arguments
+        // already carry a device allow-list: DPDK rejects mixing "-a" with the legacy "-w" option.
+        let has_allow_list: bool = args.iter().any(|arg| {
+            let arg: &[u8] = arg.as_bytes();
+            arg.starts_with(b"-a")
+                || arg.starts_with(b"-w")
+                || arg.starts_with(b"--allow")
+                || arg.starts_with(b"--pci-whitelist")
+        });
+        if has_allow_list {
+            return Ok(args);
+        }
+        if let Some(pci_addr) = self.pci_addr()? {
+            args.push(CString::new("-a").unwrap());
+            match CString::new(pci_addr) {
+                Ok(pci_addr) => args.push(pci_addr),
+                Err(_) => return Err(Fail::new(libc::EINVAL, "invalid pci_addr in config")),
+            }
+        }
+
+        Ok(args)
+    }
+
+    /// Reads the "PCI address" parameter from the underlying configuration file. If it is not set, the PCI address is
+    /// looked up from the "interface name" or the "link address" parameters, if any of these are set.
+    pub fn pci_addr(&self) -> Result<Option<String>, Fail> {
+        if let Some(pci_addr) = self.0["dpdk"]["pci_addr"].as_str() {
+            return Ok(Some(pci_addr.to_string()));
+        }
+
+        let interface_name: Option<&str> = self.0["dpdk"]["interface_name"].as_str();
+        let link_addr: Option<MacAddress> = match self.0["dpdk"]["link_addr"].as_str() {
+            Some(s) => match MacAddress::parse_str(s) {
+                Ok(link_addr) => Some(link_addr),
+                Err(_) => return Err(Fail::new(libc::EINVAL, "invalid link_addr in config")),
+            },
+            None => None,
+        };
+        if interface_name.is_none() && link_addr.is_none() {
+            return Ok(None);
         }
+
+        Ok(Some(resolve_pci_addr(
+            Path::new(SYSFS_NET_DIR),
+            interface_name,
+            link_addr,
+        )?))
+    }
+
+    /// Reads the "hugepage file prefix" parameter from the underlying configuration file.
+    pub fn file_prefix(&self) -> Option<String> {
+        self.0["dpdk"]["file_prefix"].as_str().map(|s| s.to_string())
     }
 
     /// Reads the "ARP Disable" parameter from the underlying configuration file.
@@ -75,30 +189,361 @@ impl Config {
         disable_arp
     }
 
-    /// Gets the "MTU" parameter from environment variables.
-    pub fn mtu(&self) -> u16 {
-        // FIXME: this function should return a Result.
-        ::std::env::var("MTU").unwrap().parse().unwrap()
+    /// Reads the "static ARP" parameter from the underlying configuration file. In this mode, link addresses are only
+    /// resolved from the ARP table and no ARP requests are ever sent.
+    pub fn static_arp(&self) -> bool {
+        self.0["catnip"]["static_arp"].as_bool().unwrap_or(false)
     }
 
-    /// Gets the "MSS" parameter from environment variables.
-    pub fn mss(&self) -> usize {
-        // FIXME: this function should return a Result.
-        ::std::env::var("MSS").unwrap().parse().unwrap()
+    /// Reads the "MTU" parameter from the underlying configuration file. The "MTU" environment variable, if set,
+    /// takes precedence.
+    pub fn mtu(&self) -> Result<u16, Fail> {
+        self.read_number("MTU", &self.0["catnip"]["mtu"], DEFAULT_MTU)
     }
 
-    /// Gets the "TCP_CHECKSUM_OFFLOAD" parameter from environment variables.
+    /// Reads the "MSS" parameter from the underlying configuration file. The "MSS" environment variable, if set,
+    /// takes precedence.
+    pub fn mss(&self) -> Result<usize, Fail> {
+        self.read_number("MSS", &self.0["catnip"]["mss"], DEFAULT_MSS)
+    }
+
+    /// Reads the "TCP checksum offload" parameter from the underlying configuration file. Setting the
+    /// "TCP_CHECKSUM_OFFLOAD" environment variable turns it on.
     pub fn tcp_checksum_offload(&self) -> bool {
-        ::std::env::var("TCP_CHECKSUM_OFFLOAD").is_ok()
+        self.read_flag("TCP_CHECKSUM_OFFLOAD", &self.0["catnip"]["tcp_checksum_offload"])
     }
 
-    /// Gets the "UDP_CHECKSUM_OFFLOAD" parameter from environment variables.
+    /// Reads the "UDP checksum offload" parameter from the underlying configuration file. Setting the
+    /// "UDP_CHECKSUM_OFFLOAD" environment variable turns it on.
     pub fn udp_checksum_offload(&self) -> bool {
-        ::std::env::var("UDP_CHECKSUM_OFFLOAD").is_ok()
+        self.read_flag("UDP_CHECKSUM_OFFLOAD", &self.0["catnip"]["udp_checksum_offload"])
     }
 
-    /// Gets the "USE_JUMBO" parameter from environment variables.
+    /// Reads the "jumbo frames" parameter from the underlying configuration file. Setting the "USE_JUMBO" environment
+    /// variable turns it on.
     pub fn use_jumbo_frames(&self) -> bool {
-        ::std::env::var("USE_JUMBO").is_ok()
+        self.read_flag("USE_JUMBO", &self.0["catnip"]["use_jumbo_frames"])
+    }
+
+    /// Reads and validates the MTU, MSS, and jumbo frames parameters.
+    pub fn link_params(&self) -> Result<(u16, usize, bool), Fail> {
+        let mtu: u16 = self.mtu()?;
+        let mss: usize = self.mss()?;
+        let use_jumbo_frames: bool = self.use_jumbo_frames();
+        validate_link_params(mtu, mss, use_jumbo_frames)?;
+        Ok((mtu, mss, use_jumbo_frames))
+    }
+
+    /// Reads the memory pool parameters from the underlying configuration file and validates them. Each of them may be
+    /// overridden by the environment variable of the same name in upper case. If "autosize_pools" is set, the header
+    /// and body pools that are not sized explicitly are sized from the link parameters once the device is known.
+    pub fn memory_config(&self, max_body_size: usize) -> Result<MemoryConfig, Fail> {
+        let inline_body_size: usize = self.read_number(
+            "INLINE_BODY_SIZE",
+            &self.0["catnip"]["inline_body_size"],
+            DEFAULT_INLINE_BODY_SIZE,
+        )?;
+        let header_pool_size: Option<usize> =
+            self.read_optional_number("HEADER_POOL_SIZE", &self.0["catnip"]["header_pool_size"])?;
+        let body_pool_size: Option<usize> =
+            self.read_optional_number("BODY_POOL_SIZE", &self.0["catnip"]["body_pool_size"])?;
+        let cache_size: usize = self.read_number(
+            "MEMPOOL_CACHE_SIZE",
+            &self.0["catnip"]["mempool_cache_size"],
+            DEFAULT_CACHE_SIZE,
+        )?;
+        let control_pool_size: usize = self.read_number(
+            "CONTROL_POOL_SIZE",
+            &self.0["catnip"]["control_pool_size"],
+            DEFAULT_CONTROL_POOL_SIZE,
+        )?;
+        let memory_config: MemoryConfig = MemoryConfig::new(
+            Some(inline_body_size),
+            header_pool_size,
+            Some(max_body_size),
+            body_pool_size,
+            Some(cache_size),
+            Some(control_pool_size),
+            Some(self.read_flag("AUTOSIZE_POOLS", &self.0["catnip"]["autosize_pools"])),
+        );
+        memory_config.validate()?;
+        Ok(memory_config)
+    }
+
+    /// Reads the "receive batch size" parameter from the underlying configuration file. The "RX_BATCH_SIZE"
+    /// environment variable, if set, takes precedence.
+    pub fn rx_batch_size(&self) -> Result<usize, Fail> {
+        let rx_batch_size: usize = self.read_number(
+            "RX_BATCH_SIZE",
+            &self.0["catnip"]["rx_batch_size"],
+            DEFAULT_RECEIVE_BATCH_SIZE,
+        )?;
+        if rx_batch_size == 0 || rx_batch_size > RECEIVE_BATCH_SIZE {
+            let cause: String = format!(
+                "receive batch size {} is out of range [1, {}]",
+                rx_batch_size, RECEIVE_BATCH_SIZE
+            );
+            return Err(Fail::new(libc::EINVAL, &cause));
+        }
+        Ok(rx_batch_size)
+    }
+
+    /// Reads the "allocation retries" parameter from the underlying configuration file. This is how many times buffer
+    /// allocations are retried, after reclaiming buffers of completed transmissions, before failing with `ENOMEM`. The
+    /// "ALLOC_RETRIES" environment variable, if set, takes precedence.
+    pub fn alloc_retries(&self) -> Result<usize, Fail> {
+        self.read_number("ALLOC_RETRIES", &self.0["catnip"]["alloc_retries"], 0)
+    }
+
+    /// Reads the transmit batching parameters from the underlying configuration file. Outgoing packets are held back
+    /// until "tx_batch_size" of them are pending or the oldest one has waited for "tx_batch_delay_us" microseconds,
+    /// whichever comes first. The delay is only checked when the application polls, so a partial batch may wait longer
+    /// if the application stops calling into Demikernel. The "TX_BATCH_SIZE" and "TX_BATCH_DELAY_US" environment
+    /// variables, if set, take precedence. By default, packets are sent right away.
+    pub fn tx_batch_params(&self) -> Result<(usize, Duration), Fail> {
+        let tx_batch_size: usize = self.read_number("TX_BATCH_SIZE", &self.0["catnip"]["tx_batch_size"], 1)?;
+        if tx_batch_size == 0 || tx_batch_size > MAX_TRANSMIT_BATCH_SIZE {
+            let cause: String = format!(
+                "transmit batch size {} is out of range [1, {}]",
+                tx_batch_size, MAX_TRANSMIT_BATCH_SIZE
+            );
+            return Err(Fail::new(libc::EINVAL, &cause));
+        }
+        let tx_batch_delay_us: u64 =
+            self.read_number("TX_BATCH_DELAY_US", &self.0["catnip"]["tx_batch_delay_us"], 0)?;
+        Ok((tx_batch_size, Duration::from_micros(tx_batch_delay_us)))
+    }
+
+    /// Reads a numeric parameter from the environment variable `var` or, if that is not set, from `yaml`.
+    fn read_number<T: FromStr + TryFrom<i64>>(&self, var: &str, yaml: &Yaml, default: T) -> Result<T, Fail> {
+        Ok(self.read_optional_number(var, yaml)?.unwrap_or(default))
+    }
+
+    /// Reads a numeric parameter like [Config::read_number], but returns `None` if it is not set at all.
+    fn read_optional_number<T: FromStr + TryFrom<i64>>(&self, var: &str, yaml: &Yaml) -> Result<Option<T>, Fail> {
+        if let Ok(value) = env::var(var) {
+            return match value.parse::<T>() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => Err(Fail::new(
+                    libc::EINVAL,
+                    &format!("invalid value for {} environment variable ({:?})", var, value),
+                )),
+            };
+        }
+        match yaml {
+            Yaml::BadValue => Ok(None),
+            Yaml::Integer(value) => match T::try_from(*value) {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => Err(Fail::new(
+                    libc::EINVAL,
+                    &format!("out of range value for {} in config ({})", var, value),
+                )),
+            },
+            _ => Err(Fail::new(
+                libc::EINVAL,
+                &format!("non-integer value for {} in config", var),
+            )),
+        }
+    }
+
+    /// Reads a boolean parameter from `yaml`, unless the environment variable `var` is set.
+    fn read_flag(&self, var: &str, yaml: &Yaml) -> bool {
+        env::var(var).is_ok() || yaml.as_bool().unwrap_or(false)
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Checks that MTU and MSS are consistent with each other and with the jumbo frames setting.
+fn validate_link_params(mtu: u16, mss: usize, use_jumbo_frames: bool) -> Result<(), Fail> {
+    let max_mtu: u16 = if use_jumbo_frames { MAX_JUMBO_MTU } else { DEFAULT_MTU };
+    if mtu > max_mtu {
+        let cause: String = format!(
+            "MTU {} is too large (maximum is {} with jumbo frames {})",
+            mtu,
+            max_mtu,
+            if use_jumbo_frames { "enabled" } else { "disabled" }
+        );
+        return Err(Fail::new(libc::EINVAL, &cause));
+    }
+    if mss < MIN_MSS || mss > MAX_MSS {
+        let cause: String = format!("MSS {} is out of range [{}, {}]", mss, MIN_MSS, MAX_MSS);
+        return Err(Fail::new(libc::EINVAL, &cause));
+    }
+    if mss + IPV4_TCP_HEADERS_SIZE > mtu as usize {
+        let cause: String = format!(
+            "MSS {} does not fit in MTU {} (at most {} bytes)",
+            mss,
+            mtu,
+            (mtu as usize).saturating_sub(IPV4_TCP_HEADERS_SIZE)
+        );
+        return Err(Fail::new(libc::EINVAL, &cause));
+    }
+    Ok(())
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Looks up the PCI address of the network interface that matches `interface_name` or `link_addr`, by scanning the
+/// sysfs directory `net_dir`. On failure, the error lists all candidate devices.
+fn resolve_pci_addr(
+    net_dir: &Path,
+    interface_name: Option<&str>,
+    link_addr: Option<MacAddress>,
+) -> Result<String, Fail> {
+    let mut candidates: Vec<String> = Vec::new();
+
+    let entries: fs::ReadDir = match fs::read_dir(net_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Err(Fail::new(libc::ENODEV, "cannot list network interfaces")),
+    };
+    for entry in entries.flatten() {
+        let name: String = entry.file_name().to_string_lossy().into_owned();
+
+        // Skip virtual interfaces, which are not backed by a PCI device.
+        let pci_addr: String = match fs::read_link(entry.path().join("device")) {
+            Ok(target) => match target.file_name() {
+                Some(pci_addr) => pci_addr.to_string_lossy().into_owned(),
+                None => continue,
+            },
+            Err(_) => continue,
+        };
+        let mac: Option<MacAddress> = fs::read_to_string(entry.path().join("address"))
+            .ok()
+            .and_then(|s| MacAddress::parse_str(s.trim()).ok());
+
+        let name_matches: bool = interface_name.map_or(true, |n| n == name);
+        let mac_matches: bool = link_addr.map_or(true, |m| Some(m) == mac);
+        if name_matches && mac_matches {
+            return Ok(pci_addr);
+        }
+
+        let mac: String = mac.map_or("??".to_string(), |m| m.to_string());
+        candidates.push(format!("{} ({}, {})", name, pci_addr, mac));
+    }
+
+    candidates.sort();
+    let cause: String = format!(
+        "no PCI device matches interface_name={:?} link_addr={:?} (candidates: [{}])",
+        interface_name,
+        link_addr,
+        candidates.join(", ")
+    );
+    Err(Fail::new(libc::ENODEV, &cause))
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        resolve_pci_addr,
+        validate_link_params,
+    };
+    use crate::{
+        demikernel::config::Config,
+        runtime::network::types::MacAddress,
+    };
+    use ::std::{
+        ffi::CString,
+        fs,
+        os::unix::fs::symlink,
+        path::PathBuf,
+    };
+    use ::yaml_rust::YamlLoader;
+
+    /// Builds a fake sysfs network directory.
+    fn fake_sysfs(name: &str) -> PathBuf {
+        let root: PathBuf = ::std::env::temp_dir().join(format!("demikernel-sysfs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (ifname, pci_addr, mac) in [
+            ("eth0", Some("0000:00:03.0"), "00:0d:3a:00:00:01"),
+            ("eth1", Some("0000:00:04.0"), "00:0d:3a:00:00:02"),
+            ("lo", None, "00:00:00:00:00:00"),
+        ] {
+            let dir: PathBuf = root.join(ifname);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("address"), format!("{}\n", mac)).unwrap();
+            if let Some(pci_addr) = pci_addr {
+                symlink(format!("../../../{}", pci_addr), dir.join("device")).unwrap();
+            }
+        }
+        root
+    }
+
+    #[test]
+    fn resolve_by_interface_name() {
+        let root: PathBuf = fake_sysfs("name");
+        assert_eq!(resolve_pci_addr(&root, Some("eth1"), None).unwrap(), "0000:00:04.0");
+        assert!(resolve_pci_addr(&root, Some("lo"), None).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_by_link_addr() {
+        let root: PathBuf = fake_sysfs("mac");
+        let mac: MacAddress = MacAddress::parse_str("00:0d:3a:00:00:01").unwrap();
+        assert_eq!(resolve_pci_addr(&root, None, Some(mac)).unwrap(), "0000:00:03.0");
+
+        // The error lists the candidates.
+        let mac: MacAddress = MacAddress::parse_str("00:0d:3a:00:00:09").unwrap();
+        let e = resolve_pci_addr(&root, None, Some(mac)).unwrap_err();
+        assert!(e.cause.contains("eth0 (0000:00:03.0"));
+        assert!(e.cause.contains("eth1 (0000:00:04.0"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn eal_init_args_allow_list() {
+        let config = |s: &str| Config(YamlLoader::load_from_str(s).unwrap().remove(0));
+
+        let args: Vec<CString> = config("dpdk:\n  eal_init: [\"-n\", \"4\"]\n  pci_addr: \"0000:00:04.0\"\n")
+            .eal_init_args()
+            .unwrap();
+        assert_eq!(
+            args,
+            ["-n", "4", "-a", "0000:00:04.0"].map(|s| CString::new(s).unwrap())
+        );
+
+        // An allow-list in eal_init takes precedence over pci_addr.
+        let args: Vec<CString> =
+            config("dpdk:\n  eal_init: [\"-w\", \"0000:00:03.0\"]\n  pci_addr: \"0000:00:04.0\"\n")
+                .eal_init_args()
+                .unwrap();
+        assert_eq!(args, ["-w", "0000:00:03.0"].map(|s| CString::new(s).unwrap()));
+
+        // Malformed entries are reported instead of panicking.
+        assert!(config("dpdk:\n  eal_init: [\"-n\"]\n  link_addr: \"nope\"\n")
+            .eal_init_args()
+            .is_err());
+        assert!(config("dpdk:\n  pci_addr: \"0000:00:04.0\"\n").eal_init_args().is_err());
+    }
+
+    #[test]
+    fn rx_batch_size() {
+        let config = |s: &str| Config(YamlLoader::load_from_str(s).unwrap().remove(0));
+        assert_eq!(config("catnip: {}\n").rx_batch_size().unwrap(), 4);
+        assert_eq!(config("catnip:\n  rx_batch_size: 32\n").rx_batch_size().unwrap(), 32);
+        assert!(config("catnip:\n  rx_batch_size: 33\n").rx_batch_size().is_err());
+        assert!(config("catnip:\n  rx_batch_size: 0\n").rx_batch_size().is_err());
+    }
+
+    #[test]
+    fn link_params() {
+        assert!(validate_link_params(1500, 1460, false).is_ok());
+        assert!(validate_link_params(9000, 8960, true).is_ok());
+
+        // Jumbo MTU without jumbo frames.
+        assert!(validate_link_params(9000, 1460, false).is_err());
+        // MSS larger than MTU allows.
+        assert!(validate_link_params(1500, 1461, false).is_err());
+        // MSS below minimum.
+        assert!(validate_link_params(1500, 100, false).is_err());
     }
 }
@@ -11,7 +11,10 @@ pub mod runtime;
 
 use self::{
     interop::pack_result,
-    runtime::DPDKRuntime,
+    runtime::{
+        memory::MemoryConfig,
+        DPDKRuntime,
+    },
 };
 use crate::{
     demikernel::config::Config,
@@ -22,7 +25,21 @@ use crate::{
     runtime::{
         fail::Fail,
         libdpdk::load_mlx_driver,
-        memory::MemoryRuntime,
+        memory::{
+            copy,
+            stats::{
+                MempoolId,
+                MempoolStats,
+            },
+            Buffer,
+            MemoryRuntime,
+        },
+        network::{
+            event::DeviceEvent,
+            preamble::RequestPreamble,
+            stats::TxStats,
+            topology::Topology,
+        },
         timer::{
             Timer,
             TimerRc,
@@ -40,6 +57,8 @@ use crate::{
     },
 };
 use ::std::{
+    collections::HashMap,
+    mem::ManuallyDrop,
     net::SocketAddrV4,
     ops::{
         Deref,
@@ -47,6 +66,7 @@ use ::std::{
     },
     rc::Rc,
     time::{
+        Duration,
         Instant,
         SystemTime,
     },
@@ -55,6 +75,13 @@ use ::std::{
 #[cfg(feature = "profiler")]
 use crate::timer;
 
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Maximum number of static payloads whose transmit buffers are cached.
+const MAX_STATIC_BUFFERS: usize = 64;
+
 //==============================================================================
 // Structures
 //==============================================================================
@@ -62,8 +89,12 @@ use crate::timer;
 /// Catnip LibOS
 pub struct CatnipLibOS {
     scheduler: Scheduler,
-    inetstack: InetStack,
+    /// Network stack. This is dropped before the DPDK runtime is shut down, so that the buffers that it holds are
+    /// released before checking for leaks.
+    inetstack: ManuallyDrop<InetStack>,
     rt: Rc<DPDKRuntime>,
+    /// Transmit buffers holding copies of static payloads, indexed by the address and length of the payload.
+    static_bufs: HashMap<(usize, usize), Buffer>,
 }
 
 //==============================================================================
@@ -72,19 +103,31 @@ pub struct CatnipLibOS {
 
 /// Associate Functions for Catnip LibOS
 impl CatnipLibOS {
-    pub fn new(config: &Config) -> Self {
+    pub fn new(config: &Config) -> Result<Self, Fail> {
         load_mlx_driver();
+        let (mtu, mss, use_jumbo_frames): (u16, usize, bool) = config.link_params()?;
+        let memory_config: MemoryConfig = config.memory_config(DPDKRuntime::max_body_size(use_jumbo_frames))?;
+        let rx_batch_size: usize = config.rx_batch_size()?;
+        let alloc_retries: usize = config.alloc_retries()?;
+        let (tx_batch_size, tx_batch_delay): (usize, Duration) = config.tx_batch_params()?;
         let rt: Rc<DPDKRuntime> = Rc::new(DPDKRuntime::new(
             config.local_ipv4_addr(),
-            &config.eal_init_args(),
+            &config.eal_init_args()?,
             config.arp_table(),
             config.disable_arp(),
-            config.use_jumbo_frames(),
-            config.mtu(),
-            config.mss(),
+            config.static_arp(),
+            config.routing_table()?,
+            use_jumbo_frames,
+            mtu,
+            mss,
             config.tcp_checksum_offload(),
             config.udp_checksum_offload(),
-        ));
+            memory_config,
+            rx_batch_size,
+            alloc_retries,
+            tx_batch_size,
+            tx_batch_delay,
+        )?);
         let now: Instant = Instant::now();
         let clock: TimerRc = TimerRc(Rc::new(Timer::new(now)));
         let scheduler: Scheduler = Scheduler::default();
@@ -101,11 +144,12 @@ impl CatnipLibOS {
             rt.arp_options.clone(),
         )
         .unwrap();
-        CatnipLibOS {
-            inetstack,
+        Ok(CatnipLibOS {
+            inetstack: ManuallyDrop::new(inetstack),
             scheduler,
             rt,
-        }
+            static_bufs: HashMap::new(),
+        })
     }
 
     /// Create a push request for Demikernel to asynchronously write data from `sga` to the
@@ -132,6 +176,134 @@ impl CatnipLibOS {
         }
     }
 
+    /// Pushes raw data to a TCP socket. The data is copied once into a DPDK-friendly buffer, optionally prefixed with
+    /// a request preamble.
+    pub fn push_slice(&mut self, qd: QDesc, preamble: Option<&RequestPreamble>, data: &[u8]) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnip::push_slice");
+        trace!("push_slice(): qd={:?}", qd);
+
+        let offset: usize = preamble.map_or(0, |p| p.size());
+        if offset + data.len() == 0 {
+            return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
+        }
+        let mut buf: Buffer = self.rt.alloc_buffer(offset + data.len())?;
+        if let Some(preamble) = preamble {
+            preamble.serialize(&mut buf[..offset]);
+        }
+        copy::copy(&mut buf[offset..], data);
+
+        let future = self.do_push(qd, buf)?;
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        Ok(qt)
+    }
+
+    /// Pushes data that is scattered across several slices to a TCP socket. The slices are gathered into a single
+    /// DPDK-friendly buffer, so that the TCP stack sends them as one contiguous stream of bytes.
+    pub fn push_slices(&mut self, qd: QDesc, slices: &[&[u8]]) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnip::push_slices");
+        trace!("push_slices(): qd={:?}, nslices={:?}", qd, slices.len());
+
+        let len: usize = slices.iter().map(|slice| slice.len()).sum();
+        if len == 0 {
+            return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
+        }
+        let mut buf: Buffer = self.rt.alloc_buffer(len)?;
+        let mut offset: usize = 0;
+        for slice in slices {
+            copy::copy(&mut buf[offset..(offset + slice.len())], slice);
+            offset += slice.len();
+        }
+
+        let future = self.do_push(qd, buf)?;
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        Ok(qt)
+    }
+
+    /// Pushes static data to a TCP socket. The data is copied into a DPDK-friendly buffer the first time it is pushed,
+    /// and later pushes of the same data send clones of that buffer, so that canned payloads are not copied over and
+    /// over again. Once the cache is full, data that is not in it is copied on every push.
+    pub fn push_static(&mut self, qd: QDesc, data: &'static [u8]) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnip::push_static");
+        trace!("push_static(): qd={:?}", qd);
+
+        if data.is_empty() {
+            return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
+        }
+        let key: (usize, usize) = (data.as_ptr() as usize, data.len());
+        let buf: Buffer = match self.static_bufs.get(&key) {
+            Some(buf) => buf.clone(),
+            None => {
+                let mut buf: Buffer = self.rt.alloc_buffer(data.len())?;
+                copy::copy(&mut buf[..], data);
+                if self.static_bufs.len() < MAX_STATIC_BUFFERS {
+                    self.static_bufs.insert(key, buf.clone());
+                }
+                buf
+            },
+        };
+
+        let future = self.do_push(qd, buf)?;
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        Ok(qt)
+    }
+
+    /// Pushes the same scatter-gather array to several TCP sockets. The payload is shared by all sockets: each of them
+    /// sends a clone of the same buffer, which only bumps its reference count, while headers and sequence numbers
+    /// are still handled by each connection. Pushes are issued in the order of `qds`. A failure to push to one socket
+    /// does not prevent pushes to the others, so one result is returned for each socket. If the buffer cannot be
+    /// cloned for a socket, its result is `ENOBUFS`.
+    pub fn push_broadcast(&mut self, qds: &[QDesc], sga: &demi_sgarray_t) -> Result<Vec<Result<QToken, Fail>>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnip::push_broadcast");
+        trace!("push_broadcast(): qds={:?}", qds);
+
+        let buf: Buffer = self.rt.clone_sgarray(sga)?;
+        if buf.len() == 0 {
+            return Err(Fail::new(libc::EINVAL, "zero-length buffer"));
+        }
+
+        let qts: Vec<Result<QToken, Fail>> = broadcast(
+            qds,
+            || buf.try_clone(),
+            |qd, buf| {
+                let future = self.do_push(qd, buf)?;
+                match self.scheduler.insert(future) {
+                    Some(handle) => Ok(handle.into_raw().into()),
+                    None => Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+                }
+            },
+        );
+        Ok(qts)
+    }
+
+    /// Pushes several scatter-gather arrays to a TCP socket with a single operation. The returned queue token completes
+    /// once all of them have been handed to the TCP stack, in order.
+    pub fn push_batch(&mut self, qd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnip::push_batch");
+        trace!("push_batch(): qd={:?}", qd);
+        let bufs: Vec<Buffer> = sgas
+            .iter()
+            .map(|sga| self.rt.clone_sgarray(sga))
+            .collect::<Result<Vec<Buffer>, Fail>>()?;
+        self.inetstack.push_batch(qd, bufs)
+    }
+
     pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
         #[cfg(feature = "profiler")]
         timer!("catnip::pushto");
@@ -183,6 +355,24 @@ impl CatnipLibOS {
         Ok((i, pack_result(self.rt.clone(), r, qd, qts[i].into())))
     }
 
+    /// Waits for up to `max_results` operations to complete.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catnip::wait_many");
+        trace!("wait_many(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let results: Vec<(usize, QDesc, OperationResult)> = self.wait_many2(qts, max_results, abstime)?;
+        Ok(results
+            .into_iter()
+            .map(|(i, qd, r)| (i, pack_result(self.rt.clone(), r, qd, qts[i].into())))
+            .collect())
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         self.rt.alloc_sgarray(size)
@@ -192,6 +382,50 @@ impl CatnipLibOS {
     pub fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
         self.rt.free_sgarray(sga)
     }
+
+    /// Returns the utilization of a memory pool.
+    pub fn pool_stats(&self, id: MempoolId) -> MempoolStats {
+        self.rt.pool_stats(id)
+    }
+
+    /// Returns how many payload bytes were sent without being copied, and how many were copied and why.
+    pub fn tx_stats(&self) -> TxStats {
+        self.rt.tx_stats()
+    }
+
+    /// Resets the transmit copy statistics.
+    pub fn reset_tx_stats(&self) {
+        self.rt.reset_tx_stats()
+    }
+
+    /// Returns a description of the datapath instantiated by the target LibOS.
+    pub fn topology(&self) -> Topology {
+        self.rt.topology()
+    }
+
+    /// Returns the device events that occurred since the last call.
+    pub fn poll_events(&self) -> Vec<DeviceEvent> {
+        self.rt.poll_events()
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Pushes a clone of the same buffer to each queue descriptor in `qds`, in order, and returns one result per queue
+/// descriptor. A failure to clone or to push the buffer only fails the result of the affected queue descriptor.
+fn broadcast<C: FnMut() -> Result<Buffer, Fail>, P: FnMut(QDesc, Buffer) -> Result<QToken, Fail>>(
+    qds: &[QDesc],
+    mut clone: C,
+    mut push: P,
+) -> Vec<Result<QToken, Fail>> {
+    qds.iter()
+        .map(|qd| -> Result<QToken, Fail> {
+            let buf: Buffer = clone()?;
+            push(*qd, buf)
+        })
+        .collect()
 }
 
 //==============================================================================
@@ -213,3 +447,65 @@ impl DerefMut for CatnipLibOS {
         &mut self.inetstack
     }
 }
+
+/// Drop Trait Implementation for Catnip LibOS
+impl Drop for CatnipLibOS {
+    fn drop(&mut self) {
+        // Release all buffers held by the network stack, including those held by pending operations and background
+        // tasks, so that only buffers leaked by the application remain when checking for leaks.
+        self.static_bufs.clear();
+        unsafe { ManuallyDrop::drop(&mut self.inetstack) };
+        self.scheduler.clear();
+        self.rt.shutdown();
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::broadcast;
+    use crate::runtime::{
+        fail::Fail,
+        memory::{
+            Buffer,
+            DataBuffer,
+        },
+        QDesc,
+        QToken,
+    };
+
+    #[test]
+    fn broadcast_clone_failure() {
+        let qds: Vec<QDesc> = (0..3_i32).map(QDesc::from).collect();
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&[0x5a; 64]));
+
+        // The pool runs out of buffers on the second clone.
+        let mut nclones: usize = 0;
+        let mut pushed: Vec<QDesc> = Vec::new();
+        let qts: Vec<Result<QToken, Fail>> = broadcast(
+            &qds,
+            || {
+                nclones += 1;
+                match nclones {
+                    2 => Err(Fail::new(libc::ENOBUFS, "cannot clone mbuf")),
+                    _ => buf.try_clone(),
+                }
+            },
+            |qd, buf| {
+                assert_eq!(buf.len(), 64);
+                pushed.push(qd);
+                Ok(QToken::from(i32::from(qd) as u64))
+            },
+        );
+
+        // Only the affected socket fails, and the others are still pushed to.
+        assert_eq!(qts.len(), 3);
+        assert_eq!(*qts[0].as_ref().unwrap(), QToken::from(0));
+        assert_eq!(qts[1].as_ref().unwrap_err().errno, libc::ENOBUFS);
+        assert_eq!(*qts[2].as_ref().unwrap(), QToken::from(2));
+        assert_eq!(pushed, vec![qds[0], qds[2]]);
+    }
+}
@@ -6,12 +6,18 @@
 //==============================================================================
 
 use super::consts::{
+    AUTOSIZE_BUFFERING_TIME_US,
     DEFAULT_BODY_POOL_SIZE,
     DEFAULT_CACHE_SIZE,
+    DEFAULT_CONTROL_POOL_SIZE,
     DEFAULT_HEADER_POOL_SIZE,
     DEFAULT_INLINE_BODY_SIZE,
     DEFAULT_MAX_BODY_SIZE,
+    ETHERNET_FRAME_OVERHEAD,
+    MAX_AUTOSIZED_POOL_SIZE,
+    MAX_CACHE_SIZE,
 };
+use crate::runtime::fail::Fail;
 
 //==============================================================================
 // Structures
@@ -36,6 +42,15 @@ pub struct MemoryConfig {
 
     /// How many buffers should remain within `rte_mempool`'s per-thread cache?
     cache_size: usize,
+
+    /// How many buffers are reserved for control packets, such as pure ACKs, that the network stack sends on its own?
+    control_pool_size: usize,
+
+    /// Should the header pool be sized from the link parameters once the device is known?
+    autosize_header_pool: bool,
+
+    /// Should the body pool be sized from the link parameters once the device is known?
+    autosize_body_pool: bool,
 }
 
 //==============================================================================
@@ -50,6 +65,8 @@ impl MemoryConfig {
         max_body_size: Option<usize>,
         body_pool_size: Option<usize>,
         cache_size: Option<usize>,
+        control_pool_size: Option<usize>,
+        autosize_pools: Option<bool>,
     ) -> Self {
         let mut config: Self = Self::default();
 
@@ -78,6 +95,17 @@ impl MemoryConfig {
             config.cache_size = cache_size;
         }
 
+        // Sets the control pool size config option.
+        if let Some(control_pool_size) = control_pool_size {
+            config.control_pool_size = control_pool_size;
+        }
+
+        // Pools whose size is set explicitly are never sized automatically.
+        if autosize_pools == Some(true) {
+            config.autosize_header_pool = header_pool_size.is_none();
+            config.autosize_body_pool = body_pool_size.is_none();
+        }
+
         config
     }
 
@@ -105,6 +133,66 @@ impl MemoryConfig {
     pub fn get_cache_size(&self) -> usize {
         self.cache_size
     }
+
+    /// Returns the control pool size config stored in the target [MemoryConfig].
+    pub fn get_control_pool_size(&self) -> usize {
+        self.control_pool_size
+    }
+
+    /// Checks if any pool in the target [MemoryConfig] is to be sized automatically.
+    pub fn is_autosized(&self) -> bool {
+        self.autosize_header_pool || self.autosize_body_pool
+    }
+
+    /// Sizes the pools of the target [MemoryConfig] that were not set explicitly. Pools hold enough buffers to fill
+    /// `num_descriptors` device ring descriptors, plus the packets of MTU size that arrive within
+    /// [AUTOSIZE_BUFFERING_TIME_US] at `link_speed_mbps`. Sizes are rounded up to one less than a power of two, which is
+    /// optimal for `rte_mempool`, and never fall below the default sizes.
+    pub fn autosize(&mut self, link_speed_mbps: u32, mtu: u16, num_descriptors: usize) {
+        let frame_bits: u64 = (mtu as u64 + ETHERNET_FRAME_OVERHEAD) * 8;
+        let packets_per_sec: u64 = link_speed_mbps as u64 * 1_000_000 / frame_bits;
+        let buffered_packets: usize = (packets_per_sec * AUTOSIZE_BUFFERING_TIME_US / 1_000_000) as usize;
+        let pool_size: usize = ((num_descriptors + buffered_packets + 1).next_power_of_two() - 1)
+            .min(MAX_AUTOSIZED_POOL_SIZE)
+            .max(DEFAULT_HEADER_POOL_SIZE.min(DEFAULT_BODY_POOL_SIZE));
+
+        if self.autosize_header_pool {
+            self.header_pool_size = pool_size;
+        }
+        if self.autosize_body_pool {
+            self.body_pool_size = pool_size;
+        }
+    }
+
+    /// Checks the target [MemoryConfig] against the limits imposed by DPDK on memory pools.
+    pub fn validate(&self) -> Result<(), Fail> {
+        if self.inline_body_size > self.max_body_size {
+            let cause: String = format!(
+                "inline body size {} exceeds maximum body size {}",
+                self.inline_body_size, self.max_body_size
+            );
+            return Err(Fail::new(libc::EINVAL, &cause));
+        }
+        if self.cache_size > MAX_CACHE_SIZE {
+            let cause: String = format!("cache size {} exceeds maximum {}", self.cache_size, MAX_CACHE_SIZE);
+            return Err(Fail::new(libc::EINVAL, &cause));
+        }
+        // DPDK refuses to create a pool whose per-thread cache is larger than two thirds of the pool.
+        for (name, pool_size) in [("header", self.header_pool_size), ("body", self.body_pool_size)] {
+            if pool_size == 0 || self.cache_size * 3 > pool_size * 2 {
+                let cause: String = format!(
+                    "{} pool size {} is too small for cache size {}",
+                    name, pool_size, self.cache_size
+                );
+                return Err(Fail::new(libc::EINVAL, &cause));
+            }
+        }
+        // The control pool has no per-thread cache, so that all of its buffers remain available as a reserve.
+        if self.control_pool_size == 0 {
+            return Err(Fail::new(libc::EINVAL, "control pool size must be positive"));
+        }
+        Ok(())
+    }
 }
 
 //==============================================================================
@@ -120,6 +208,74 @@ impl Default for MemoryConfig {
             max_body_size: DEFAULT_MAX_BODY_SIZE,
             body_pool_size: DEFAULT_BODY_POOL_SIZE,
             cache_size: DEFAULT_CACHE_SIZE,
+            control_pool_size: DEFAULT_CONTROL_POOL_SIZE,
+            autosize_header_pool: false,
+            autosize_body_pool: false,
         }
     }
 }
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryConfig;
+
+    #[test]
+    fn validate_memory_config() {
+        assert!(MemoryConfig::default().validate().is_ok());
+        let config: MemoryConfig =
+            MemoryConfig::new(Some(512), Some(1023), Some(2048), Some(4095), Some(64), Some(63), None);
+        assert!(config.validate().is_ok());
+
+        // Inline body larger than the maximum body.
+        let config: MemoryConfig = MemoryConfig::new(Some(4096), None, Some(2048), None, None, None, None);
+        assert!(config.validate().is_err());
+        // Cache larger than DPDK supports.
+        let config: MemoryConfig = MemoryConfig::new(None, None, None, None, Some(1024), None, None);
+        assert!(config.validate().is_err());
+        // Pool too small for its cache.
+        let config: MemoryConfig = MemoryConfig::new(None, Some(256), None, None, Some(250), None, None);
+        assert!(config.validate().is_err());
+        let config: MemoryConfig = MemoryConfig::new(None, None, None, Some(0), Some(0), None, None);
+        assert!(config.validate().is_err());
+        // Empty control pool.
+        let config: MemoryConfig = MemoryConfig::new(None, None, None, None, None, Some(0), None);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn autosize_memory_config() {
+        // Pools are left alone unless requested.
+        let mut config: MemoryConfig = MemoryConfig::default();
+        config.autosize(100_000, 1500, 4096);
+        assert!(!config.is_autosized());
+        assert_eq!(
+            config.get_header_pool_size(),
+            MemoryConfig::default().get_header_pool_size()
+        );
+
+        // Slow links fall back to the default sizes.
+        let mut config: MemoryConfig = MemoryConfig::new(None, None, None, None, None, None, Some(true));
+        config.autosize(1_000, 1500, 4096);
+        assert_eq!(config.get_header_pool_size(), 8191);
+        assert_eq!(config.get_body_pool_size(), 8191);
+        assert!(config.validate().is_ok());
+
+        // Fast links get larger pools, unless frames are large.
+        config.autosize(100_000, 1500, 4096);
+        assert_eq!(config.get_body_pool_size(), 16383);
+        config.autosize(100_000, 9000, 4096);
+        assert_eq!(config.get_body_pool_size(), 8191);
+        config.autosize(u32::MAX, 1500, 4096);
+        assert_eq!(config.get_body_pool_size(), 256 * 1024 - 1);
+
+        // Explicit sizes take precedence.
+        let mut config: MemoryConfig = MemoryConfig::new(None, Some(1023), None, None, None, None, Some(true));
+        config.autosize(100_000, 1500, 4096);
+        assert_eq!(config.get_header_pool_size(), 1023);
+        assert_eq!(config.get_body_pool_size(), 16383);
+    }
+}
@@ -23,8 +23,25 @@ pub const DEFAULT_HEADER_POOL_SIZE: usize = 8192 - 1;
 /// Default number of buffers in the body pool.
 pub const DEFAULT_BODY_POOL_SIZE: usize = 8192 - 1;
 
+/// Default number of buffers in the control pool.
+pub const DEFAULT_CONTROL_POOL_SIZE: usize = 512 - 1;
+
 /// Default value for maximum body size.
 pub const DEFAULT_MAX_BODY_SIZE: usize = (RTE_MBUF_DEFAULT_BUF_SIZE + RTE_PKTMBUF_HEADROOM) as usize;
 
 /// Default per-thread cache size.
 pub const DEFAULT_CACHE_SIZE: usize = 250;
+
+/// Maximum per-thread cache size supported by DPDK (see `RTE_MEMPOOL_CACHE_MAX_SIZE`).
+pub const MAX_CACHE_SIZE: usize = 512;
+
+/// How long (in microseconds) pools that are sized automatically can buffer traffic arriving at line rate, on top of
+/// the buffers held by the device rings.
+pub const AUTOSIZE_BUFFERING_TIME_US: u64 = 1000;
+
+/// Upper bound on the number of buffers in a pool that is sized automatically.
+pub const MAX_AUTOSIZED_POOL_SIZE: usize = 256 * 1024 - 1;
+
+/// Bytes that each Ethernet frame occupies on the wire beyond its MTU-sized payload (header, FCS, preamble, and
+/// inter-frame gap).
+pub const ETHERNET_FRAME_OVERHEAD: u64 = 14 + 4 + 8 + 12;
@@ -19,10 +19,15 @@ use crate::{
             rte_mempool,
         },
         memory::{
+            stats::{
+                MempoolId,
+                MempoolStats,
+            },
             Buffer,
             DPDKBuffer,
             DataBuffer,
         },
+        network::topology::MempoolTopology,
         types::{
             demi_sgarray_t,
             demi_sgaseg_t,
@@ -60,6 +65,10 @@ pub struct Inner {
 
     // Large body pool for buffers given to the application for zero-copy.
     body_pool: Rc<MemoryPool>,
+
+    // Reserve of header buffers for control packets, used once the header pool runs dry. This keeps data-plane
+    // pressure on the header pool from stalling the TCP state machine.
+    control_pool: Rc<MemoryPool>,
 }
 
 /// Memory Manager
@@ -75,14 +84,18 @@ pub struct MemoryManager {
 /// Associated Functions for Memory Managers
 impl MemoryManager {
     /// Instantiates a memory manager.
-    pub fn new(max_body_size: usize) -> Result<Self, Error> {
-        let memory_config: MemoryConfig = MemoryConfig::new(None, None, Some(max_body_size), None, None);
-
+    pub fn new(memory_config: MemoryConfig) -> Result<Self, Error> {
+        memory_config.validate()?;
         Ok(Self {
             inner: Rc::new(Inner::new(memory_config)?),
         })
     }
 
+    /// Returns the configuration of the target memory manager.
+    pub fn config(&self) -> &MemoryConfig {
+        &self.inner.config
+    }
+
     /// Converts a runtime buffer into a scatter-gather array.
     pub fn into_sgarray(&self, buf: Buffer) -> Result<demi_sgarray_t, Fail> {
         let (mbuf_ptr, sgaseg): (*mut rte_mbuf, demi_sgaseg_t) = match buf {
@@ -98,7 +111,8 @@ impl MemoryManager {
                     },
                 )
             },
-            // DPDK-managed buffer.
+            // DPDK-managed buffer. The segment starts at the data offset of the mbuf, which the network stack moves
+            // past the headers that it strips from received frames.
             Buffer::DPDK(mbuf) => {
                 let mbuf_ptr: *mut rte_mbuf = mbuf.get_ptr();
                 let sgaseg: demi_sgaseg_t = demi_sgaseg_t {
@@ -126,6 +140,12 @@ impl MemoryManager {
         Ok(DPDKBuffer::new(mbuf_ptr))
     }
 
+    /// Allocates a header mbuf from the reserved control pool.
+    pub fn alloc_control_mbuf(&self) -> Result<DPDKBuffer, Fail> {
+        let mbuf_ptr: *mut rte_mbuf = self.inner.control_pool.alloc_mbuf(None)?;
+        Ok(DPDKBuffer::new(mbuf_ptr))
+    }
+
     /// Allocates a body mbuf.
     /// TODO: Review the need of this function after we are done with the refactor of the DPDK runtime.
     pub fn alloc_body_mbuf(&self) -> Result<DPDKBuffer, Fail> {
@@ -133,6 +153,20 @@ impl MemoryManager {
         Ok(DPDKBuffer::new(mbuf_ptr))
     }
 
+    /// Allocates a buffer. Small buffers are allocated on the heap, so that they are later inlined in the header mbuf,
+    /// while large buffers are allocated from the body pool, so that they are later sent without being copied.
+    pub fn alloc_buffer(&self, size: usize) -> Result<Buffer, Fail> {
+        if size > self.inner.config.get_max_body_size() {
+            return Err(Fail::new(libc::EINVAL, "buffer is too large"));
+        }
+        if size > self.inner.config.get_inline_body_size() {
+            let mbuf_ptr: *mut rte_mbuf = self.inner.body_pool.alloc_mbuf(Some(size))?;
+            Ok(Buffer::DPDK(DPDKBuffer::new(mbuf_ptr)))
+        } else {
+            Ok(Buffer::Heap(DataBuffer::new(size)?))
+        }
+    }
+
     /// Allocates a scatter-gather array.
     pub fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         // Allocate underlying buffer.
@@ -223,10 +257,7 @@ impl MemoryManager {
         let buf: Buffer = if !sga.sga_buf.is_null() {
             // Clone DPDK-managed buffer.
             let mbuf_ptr: *mut rte_mbuf = sga.sga_buf as *mut rte_mbuf;
-            let body_clone: *mut rte_mbuf = match MemoryPool::clone_mbuf(mbuf_ptr) {
-                Ok(mbuf_ptr) => mbuf_ptr,
-                Err(e) => panic!("failed to clone mbuf: {:?}", e.cause),
-            };
+            let body_clone: *mut rte_mbuf = MemoryPool::clone_mbuf(mbuf_ptr)?;
             Buffer::DPDK(DPDKBuffer::new(body_clone))
         } else {
             // Clone heap-managed buffer.
@@ -238,6 +269,50 @@ impl MemoryManager {
         Ok(buf)
     }
 
+    /// Returns the utilization of a memory pool.
+    pub fn pool_stats(&self, id: MempoolId) -> MempoolStats {
+        match id {
+            MempoolId::Header => self.inner.header_pool.stats(),
+            MempoolId::Body => self.inner.body_pool.stats(),
+            MempoolId::Control => self.inner.control_pool.stats(),
+        }
+    }
+
+    /// Describes the memory pools of the target memory manager.
+    pub fn pool_topology(&self) -> Vec<MempoolTopology> {
+        let config: &MemoryConfig = &self.inner.config;
+        let header_mbuf_size: usize = Inner::header_mbuf_size(config);
+        [
+            ("header_pool", config.get_header_pool_size(), header_mbuf_size),
+            ("body_pool", config.get_body_pool_size(), config.get_max_body_size()),
+            ("control_pool", config.get_control_pool_size(), header_mbuf_size),
+        ]
+        .iter()
+        .map(|(name, num_buffers, buffer_size)| MempoolTopology {
+            name: name.to_string(),
+            num_buffers: *num_buffers,
+            buffer_size: *buffer_size,
+        })
+        .collect()
+    }
+
+    /// Reports buffers that have not been returned to their memory pool. This is meant to be called on shutdown,
+    /// once the device has been stopped, and returns the total number of such buffers.
+    pub fn check_leaks(&self) -> usize {
+        let mut leaked: usize = 0;
+        for id in [MempoolId::Header, MempoolId::Body, MempoolId::Control] {
+            let stats: MempoolStats = self.pool_stats(id);
+            if stats.allocated > 0 {
+                warn!(
+                    "check_leaks(): {:?} pool has {} buffers that were never released ({} free)",
+                    id, stats.allocated, stats.free
+                );
+            }
+            leaked += stats.allocated;
+        }
+        leaked
+    }
+
     /// Returns a raw pointer to the underlying body pool.
     /// TODO: Review the need of this function after we are done with the refactor of the DPDK runtime.
     pub fn body_pool(&self) -> *mut rte_mempool {
@@ -248,9 +323,7 @@ impl MemoryManager {
 /// Associated Functions for Memory Managers
 impl Inner {
     fn new(config: MemoryConfig) -> Result<Self, Error> {
-        // TODO: The following computation for header size is bad. It should be fixed to maximum possible size.
-        let header_size: usize = ETHERNET2_HEADER_SIZE + IPV4_HEADER_DEFAULT_SIZE + MAX_TCP_HEADER_SIZE;
-        let header_mbuf_size: usize = header_size + config.get_inline_body_size();
+        let header_mbuf_size: usize = Self::header_mbuf_size(&config);
 
         // Create memory pool for holding packet headers.
         let header_pool: MemoryPool = MemoryPool::new(
@@ -268,10 +341,27 @@ impl Inner {
             config.get_cache_size(),
         )?;
 
+        // Create memory pool for holding headers of control packets. It has no per-thread cache, so that none of its
+        // buffers are held back.
+        let control_pool: MemoryPool = MemoryPool::new(
+            CString::new("control_pool")?,
+            header_mbuf_size,
+            config.get_control_pool_size(),
+            0,
+        )?;
+
         Ok(Self {
             config,
             header_pool: Rc::new(header_pool),
             body_pool: Rc::new(body_pool),
+            control_pool: Rc::new(control_pool),
         })
     }
+
+    /// Computes the size of buffers in the header and control pools.
+    fn header_mbuf_size(config: &MemoryConfig) -> usize {
+        // TODO: The following computation for header size is bad. It should be fixed to maximum possible size.
+        let header_size: usize = ETHERNET2_HEADER_SIZE + IPV4_HEADER_DEFAULT_SIZE + MAX_TCP_HEADER_SIZE;
+        header_size + config.get_inline_body_size()
+    }
 }
@@ -10,12 +10,18 @@ use crate::runtime::{
     libdpdk::{
         rte_mbuf,
         rte_mempool,
+        rte_mempool_avail_count,
+        rte_mempool_in_use_count,
         rte_pktmbuf_alloc,
         rte_pktmbuf_clone,
         rte_pktmbuf_free,
         rte_pktmbuf_pool_create,
         rte_socket_id,
     },
+    memory::{
+        poison,
+        stats::MempoolStats,
+    },
 };
 use ::std::ffi::CString;
 
@@ -62,6 +68,16 @@ impl MemoryPool {
         self.pool
     }
 
+    /// Returns the utilization of the target memory pool.
+    pub fn stats(&self) -> MempoolStats {
+        unsafe {
+            MempoolStats {
+                allocated: rte_mempool_in_use_count(self.pool) as usize,
+                free: rte_mempool_avail_count(self.pool) as usize,
+            }
+        }
+    }
+
     /// Allocates a mbuf in the target memory pool.
     pub fn alloc_mbuf(&self, size: Option<usize>) -> Result<*mut rte_mbuf, Fail> {
         // TODO: Drop the following warning once DPDK memory management is more stable.
@@ -72,6 +88,7 @@ impl MemoryPool {
         if mbuf_ptr.is_null() {
             return Err(Fail::new(libc::ENOMEM, "cannot allocate more mbufs"));
         }
+        poison::check_poison(mbuf_ptr);
 
         // Fill out some fields of the underlying mbuf.
         unsafe {
@@ -81,7 +98,7 @@ impl MemoryPool {
                 // Check if allocated buffer is big enough.
                 if (size as u16) > num_bytes {
                     // Allocated buffer is not big enough, rollback allocation.
-                    rte_pktmbuf_free(mbuf_ptr);
+                    Self::free_mbuf(mbuf_ptr);
                     return Err(Fail::new(libc::EFAULT, "cannot allocate a mbuf this big"));
                 }
                 num_bytes = size as u16;
@@ -95,6 +112,7 @@ impl MemoryPool {
 
     /// Releases a mbuf in the target memory pool.
     pub fn free_mbuf(mbuf_ptr: *mut rte_mbuf) {
+        poison::poison(mbuf_ptr);
         unsafe {
             rte_pktmbuf_free(mbuf_ptr);
         }
@@ -102,11 +120,12 @@ impl MemoryPool {
 
     /// Clones a mbuf into a memory pool.
     pub fn clone_mbuf(mbuf_ptr: *mut rte_mbuf) -> Result<*mut rte_mbuf, Fail> {
+        poison::check_refcnt(mbuf_ptr);
         unsafe {
             let mempool_ptr: *mut rte_mempool = (*mbuf_ptr).pool;
             let mbuf_ptr_clone: *mut rte_mbuf = rte_pktmbuf_clone(mbuf_ptr, mempool_ptr);
             if mbuf_ptr_clone.is_null() {
-                return Err(Fail::new(libc::EINVAL, "cannot clone mbuf"));
+                return Err(Fail::new(libc::ENOBUFS, "cannot clone mbuf"));
             }
 
             Ok(mbuf_ptr_clone)
@@ -10,7 +10,10 @@ mod mempool;
 // Exports
 //==============================================================================
 
-pub use self::manager::MemoryManager;
+pub use self::manager::{
+    MemoryConfig,
+    MemoryManager,
+};
 
 //==============================================================================
 // Imports
@@ -18,14 +21,59 @@ pub use self::manager::MemoryManager;
 
 use super::DPDKRuntime;
 use crate::runtime::{
-    fail::Fail,
+    fail::{
+        ErrorKind,
+        Fail,
+    },
+    libdpdk::rte_eth_tx_done_cleanup,
     memory::{
+        stats::{
+            MempoolId,
+            MempoolStats,
+        },
         Buffer,
         MemoryRuntime,
     },
     types::demi_sgarray_t,
 };
 
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for DPDK Runtime
+impl DPDKRuntime {
+    /// Allocates a [Buffer] that is suitable to be pushed.
+    pub fn alloc_buffer(&self, size: usize) -> Result<Buffer, Fail> {
+        self.retry_alloc(|| self.mm.alloc_buffer(size))
+    }
+
+    /// Runs an allocation, retrying it up to the configured number of times while memory pools are exhausted. Before
+    /// each retry, buffers held by the device for transmissions that have already completed are released.
+    pub(super) fn retry_alloc<T, F: Fn() -> Result<T, Fail>>(&self, alloc: F) -> Result<T, Fail> {
+        let mut retries: usize = 0;
+        loop {
+            match alloc() {
+                Err(e) if e.kind() == ErrorKind::OutOfMemory && retries < self.alloc_retries => {
+                    retries += 1;
+                    let nfreed: i32 = unsafe { rte_eth_tx_done_cleanup(self.port_id, 0, 0) };
+                    trace!(
+                        "retry_alloc(): reclaimed {} transmit buffers (retry {})",
+                        nfreed,
+                        retries
+                    );
+                },
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns the utilization of a memory pool.
+    pub fn pool_stats(&self, id: MempoolId) -> MempoolStats {
+        self.mm.pool_stats(id)
+    }
+}
+
 //==============================================================================
 // Trait Implementations
 //==============================================================================
@@ -39,7 +87,7 @@ impl MemoryRuntime for DPDKRuntime {
 
     /// Allocates a [demi_sgarray_t].
     fn alloc_sgarray(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
-        self.mm.alloc_sgarray(size)
+        self.retry_alloc(|| self.mm.alloc_sgarray(size))
     }
 
     /// Releases a [demi_sgarray_t].
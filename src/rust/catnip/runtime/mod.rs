@@ -3,27 +3,43 @@
 
 pub mod memory;
 mod network;
+pub mod stats;
 
 //==============================================================================
 // Imports
 //==============================================================================
 
-use self::memory::{
-    consts::DEFAULT_MAX_BODY_SIZE,
-    MemoryManager,
+use self::{
+    memory::{
+        consts::DEFAULT_MAX_BODY_SIZE,
+        MemoryConfig,
+        MemoryManager,
+    },
+    network::TxBatch,
+    stats::DeviceState,
 };
 use crate::runtime::{
+    fail::Fail,
     libdpdk::{
         rte_delay_us_block,
+        rte_eal_cleanup,
         rte_eal_init,
         rte_eth_conf,
         rte_eth_dev_configure,
         rte_eth_dev_count_avail,
+        rte_eth_dev_fw_version_get,
         rte_eth_dev_get_mtu,
+        rte_eth_dev_get_name_by_port,
+        rte_eth_dev_info,
         rte_eth_dev_info_get,
         rte_eth_dev_is_valid_port,
+        rte_eth_dev_owner,
+        rte_eth_dev_owner_new,
+        rte_eth_dev_owner_set,
+        rte_eth_dev_owner_unset,
         rte_eth_dev_set_mtu,
         rte_eth_dev_start,
+        rte_eth_dev_stop,
         rte_eth_find_next_owned_by,
         rte_eth_link,
         rte_eth_link_get_nowait,
@@ -32,17 +48,31 @@ use crate::runtime::{
         rte_eth_rx_mq_mode_ETH_MQ_RX_RSS as ETH_MQ_RX_RSS,
         rte_eth_rx_queue_setup,
         rte_eth_rxconf,
+        rte_eth_stats,
+        rte_eth_stats_get,
         rte_eth_tx_mq_mode_ETH_MQ_TX_NONE as ETH_MQ_TX_NONE,
         rte_eth_tx_queue_setup,
         rte_eth_txconf,
         rte_ether_addr,
         DEV_RX_OFFLOAD_JUMBO_FRAME,
         DEV_RX_OFFLOAD_TCP_CKSUM,
+        DEV_RX_OFFLOAD_TIMESTAMP,
         DEV_RX_OFFLOAD_UDP_CKSUM,
         DEV_TX_OFFLOAD_MULTI_SEGS,
         DEV_TX_OFFLOAD_TCP_CKSUM,
         DEV_TX_OFFLOAD_UDP_CKSUM,
         ETH_LINK_FULL_DUPLEX,
+        ETH_LINK_SPEED_100G,
+        ETH_LINK_SPEED_10G,
+        ETH_LINK_SPEED_1G,
+        ETH_LINK_SPEED_200G,
+        ETH_LINK_SPEED_20G,
+        ETH_LINK_SPEED_25G,
+        ETH_LINK_SPEED_2_5G,
+        ETH_LINK_SPEED_40G,
+        ETH_LINK_SPEED_50G,
+        ETH_LINK_SPEED_56G,
+        ETH_LINK_SPEED_5G,
         ETH_LINK_UP,
         ETH_RSS_IP,
         RTE_ETHER_MAX_JUMBO_FRAME_LEN,
@@ -50,12 +80,21 @@ use crate::runtime::{
         RTE_ETH_DEV_NO_OWNER,
         RTE_PKTMBUF_HEADROOM,
     },
+    memory::Buffer,
     network::{
         config::{
             ArpConfig,
+            RoutingTable,
             TcpConfig,
             UdpConfig,
         },
+        event::DeviceEvent,
+        stats::TxStats,
+        topology::{
+            Capabilities,
+            OffloadTopology,
+            Topology,
+        },
         types::MacAddress,
     },
     Runtime,
@@ -65,14 +104,45 @@ use ::anyhow::{
     format_err,
     Error,
 };
+use ::libc::c_char;
 use ::std::{
-    collections::HashMap,
-    ffi::CString,
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    ffi::{
+        CStr,
+        CString,
+    },
     mem::MaybeUninit,
     net::Ipv4Addr,
+    rc::Rc,
     time::Duration,
 };
 
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Number of receive queues.
+const RX_RINGS: u16 = 1;
+
+/// Number of transmit queues.
+const TX_RINGS: u16 = 1;
+
+/// Number of descriptors in each receive queue.
+const RX_RING_SIZE: u16 = 2048;
+
+/// Number of descriptors in each transmit queue.
+const TX_RING_SIZE: u16 = 2048;
+
+/// Maximum length of device names and firmware versions reported by DPDK.
+const DEVICE_STRING_MAX_LEN: usize = 64;
+
 //==============================================================================
 // Macros
 //==============================================================================
@@ -97,6 +167,15 @@ macro_rules! expect_zero {
 pub struct DPDKRuntime {
     mm: MemoryManager,
     port_id: u16,
+    rx_batch_size: usize,
+    alloc_retries: usize,
+    tx_batch_size: usize,
+    tx_batch_delay: Duration,
+    tx_batch: Rc<RefCell<TxBatch>>,
+    loopback: Rc<RefCell<VecDeque<Buffer>>>,
+    tx_stats: Rc<Cell<TxStats>>,
+    device_state: Rc<Cell<DeviceState>>,
+    topology: Topology,
     pub link_addr: MacAddress,
     pub ipv4_addr: Ipv4Addr,
     pub arp_options: ArpConfig,
@@ -113,22 +192,39 @@ impl DPDKRuntime {
     pub fn new(
         ipv4_addr: Ipv4Addr,
         eal_init_args: &[CString],
-        arp_table: HashMap<Ipv4Addr, MacAddress>,
+        mut arp_table: HashMap<Ipv4Addr, MacAddress>,
         disable_arp: bool,
+        static_arp: bool,
+        routing_table: RoutingTable,
         use_jumbo_frames: bool,
         mtu: u16,
         mss: usize,
         tcp_checksum_offload: bool,
         udp_checksum_offload: bool,
-    ) -> DPDKRuntime {
-        let (mm, port_id, link_addr) = Self::initialize_dpdk(
+        memory_config: MemoryConfig,
+        rx_batch_size: usize,
+        alloc_retries: usize,
+        tx_batch_size: usize,
+        tx_batch_delay: Duration,
+    ) -> Result<DPDKRuntime, Fail> {
+        let (mm, port_id, link_addr) = match Self::initialize_dpdk(
             eal_init_args,
+            memory_config,
             use_jumbo_frames,
             mtu,
             tcp_checksum_offload,
             udp_checksum_offload,
-        )
-        .unwrap();
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                let cause: String = format!("failed to initialize DPDK device: {}", e);
+                error!("new(): {}", cause);
+                return Err(Fail::new(libc::ENODEV, &cause));
+            },
+        };
+
+        // Frames addressed to ourselves never reach the device, so our own address is always resolved locally.
+        arp_table.insert(ipv4_addr, link_addr);
 
         let arp_options = ArpConfig::new(
             Some(Duration::from_secs(15)),
@@ -136,6 +232,8 @@ impl DPDKRuntime {
             Some(5),
             Some(arp_table),
             Some(disable_arp),
+            Some(static_arp),
+            Some(routing_table),
         );
 
         let tcp_options = TcpConfig::new(
@@ -147,24 +245,123 @@ impl DPDKRuntime {
             None,
             Some(tcp_checksum_offload),
             Some(tcp_checksum_offload),
+            None,
+            None,
         );
 
         let udp_options = UdpConfig::new(Some(udp_checksum_offload), Some(udp_checksum_offload));
 
-        Self {
+        let mut topology: Topology = Self::query_topology(port_id);
+        topology.mtu = Some(mtu);
+        topology.link_addr = Some(link_addr);
+        topology.ipv4_addr = Some(ipv4_addr);
+        topology.mempools = mm.pool_topology();
+        topology.offloads = OffloadTopology::new(&tcp_options, &udp_options);
+        topology.offloads.tx_multi_segs = true;
+
+        Ok(Self {
             mm,
             port_id,
+            rx_batch_size,
+            alloc_retries,
+            tx_batch_size,
+            tx_batch_delay,
+            tx_batch: Rc::new(RefCell::new(TxBatch::default())),
+            loopback: Rc::new(RefCell::new(VecDeque::new())),
+            tx_stats: Rc::new(Cell::new(TxStats::default())),
+            device_state: Rc::new(Cell::new(Self::query_device_state(port_id))),
+            topology,
             link_addr,
             ipv4_addr,
             arp_options,
             tcp_options,
             udp_options,
+        })
+    }
+
+    /// Stops the underlying DPDK port, so that buffers held by the device return to their memory pools, and reports
+    /// buffers that were leaked.
+    pub fn shutdown(&self) {
+        self.flush_tx_batch();
+        self.discard_tx_batch();
+        unsafe {
+            rte_eth_dev_stop(self.port_id);
+        }
+        self.mm.check_leaks();
+    }
+
+    /// Returns the transmit copy statistics collected so far.
+    pub fn tx_stats(&self) -> TxStats {
+        self.tx_stats.get()
+    }
+
+    /// Resets the transmit copy statistics.
+    pub fn reset_tx_stats(&self) {
+        self.tx_stats.set(TxStats::default());
+    }
+
+    /// Returns a description of the datapath instantiated by the target DPDK runtime.
+    pub fn topology(&self) -> Topology {
+        self.topology.clone()
+    }
+
+    /// Returns the device events that occurred since the last call. This queries the device, which is slower than
+    /// regular I/O, so applications should not call it on every iteration of their event loop.
+    pub fn poll_events(&self) -> Vec<DeviceEvent> {
+        let state: DeviceState = Self::query_device_state(self.port_id);
+        let events: Vec<DeviceEvent> = state.events_since(&self.device_state.replace(state));
+        for event in &events {
+            match event {
+                DeviceEvent::LinkUp(_) | DeviceEvent::LinkDown => warn!("poll_events(): {:?}", event),
+                _ => debug!("poll_events(): {:?}", event),
+            }
+        }
+        events
+    }
+
+    /// Queries DPDK for the link status and error counters of a device.
+    fn query_device_state(port_id: u16) -> DeviceState {
+        let link: rte_eth_link = unsafe {
+            let mut link: MaybeUninit<rte_eth_link> = MaybeUninit::zeroed();
+            rte_eth_link_get_nowait(port_id, link.as_mut_ptr());
+            link.assume_init()
+        };
+        let mut state: DeviceState = DeviceState {
+            link_up: link.link_status() as u32 == ETH_LINK_UP,
+            link_speed: link.link_speed,
+            ..Default::default()
+        };
+        let mut stats: MaybeUninit<rte_eth_stats> = MaybeUninit::zeroed();
+        if unsafe { rte_eth_stats_get(port_id, stats.as_mut_ptr()) } == 0 {
+            let stats: rte_eth_stats = unsafe { stats.assume_init() };
+            state.rx_errors = stats.ierrors;
+            state.rx_nombuf = stats.rx_nombuf;
+            state.tx_errors = stats.oerrors;
+        }
+        state
+    }
+
+    /// Updates the transmit copy statistics.
+    fn update_tx_stats<F: FnOnce(&mut TxStats)>(&self, f: F) {
+        let mut stats: TxStats = self.tx_stats.get();
+        f(&mut stats);
+        self.tx_stats.set(stats);
+    }
+
+    /// Returns the maximum body size for the given jumbo frames setting.
+    pub fn max_body_size(use_jumbo_frames: bool) -> usize {
+        if use_jumbo_frames {
+            (RTE_ETHER_MAX_JUMBO_FRAME_LEN + RTE_PKTMBUF_HEADROOM) as usize
+        } else {
+            DEFAULT_MAX_BODY_SIZE
         }
     }
 
-    /// Initializes DPDK.
+    /// Initializes DPDK. On failure, everything that was set up is torn down again, so that the process may fall back
+    /// to another LibOS.
     fn initialize_dpdk(
         eal_init_args: &[CString],
+        memory_config: MemoryConfig,
         use_jumbo_frames: bool,
         mtu: u16,
         tcp_checksum_offload: bool,
@@ -174,25 +371,92 @@ impl DPDKRuntime {
         std::env::set_var("MLX5_SINGLE_THREADED", "1");
         std::env::set_var("MLX4_SINGLE_THREADED", "1");
         let eal_init_refs = eal_init_args.iter().map(|s| s.as_ptr() as *mut u8).collect::<Vec<_>>();
-        unsafe {
-            rte_eal_init(eal_init_refs.len() as i32, eal_init_refs.as_ptr() as *mut _);
+        let ret: i32 = unsafe { rte_eal_init(eal_init_refs.len() as i32, eal_init_refs.as_ptr() as *mut _) };
+        if ret < 0 {
+            bail!(
+                "Failed to initialize DPDK EAL (is another process using the same file prefix or device?) {:?}",
+                eal_init_args
+            );
+        }
+        match Self::initialize_dpdk_device(
+            memory_config,
+            use_jumbo_frames,
+            mtu,
+            tcp_checksum_offload,
+            udp_checksum_offload,
+        ) {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                unsafe { rte_eal_cleanup() };
+                Err(e)
+            },
         }
+    }
+
+    /// Finds a free DPDK port, claims it, and initializes it. On failure, the port is stopped and released.
+    fn initialize_dpdk_device(
+        memory_config: MemoryConfig,
+        use_jumbo_frames: bool,
+        mtu: u16,
+        tcp_checksum_offload: bool,
+        udp_checksum_offload: bool,
+    ) -> Result<(MemoryManager, u16, MacAddress), Error> {
         let nb_ports: u16 = unsafe { rte_eth_dev_count_avail() };
         if nb_ports == 0 {
             bail!("No ethernet ports available");
         }
         eprintln!("DPDK reports that {} ports (interfaces) are available.", nb_ports);
 
-        let max_body_size: usize = if use_jumbo_frames {
-            (RTE_ETHER_MAX_JUMBO_FRAME_LEN + RTE_PKTMBUF_HEADROOM) as usize
-        } else {
-            DEFAULT_MAX_BODY_SIZE
-        };
-
-        let memory_manager = MemoryManager::new(max_body_size)?;
-
         let owner: u64 = RTE_ETH_DEV_NO_OWNER as u64;
         let port_id: u16 = unsafe { rte_eth_find_next_owned_by(0, owner) as u16 };
+        if unsafe { rte_eth_dev_is_valid_port(port_id) } == 0 {
+            bail!("All ethernet ports are busy (owned by other processes)");
+        }
+        let owner_id: u64 = Self::claim_dpdk_port(port_id)?;
+        match Self::initialize_claimed_dpdk_port(
+            port_id,
+            memory_config,
+            use_jumbo_frames,
+            mtu,
+            tcp_checksum_offload,
+            udp_checksum_offload,
+        ) {
+            Ok((memory_manager, local_link_addr)) => Ok((memory_manager, port_id, local_link_addr)),
+            Err(e) => {
+                unsafe {
+                    rte_eth_dev_stop(port_id);
+                    rte_eth_dev_owner_unset(port_id, owner_id);
+                }
+                Err(e)
+            },
+        }
+    }
+
+    /// Sizes memory pools for a DPDK port that we own, and initializes that port.
+    fn initialize_claimed_dpdk_port(
+        port_id: u16,
+        mut memory_config: MemoryConfig,
+        use_jumbo_frames: bool,
+        mtu: u16,
+        tcp_checksum_offload: bool,
+        udp_checksum_offload: bool,
+    ) -> Result<(MemoryManager, MacAddress), Error> {
+        // Pools are sized once the device is known, since that is when we learn about its link speed.
+        if memory_config.is_autosized() {
+            let num_descriptors: usize =
+                RX_RINGS as usize * RX_RING_SIZE as usize + TX_RINGS as usize * TX_RING_SIZE as usize;
+            match Self::max_link_speed_mbps(port_id) {
+                Some(link_speed_mbps) => memory_config.autosize(link_speed_mbps, mtu, num_descriptors),
+                None => eprintln!("WARNING: Unknown link speed, using default memory pool sizes."),
+            }
+            memory_config.validate()?;
+            eprintln!(
+                "Memory pools sized to {} header buffers and {} body buffers.",
+                memory_config.get_header_pool_size(),
+                memory_config.get_body_pool_size()
+            );
+        }
+        let memory_manager = MemoryManager::new(memory_config)?;
         Self::initialize_dpdk_port(
             port_id,
             &memory_manager,
@@ -217,7 +481,110 @@ impl DPDKRuntime {
             Err(format_err!("Invalid mac address"))?;
         }
 
-        Ok((memory_manager, port_id, local_link_addr))
+        Ok((memory_manager, local_link_addr))
+    }
+
+    /// Returns the highest link speed (in Mbps) that a device supports, if it reports one.
+    fn max_link_speed_mbps(port_id: u16) -> Option<u32> {
+        const LINK_SPEEDS: [(u32, u32); 11] = [
+            (ETH_LINK_SPEED_200G, 200_000),
+            (ETH_LINK_SPEED_100G, 100_000),
+            (ETH_LINK_SPEED_56G, 56_000),
+            (ETH_LINK_SPEED_50G, 50_000),
+            (ETH_LINK_SPEED_40G, 40_000),
+            (ETH_LINK_SPEED_25G, 25_000),
+            (ETH_LINK_SPEED_20G, 20_000),
+            (ETH_LINK_SPEED_10G, 10_000),
+            (ETH_LINK_SPEED_5G, 5_000),
+            (ETH_LINK_SPEED_2_5G, 2_500),
+            (ETH_LINK_SPEED_1G, 1_000),
+        ];
+        let dev_info: rte_eth_dev_info = unsafe {
+            let mut d: MaybeUninit<rte_eth_dev_info> = MaybeUninit::zeroed();
+            rte_eth_dev_info_get(port_id, d.as_mut_ptr());
+            d.assume_init()
+        };
+        LINK_SPEEDS
+            .iter()
+            .find(|(capability, _)| dev_info.speed_capa & capability != 0)
+            .map(|(_, link_speed_mbps)| *link_speed_mbps)
+    }
+
+    /// Translates the device information reported by DPDK into device capabilities.
+    fn query_capabilities(dev_info: &rte_eth_dev_info) -> Capabilities {
+        let rx_offload_capa: u64 = dev_info.rx_offload_capa;
+        let tx_offload_capa: u64 = dev_info.tx_offload_capa;
+        Capabilities {
+            max_rx_queues: dev_info.max_rx_queues as usize,
+            max_tx_queues: dev_info.max_tx_queues as usize,
+            max_rx_ring_size: dev_info.rx_desc_lim.nb_max as usize,
+            max_tx_ring_size: dev_info.tx_desc_lim.nb_max as usize,
+            max_tx_segments: dev_info.tx_desc_lim.nb_seg_max as usize,
+            max_mtu: dev_info.max_mtu,
+            offloads: OffloadTopology {
+                tcp_rx_checksum: rx_offload_capa & DEV_RX_OFFLOAD_TCP_CKSUM as u64 != 0,
+                tcp_tx_checksum: tx_offload_capa & DEV_TX_OFFLOAD_TCP_CKSUM as u64 != 0,
+                udp_rx_checksum: rx_offload_capa & DEV_RX_OFFLOAD_UDP_CKSUM as u64 != 0,
+                udp_tx_checksum: tx_offload_capa & DEV_TX_OFFLOAD_UDP_CKSUM as u64 != 0,
+                tx_multi_segs: tx_offload_capa & DEV_TX_OFFLOAD_MULTI_SEGS as u64 != 0,
+            },
+            jumbo_frames: rx_offload_capa & DEV_RX_OFFLOAD_JUMBO_FRAME as u64 != 0,
+            rx_timestamp: rx_offload_capa & DEV_RX_OFFLOAD_TIMESTAMP as u64 != 0,
+            rss: dev_info.flow_type_rss_offloads != 0,
+        }
+    }
+
+    /// Queries DPDK for the device-specific parts of the datapath topology.
+    fn query_topology(port_id: u16) -> Topology {
+        let mut topology: Topology = Topology::new("catnip");
+
+        let dev_info: rte_eth_dev_info = unsafe {
+            let mut d: MaybeUninit<rte_eth_dev_info> = MaybeUninit::zeroed();
+            rte_eth_dev_info_get(port_id, d.as_mut_ptr());
+            d.assume_init()
+        };
+        topology.capabilities = Self::query_capabilities(&dev_info);
+        if !dev_info.driver_name.is_null() {
+            let driver_name: &CStr = unsafe { CStr::from_ptr(dev_info.driver_name) };
+            topology.driver = Some(driver_name.to_string_lossy().into_owned());
+        }
+
+        // For PCI devices, the name of the port is the PCI address of the device.
+        let mut name: [c_char; DEVICE_STRING_MAX_LEN] = [0; DEVICE_STRING_MAX_LEN];
+        if unsafe { rte_eth_dev_get_name_by_port(port_id, name.as_mut_ptr()) } == 0 {
+            let name: &CStr = unsafe { CStr::from_ptr(name.as_ptr()) };
+            topology.device = Some(name.to_string_lossy().into_owned());
+        }
+
+        // Not all drivers report a firmware version.
+        let mut fw_version: [c_char; DEVICE_STRING_MAX_LEN] = [0; DEVICE_STRING_MAX_LEN];
+        if unsafe { rte_eth_dev_fw_version_get(port_id, fw_version.as_mut_ptr(), DEVICE_STRING_MAX_LEN as _) } == 0 {
+            let fw_version: &CStr = unsafe { CStr::from_ptr(fw_version.as_ptr()) };
+            topology.firmware_version = Some(fw_version.to_string_lossy().into_owned());
+        }
+
+        topology.num_rx_queues = dev_info.nb_rx_queues as usize;
+        topology.num_tx_queues = dev_info.nb_tx_queues as usize;
+        topology.rx_ring_size = RX_RING_SIZE as usize;
+        topology.tx_ring_size = TX_RING_SIZE as usize;
+        topology
+    }
+
+    /// Takes ownership of a DPDK port, so that other processes attached to the same device do not use it. The returned
+    /// owner identifier is needed to release the port.
+    fn claim_dpdk_port(port_id: u16) -> Result<u64, Error> {
+        let mut owner: rte_eth_dev_owner = unsafe { MaybeUninit::zeroed().assume_init() };
+        unsafe {
+            expect_zero!(rte_eth_dev_owner_new(&mut owner.id as *mut u64))?;
+        }
+        let name: String = format!("demikernel-{}", std::process::id());
+        for (i, b) in name.bytes().take(owner.name.len() - 1).enumerate() {
+            owner.name[i] = b as _;
+        }
+        if unsafe { rte_eth_dev_owner_set(port_id, &owner as *const _) } != 0 {
+            bail!("Port {} is busy (owned by another process)", port_id);
+        }
+        Ok(owner.id)
     }
 
     /// Initializes a DPDK port.
@@ -229,12 +596,8 @@ impl DPDKRuntime {
         tcp_checksum_offload: bool,
         udp_checksum_offload: bool,
     ) -> Result<(), Error> {
-        let rx_rings = 1;
-        let tx_rings = 1;
-        let rx_ring_size = 2048;
-        let tx_ring_size = 2048;
-        let nb_rxd = rx_ring_size;
-        let nb_txd = tx_ring_size;
+        let nb_rxd: u16 = RX_RING_SIZE;
+        let nb_txd: u16 = TX_RING_SIZE;
 
         let rx_pthresh = 8;
         let rx_hthresh = 8;
@@ -251,6 +614,37 @@ impl DPDKRuntime {
         };
 
         println!("dev_info: {:?}", dev_info);
+        // Fail early, with a clear message, if the device lacks a feature that we are about to configure.
+        let required: Capabilities = Capabilities {
+            max_rx_queues: RX_RINGS as usize,
+            max_tx_queues: TX_RINGS as usize,
+            max_rx_ring_size: nb_rxd as usize,
+            max_tx_ring_size: nb_txd as usize,
+            // Packets may span a header and a body buffer.
+            max_tx_segments: 2,
+            max_mtu: mtu,
+            offloads: OffloadTopology {
+                tcp_rx_checksum: tcp_checksum_offload,
+                tcp_tx_checksum: tcp_checksum_offload,
+                udp_rx_checksum: udp_checksum_offload,
+                udp_tx_checksum: udp_checksum_offload,
+                tx_multi_segs: true,
+            },
+            jumbo_frames: use_jumbo_frames,
+            ..Default::default()
+        };
+        if let Err(e) = Self::query_capabilities(&dev_info).check(&required) {
+            bail!("{}", e.cause);
+        }
+        // Received packets are stored in the body pool, so it must be able to fill the whole receive ring.
+        let body_pool_size: usize = memory_manager.config().get_body_pool_size();
+        if body_pool_size < nb_rxd as usize {
+            bail!(
+                "Body pool size {} is too small for receive ring size {}",
+                body_pool_size,
+                nb_rxd
+            );
+        }
         unsafe {
             expect_zero!(rte_eth_dev_set_mtu(port_id, mtu))?;
             let mut dpdk_mtu = 0u16;
@@ -302,8 +696,8 @@ impl DPDKRuntime {
         unsafe {
             expect_zero!(rte_eth_dev_configure(
                 port_id,
-                rx_rings,
-                tx_rings,
+                RX_RINGS,
+                TX_RINGS,
                 &port_conf as *const _,
             ))?;
         }
@@ -311,7 +705,7 @@ impl DPDKRuntime {
         let socket_id = 0;
 
         unsafe {
-            for i in 0..rx_rings {
+            for i in 0..RX_RINGS {
                 expect_zero!(rte_eth_rx_queue_setup(
                     port_id,
                     i,
@@ -321,7 +715,7 @@ impl DPDKRuntime {
                     memory_manager.body_pool(),
                 ))?;
             }
-            for i in 0..tx_rings {
+            for i in 0..TX_RINGS {
                 expect_zero!(rte_eth_tx_queue_setup(
                     port_id,
                     i,
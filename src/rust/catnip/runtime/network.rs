@@ -9,29 +9,213 @@ use super::DPDKRuntime;
 use crate::{
     inetstack::protocols::ethernet2::MIN_PAYLOAD_SIZE,
     runtime::{
+        fail::{
+            ErrorKind,
+            Fail,
+        },
         libdpdk::{
             rte_eth_rx_burst,
             rte_eth_tx_burst,
             rte_mbuf,
             rte_pktmbuf_chain,
+            rte_pktmbuf_free,
         },
         memory::{
+            copy,
+            poison,
             Buffer,
             DPDKBuffer,
+            DataBuffer,
         },
         network::{
-            consts::RECEIVE_BATCH_SIZE,
+            consts::{
+                MAX_TRANSMIT_BATCH_SIZE,
+                RECEIVE_BATCH_SIZE,
+            },
             NetworkRuntime,
             PacketBuf,
         },
     },
 };
 use ::arrayvec::ArrayVec;
-use ::std::mem;
+use ::std::{
+    mem,
+    slice,
+    time::Instant,
+};
 
 #[cfg(feature = "profiler")]
 use crate::timer;
 
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Number of times in a row that a transmit burst may fail to send any packet before giving up until the next poll.
+const MAX_TX_BURST_RETRIES: usize = 4;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Transmit Batch
+///
+/// Packets that were handed to the device driver but not yet sent to the device.
+#[derive(Default)]
+pub struct TxBatch {
+    /// Pending packets.
+    pkts: ArrayVec<*mut rte_mbuf, MAX_TRANSMIT_BATCH_SIZE>,
+    /// Time at which the oldest pending packet should be sent at the latest.
+    deadline: Option<Instant>,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Transmit Functions for DPDK Runtime
+impl DPDKRuntime {
+    /// Sends a frame. Frames addressed to the local link address are handed straight back to the receive path
+    /// instead of going out on the wire, where they may or may not be reflected back to us.
+    fn transmit_frame(&self, frame: DPDKBuffer) {
+        if frame[..6] == self.link_addr.octets() {
+            self.update_tx_stats(|stats| stats.packets += 1);
+            let frame: Buffer = Self::linearize(frame);
+            self.loopback.borrow_mut().push_back(frame);
+        } else {
+            self.enqueue_tx(frame.into_raw());
+        }
+    }
+
+    /// Copies all segments of a frame into a single heap buffer, and releases the frame.
+    fn linearize(frame: DPDKBuffer) -> Buffer {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut segment: *mut rte_mbuf = frame.get_ptr();
+        while !segment.is_null() {
+            unsafe {
+                let data: *const u8 = ((*segment).buf_addr as *const u8).add((*segment).data_off as usize);
+                bytes.extend_from_slice(slice::from_raw_parts(data, (*segment).data_len as usize));
+                segment = (*segment).next;
+            }
+        }
+        Buffer::Heap(DataBuffer::from_slice(&bytes))
+    }
+
+    /// Queues a packet for transmission. The transmit batch is sent to the device once it is full, so that the
+    /// doorbell of the transmit queue is rung once per batch rather than once per packet. A partial batch is sent by
+    /// [DPDKRuntime::flush_expired_tx_batch] once its delay expires. Since that only happens when the network stack is
+    /// polled, a partial batch waits until the application next polls (e.g. when it waits on a queue token).
+    fn enqueue_tx(&self, pkt: *mut rte_mbuf) {
+        // Packets that the device did not take may still fill up the batch.
+        if self.tx_batch.borrow().pkts.is_full() {
+            self.flush_tx_batch();
+        }
+        let full: bool = {
+            let mut batch = self.tx_batch.borrow_mut();
+            if batch.pkts.is_full() {
+                // The transmit queue of the device is stuck, so drop the packet as a full link would.
+                warn!("enqueue_tx(): transmit queue is full, dropping packet");
+                unsafe { rte_pktmbuf_free(pkt) };
+                self.update_tx_stats(|stats| stats.dropped_packets += 1);
+                return;
+            }
+            if batch.pkts.is_empty() && !self.tx_batch_delay.is_zero() {
+                batch.deadline = Some(Instant::now() + self.tx_batch_delay);
+            }
+            batch.pkts.push(pkt);
+            batch.pkts.len() >= self.tx_batch_size || self.tx_batch_delay.is_zero()
+        };
+        self.update_tx_stats(|stats| stats.packets += 1);
+        if full {
+            self.flush_tx_batch();
+        }
+    }
+
+    /// Sends the pending transmit batch if its delay has expired.
+    fn flush_expired_tx_batch(&self) {
+        let expired: bool = match self.tx_batch.borrow().deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        };
+        if expired {
+            self.flush_tx_batch();
+        }
+    }
+
+    /// Sends pending packets to the device. If the transmit queue of the device is full, the burst is retried up to
+    /// [MAX_TX_BURST_RETRIES] times. Packets that are still not sent then stay in the batch, and are sent the next time
+    /// the network stack is polled.
+    pub(super) fn flush_tx_batch(&self) {
+        let mut batch = self.tx_batch.borrow_mut();
+        let mut sent: usize = 0;
+        let mut retries: usize = 0;
+        while sent < batch.pkts.len() && retries < MAX_TX_BURST_RETRIES {
+            let pending: &mut [*mut rte_mbuf] = &mut batch.pkts[sent..];
+            // No fence is needed before handing mbufs over: the poll mode driver writes the descriptors and issues
+            // the write barrier that orders them before the doorbell, and the call is opaque to the compiler.
+            let num_sent: u16 =
+                unsafe { rte_eth_tx_burst(self.port_id, 0, pending.as_mut_ptr(), pending.len() as u16) };
+            if num_sent == 0 {
+                retries += 1;
+            }
+            sent += num_sent as usize;
+        }
+        batch.pkts.drain(..sent);
+        batch.deadline = if batch.pkts.is_empty() {
+            None
+        } else {
+            Some(Instant::now())
+        };
+    }
+
+    /// Allocates the header mbuf of an outgoing packet, retrying while the pools are exhausted.
+    fn alloc_tx_header_mbuf(&self, control: bool) -> Result<DPDKBuffer, Fail> {
+        alloc_header_or_control(
+            control,
+            || self.retry_alloc(|| self.mm.alloc_header_mbuf()),
+            || self.retry_alloc(|| self.mm.alloc_control_mbuf()),
+        )
+    }
+
+    /// Drops an outgoing packet for which an mbuf could not be allocated.
+    fn drop_tx_packet(&self, what: &str, e: Fail) {
+        warn!(
+            "transmit(): failed to allocate {} mbuf, dropping packet: {:?}",
+            what, e.cause
+        );
+        self.update_tx_stats(|stats| stats.dropped_packets += 1);
+    }
+
+    /// Releases packets that the device did not take.
+    pub(super) fn discard_tx_batch(&self) {
+        let mut batch = self.tx_batch.borrow_mut();
+        if !batch.pkts.is_empty() {
+            warn!("discard_tx_batch(): dropping {} unsent packets", batch.pkts.len());
+        }
+        for pkt in batch.pkts.drain(..) {
+            unsafe { rte_pktmbuf_free(pkt) };
+        }
+        batch.deadline = None;
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Allocates a header mbuf. Packets that carry no data are control packets (e.g. ACKs), so they fall back to the
+/// reserved control pool when the header pool is exhausted.
+fn alloc_header_or_control<T, H: FnOnce() -> Result<T, Fail>, C: FnOnce() -> Result<T, Fail>>(
+    control: bool,
+    alloc_header: H,
+    alloc_control: C,
+) -> Result<T, Fail> {
+    match alloc_header() {
+        Err(e) if control && e.kind() == ErrorKind::OutOfMemory => alloc_control(),
+        result => result,
+    }
+}
+
 //==============================================================================
 // Trait Implementations
 //==============================================================================
@@ -50,10 +234,14 @@ impl NetworkRuntime for DPDKRuntime {
         //   2) Not managed => alloc body
         // Chain body buffer.
 
-        // First, allocate a header mbuf and write the header into it.
-        let mut header_mbuf = match self.mm.alloc_header_mbuf() {
+        // First, allocate a header mbuf and write the header into it. If memory is exhausted, the packet is dropped as
+        // it would be by a congested link, and the network stack recovers as usual (e.g. TCP retransmits).
+        let mut header_mbuf: DPDKBuffer = match self.alloc_tx_header_mbuf(buf.body_size() == 0) {
             Ok(mbuf) => mbuf,
-            Err(e) => panic!("failed to allocate header mbuf: {:?}", e.cause),
+            Err(e) => {
+                self.drop_tx_packet("header", e);
+                return;
+            },
         };
         let header_size = buf.header_size();
         assert!(header_size <= header_mbuf.len());
@@ -70,15 +258,23 @@ impl NetworkRuntime for DPDKRuntime {
                 // We're only using the header_mbuf for, well, the header.
                 header_mbuf.trim(header_mbuf.len() - header_size);
 
+                let body_len: u64 = body.len() as u64;
                 let body_mbuf = match body {
-                    Buffer::DPDK(mbuf) => mbuf.clone(),
+                    Buffer::DPDK(mbuf) => {
+                        self.update_tx_stats(|stats| stats.zero_copy_bytes += body_len);
+                        mbuf.clone()
+                    },
                     Buffer::Heap(bytes) => {
-                        let mut mbuf = match self.mm.alloc_body_mbuf() {
+                        self.update_tx_stats(|stats| stats.heap_copy_bytes += body_len);
+                        let mut mbuf: DPDKBuffer = match self.retry_alloc(|| self.mm.alloc_body_mbuf()) {
                             Ok(mbuf) => mbuf,
-                            Err(e) => panic!("failed to allocate body mbuf: {:?}", e.cause),
+                            Err(e) => {
+                                self.drop_tx_packet("body", e);
+                                return;
+                            },
                         };
                         assert!(mbuf.len() >= bytes.len());
-                        unsafe { mbuf.slice_mut()[..bytes.len()].copy_from_slice(&bytes[..]) };
+                        copy::copy(unsafe { &mut mbuf.slice_mut()[..bytes.len()] }, &bytes[..]);
                         mbuf.trim(mbuf.len() - bytes.len());
                         mbuf
                     },
@@ -86,14 +282,13 @@ impl NetworkRuntime for DPDKRuntime {
                 unsafe {
                     assert_eq!(rte_pktmbuf_chain(header_mbuf.get_ptr(), body_mbuf.into_raw()), 0);
                 }
-                let mut header_mbuf_ptr = header_mbuf.into_raw();
-                let num_sent = unsafe { rte_eth_tx_burst(self.port_id, 0, &mut header_mbuf_ptr, 1) };
-                assert_eq!(num_sent, 1);
+                self.transmit_frame(header_mbuf);
             }
             // Otherwise, write in the inline space.
             else {
+                self.update_tx_stats(|stats| stats.inline_bytes += body.len() as u64);
                 let body_buf = unsafe { &mut header_mbuf.slice_mut()[header_size..(header_size + body.len())] };
-                body_buf.copy_from_slice(&body[..]);
+                copy::copy(body_buf, &body[..]);
 
                 if header_size + body.len() < MIN_PAYLOAD_SIZE {
                     let padding_bytes = MIN_PAYLOAD_SIZE - (header_size + body.len());
@@ -107,9 +302,7 @@ impl NetworkRuntime for DPDKRuntime {
                 let frame_size = std::cmp::max(header_size + body.len(), MIN_PAYLOAD_SIZE);
                 header_mbuf.trim(header_mbuf.len() - frame_size);
 
-                let mut header_mbuf_ptr = header_mbuf.into_raw();
-                let num_sent = unsafe { rte_eth_tx_burst(self.port_id, 0, &mut header_mbuf_ptr, 1) };
-                assert_eq!(num_sent, 1);
+                self.transmit_frame(header_mbuf);
             }
         }
         // No body on our packet, just send the headers.
@@ -123,28 +316,47 @@ impl NetworkRuntime for DPDKRuntime {
             }
             let frame_size = std::cmp::max(header_size, MIN_PAYLOAD_SIZE);
             header_mbuf.trim(header_mbuf.len() - frame_size);
-            let mut header_mbuf_ptr = header_mbuf.into_raw();
-            let num_sent = unsafe { rte_eth_tx_burst(self.port_id, 0, &mut header_mbuf_ptr, 1) };
-            assert_eq!(num_sent, 1);
+            self.transmit_frame(header_mbuf);
         }
     }
 
     fn receive(&self) -> ArrayVec<Buffer, RECEIVE_BATCH_SIZE> {
+        // The network stack polls for incoming packets continuously, so this is where held back packets get sent.
+        self.flush_expired_tx_batch();
+
         let mut out = ArrayVec::new();
 
+        // Deliver frames that we sent to ourselves first.
+        {
+            let mut loopback = self.loopback.borrow_mut();
+            while out.len() < self.rx_batch_size {
+                match loopback.pop_front() {
+                    Some(frame) => out.push(frame),
+                    None => break,
+                }
+            }
+        }
+        let rx_batch_size: usize = self.rx_batch_size - out.len();
+        if rx_batch_size == 0 {
+            return out;
+        }
+
         let mut packets: [*mut rte_mbuf; RECEIVE_BATCH_SIZE] = unsafe { mem::zeroed() };
+        // Likewise, the poll mode driver orders completion reads before it fills in the returned mbufs.
         let nb_rx = unsafe {
             #[cfg(feature = "profiler")]
             timer!("catnip_libos::receive::rte_eth_rx_burst");
 
-            rte_eth_rx_burst(self.port_id, 0, packets.as_mut_ptr(), RECEIVE_BATCH_SIZE as u16)
+            rte_eth_rx_burst(self.port_id, 0, packets.as_mut_ptr(), rx_batch_size as u16)
         };
-        assert!(nb_rx as usize <= RECEIVE_BATCH_SIZE);
+        assert!(nb_rx as usize <= rx_batch_size);
 
         {
             #[cfg(feature = "profiler")]
             timer!("catnip_libos:receive::for");
             for &packet in &packets[..nb_rx as usize] {
+                // The device driver allocated this mbuf and wrote over its data room.
+                poison::forget(packet);
                 let mbuf: DPDKBuffer = DPDKBuffer::new(packet);
                 let buf: Buffer = Buffer::DPDK(mbuf);
                 out.push(buf);
@@ -154,3 +366,45 @@ impl NetworkRuntime for DPDKRuntime {
         out
     }
 }
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::alloc_header_or_control;
+    use crate::runtime::fail::Fail;
+    use ::std::cell::Cell;
+
+    /// Allocator of an exhausted pool.
+    fn exhausted() -> Result<u8, Fail> {
+        Err(Fail::new(libc::ENOMEM, "cannot allocate more mbufs"))
+    }
+
+    #[test]
+    fn alloc_header_exhausted_pool() {
+        // Data packets do not fall back to the control pool, and fail without panicking.
+        let tried_control: Cell<bool> = Cell::new(false);
+        let result: Result<u8, Fail> = alloc_header_or_control(false, exhausted, || {
+            tried_control.set(true);
+            Ok(1)
+        });
+        assert_eq!(result.unwrap_err().errno, libc::ENOMEM);
+        assert!(!tried_control.get());
+
+        // Control packets fall back to the control pool.
+        assert_eq!(alloc_header_or_control(true, exhausted, || Ok(1)).unwrap(), 1);
+
+        // Once the control pool is exhausted as well, control packets fail too.
+        assert_eq!(
+            alloc_header_or_control(true, exhausted, exhausted).unwrap_err().errno,
+            libc::ENOMEM
+        );
+
+        // Other errors are not retried from the control pool.
+        let result: Result<u8, Fail> =
+            alloc_header_or_control(true, || Err(Fail::new(libc::EINVAL, "bad size")), || Ok(1));
+        assert_eq!(result.unwrap_err().errno, libc::EINVAL);
+    }
+}
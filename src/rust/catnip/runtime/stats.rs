@@ -0,0 +1,101 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::network::event::DeviceEvent;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Device State
+///
+/// Snapshot of the link status and error counters of a device, as reported by DPDK.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeviceState {
+    /// Is the link up?
+    pub link_up: bool,
+    /// Link speed (in Mbps).
+    pub link_speed: u32,
+    /// Number of erroneous frames received.
+    pub rx_errors: u64,
+    /// Number of received frames dropped for lack of buffers.
+    pub rx_nombuf: u64,
+    /// Number of frames that failed to transmit.
+    pub tx_errors: u64,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Device States
+impl DeviceState {
+    /// Returns the events that led from the target [DeviceState] to a newer one.
+    pub fn events_since(&self, older: &DeviceState) -> Vec<DeviceEvent> {
+        let mut events: Vec<DeviceEvent> = Vec::new();
+        match (older.link_up, self.link_up) {
+            (true, false) => events.push(DeviceEvent::LinkDown),
+            (false, true) => events.push(DeviceEvent::LinkUp(self.link_speed)),
+            _ => (),
+        }
+        // Counters may go backwards if the device is reset, in which case we report nothing.
+        if self.rx_errors > older.rx_errors {
+            events.push(DeviceEvent::RxErrors(self.rx_errors - older.rx_errors));
+        }
+        if self.rx_nombuf > older.rx_nombuf {
+            events.push(DeviceEvent::RxNoBuffers(self.rx_nombuf - older.rx_nombuf));
+        }
+        if self.tx_errors > older.tx_errors {
+            events.push(DeviceEvent::TxErrors(self.tx_errors - older.tx_errors));
+        }
+        events
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::DeviceState;
+    use crate::runtime::network::event::DeviceEvent;
+
+    #[test]
+    fn device_state_events() {
+        let up: DeviceState = DeviceState {
+            link_up: true,
+            link_speed: 100_000,
+            ..Default::default()
+        };
+        assert!(up.events_since(&up).is_empty());
+
+        let down: DeviceState = DeviceState {
+            link_up: false,
+            rx_nombuf: 3,
+            ..up
+        };
+        assert_eq!(
+            down.events_since(&up),
+            vec![DeviceEvent::LinkDown, DeviceEvent::RxNoBuffers(3)]
+        );
+
+        let up_again: DeviceState = DeviceState {
+            rx_errors: 2,
+            tx_errors: 1,
+            ..up
+        };
+        assert_eq!(
+            up_again.events_since(&down),
+            vec![
+                DeviceEvent::LinkUp(100_000),
+                DeviceEvent::RxErrors(2),
+                DeviceEvent::TxErrors(1)
+            ]
+        );
+    }
+}
@@ -21,7 +21,11 @@ use crate::{
     },
     runtime::{
         fail::Fail,
-        memory::MemoryRuntime,
+        memory::{
+            Buffer,
+            MemoryRuntime,
+        },
+        network::topology::Topology,
         timer::{
             Timer,
             TimerRc,
@@ -103,6 +107,11 @@ impl CatpowderLibOS {
         }
     }
 
+    /// Returns a description of the datapath instantiated by the target LibOS.
+    pub fn topology(&self) -> Topology {
+        self.rt.topology()
+    }
+
     /// Create a push request for Demikernel to asynchronously write data from `sga` to the
     /// IO connection represented by `qd`. This operation returns immediately with a `QToken`.
     /// The data has been written when [`wait`ing](Self::wait) on the QToken returns.
@@ -127,6 +136,19 @@ impl CatpowderLibOS {
         }
     }
 
+    /// Pushes several scatter-gather arrays to a TCP socket with a single operation. The returned queue token completes
+    /// once all of them have been handed to the TCP stack, in order.
+    pub fn push_batch(&mut self, qd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catpowder::push_batch");
+        trace!("push_batch(): qd={:?}", qd);
+        let bufs: Vec<Buffer> = sgas
+            .iter()
+            .map(|sga| self.rt.clone_sgarray(sga))
+            .collect::<Result<Vec<Buffer>, Fail>>()?;
+        self.inetstack.push_batch(qd, bufs)
+    }
+
     pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
         #[cfg(feature = "profiler")]
         timer!("catnip::pushto");
@@ -178,6 +200,24 @@ impl CatpowderLibOS {
         Ok((i, pack_result(self.rt.clone(), r, qd, qts[i].into())))
     }
 
+    /// Waits for up to `max_results` operations to complete.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("catpowder::wait_many");
+        trace!("wait_many(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let results: Vec<(usize, QDesc, OperationResult)> = self.wait_many2(qts, max_results, abstime)?;
+        Ok(results
+            .into_iter()
+            .map(|(i, qd, r)| (i, pack_result(self.rt.clone(), r, qd, qts[i].into())))
+            .collect())
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         self.rt.alloc_sgarray(size)
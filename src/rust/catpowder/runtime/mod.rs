@@ -20,6 +20,10 @@ use crate::runtime::{
             TcpConfig,
             UdpConfig,
         },
+        topology::{
+            OffloadTopology,
+            Topology,
+        },
         types::MacAddress,
     },
     Runtime,
@@ -46,6 +50,7 @@ pub struct LinuxRuntime {
     pub arp_options: ArpConfig,
     pub link_addr: MacAddress,
     pub ipv4_addr: Ipv4Addr,
+    ifname: String,
     ifindex: i32,
     socket: Rc<RefCell<RawSocket>>,
 }
@@ -64,6 +69,8 @@ impl LinuxRuntime {
             Some(2),
             Some(arp),
             Some(false),
+            None,
+            None,
         );
 
         // TODO: Make this constructor return a Result and drop expect() calls bellow.
@@ -79,11 +86,24 @@ impl LinuxRuntime {
             arp_options,
             link_addr,
             ipv4_addr,
+            ifname: ifname.to_string(),
             ifindex,
             socket: Rc::new(RefCell::new(socket)),
         }
     }
 
+    /// Returns a description of the datapath instantiated by the target Linux runtime.
+    pub fn topology(&self) -> Topology {
+        let mut topology: Topology = Topology::new("catpowder");
+        topology.device = Some(self.ifname.clone());
+        topology.num_rx_queues = 1;
+        topology.num_tx_queues = 1;
+        topology.link_addr = Some(self.link_addr);
+        topology.ipv4_addr = Some(self.ipv4_addr);
+        topology.offloads = OffloadTopology::new(&self.tcp_options, &self.udp_options);
+        topology
+    }
+
     /// Gets the interface index of the network interface named `ifname`.
     fn get_ifindex(ifname: &str) -> Result<i32, ParseIntError> {
         let path: String = format!("/sys/class/net/{}/ifindex", ifname);
@@ -13,6 +13,7 @@ use crate::{
     inetstack::protocols::ethernet2::Ethernet2Header,
     runtime::{
         memory::{
+            copy,
             Buffer,
             DataBuffer,
         },
@@ -44,7 +45,7 @@ impl NetworkRuntime for LinuxRuntime {
 
         pkt.write_header(&mut buf[..header_size]);
         if let Some(body) = pkt.take_body() {
-            buf[header_size..].copy_from_slice(&body[..]);
+            copy::copy(&mut buf[header_size..], &body[..]);
         }
 
         let (header, _) = Ethernet2Header::parse(buf.clone()).unwrap();
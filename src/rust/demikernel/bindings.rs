@@ -31,6 +31,7 @@ use ::libc::{
 };
 use ::std::{
     cell::RefCell,
+    ffi::CStr,
     mem,
     net::{
         Ipv4Addr,
@@ -393,6 +394,32 @@ pub extern "C" fn demi_pop(qtok_out: *mut demi_qtoken_t, qd: c_int) -> c_int {
     }
 }
 
+//======================================================================================================================
+// peek
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_peek(qtok_out: *mut demi_qtoken_t, qd: c_int) -> c_int {
+    trace!("demi_peek()");
+
+    // Issue peek operation.
+    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.peek(qd.into()) {
+        Ok(qt) => {
+            unsafe { *qtok_out = qt.into() };
+            0
+        },
+        Err(e) => {
+            warn!("peek() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
 //======================================================================================================================
 // timedwait
 //======================================================================================================================
@@ -446,6 +473,29 @@ pub extern "C" fn demi_timedwait(
     }
 }
 
+//======================================================================================================================
+// cancel
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_cancel(qt: demi_qtoken_t) -> c_int {
+    trace!("demi_cancel() {:?}", qt);
+
+    // Issue cancel operation.
+    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.cancel(qt.into()) {
+        Ok(()) => 0,
+        Err(e) => {
+            warn!("cancel() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
 //======================================================================================================================
 // wait
 //======================================================================================================================
@@ -519,6 +569,77 @@ pub extern "C" fn demi_wait_any(
     }
 }
 
+//======================================================================================================================
+// wait_many
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_wait_many(
+    qrs_out: *mut demi_qresult_t,
+    ready_offsets: *mut c_int,
+    num_ready: *mut c_int,
+    max_results: c_int,
+    qts: *mut demi_qtoken_t,
+    num_qts: c_int,
+    abstime: *const libc::timespec,
+) -> c_int {
+    trace!("demi_wait_many()");
+
+    // Check arguments.
+    if num_qts < 0 || max_results <= 0 {
+        return libc::EINVAL;
+    }
+    if qrs_out.is_null() || ready_offsets.is_null() || num_ready.is_null() {
+        warn!("output argument is a null pointer");
+        return libc::EINVAL;
+    }
+
+    // Get queue tokens.
+    let qts: Vec<QToken> = {
+        let raw_qts: &[u64] = unsafe { slice::from_raw_parts(qts, num_qts as usize) };
+        raw_qts.iter().map(|i| QToken::from(*i)).collect()
+    };
+
+    // Convert timespec to SystemTime. A null pointer means to wait for as long as needed.
+    let abstime: Option<SystemTime> = {
+        if abstime.is_null() {
+            None
+        } else {
+            let timeout: Duration = Duration::from_nanos(
+                unsafe { (*abstime).tv_sec } as u64 * 1_000_000_000_ + unsafe { (*abstime).tv_nsec } as u64,
+            );
+            match SystemTime::UNIX_EPOCH.checked_add(timeout) {
+                Some(abstime) => Some(abstime),
+                None => Some(SystemTime::now()),
+            }
+        }
+    };
+
+    // Issue wait_many operation.
+    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.wait_many(&qts, max_results as usize, abstime) {
+        Ok(results) => {
+            let qrs_out: &mut [demi_qresult_t] = unsafe { slice::from_raw_parts_mut(qrs_out, max_results as usize) };
+            let ready_offsets: &mut [c_int] = unsafe { slice::from_raw_parts_mut(ready_offsets, max_results as usize) };
+            let n: usize = results.len();
+            for (i, (ix, qr)) in results.into_iter().enumerate() {
+                qrs_out[i] = qr;
+                ready_offsets[i] = ix as c_int;
+            }
+            unsafe { *num_ready = n as c_int };
+            0
+        },
+        Err(e) => {
+            warn!("wait_many() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
 //======================================================================================================================
 // sgaalloc
 //======================================================================================================================
@@ -596,7 +717,6 @@ pub extern "C" fn demi_getsockname(qd: c_int, saddr: *mut sockaddr, size: *mut s
 // setsockopt
 //======================================================================================================================
 
-#[allow(unused)]
 #[no_mangle]
 pub extern "C" fn demi_setsockopt(
     qd: c_int,
@@ -605,15 +725,41 @@ pub extern "C" fn demi_setsockopt(
     optval: *const c_void,
     optlen: socklen_t,
 ) -> c_int {
-    // TODO: Implement this system call.
-    libc::ENOSYS
+    trace!("demi_setsockopt() {:?} {:?} {:?}", qd, level, optname);
+
+    // Check for unsupported options.
+    if level != libc::SOL_SOCKET || optname != libc::SO_SNDBUF {
+        return libc::ENOPROTOOPT;
+    }
+
+    // Check arguments.
+    if optval.is_null() || (optlen as usize) < mem::size_of::<c_int>() {
+        return libc::EINVAL;
+    }
+    let size: c_int = unsafe { *(optval as *const c_int) };
+    if size <= 0 {
+        return libc::EINVAL;
+    }
+
+    // Issue operation.
+    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.set_send_buffer_size(qd.into(), size as usize) {
+        Ok(..) => 0,
+        Err(e) => {
+            warn!("setsockopt() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
 }
 
 //======================================================================================================================
 // getsockopt
 //======================================================================================================================
 
-#[allow(unused)]
 #[no_mangle]
 pub extern "C" fn demi_getsockopt(
     qd: c_int,
@@ -622,8 +768,64 @@ pub extern "C" fn demi_getsockopt(
     optval: *mut c_void,
     optlen: *mut socklen_t,
 ) -> c_int {
-    // TODO: Implement this system call.
-    libc::ENOSYS
+    trace!("demi_getsockopt() {:?} {:?} {:?}", qd, level, optname);
+
+    // Check for unsupported options.
+    if level != libc::SOL_SOCKET || optname != libc::SO_SNDBUF {
+        return libc::ENOPROTOOPT;
+    }
+
+    // Check arguments.
+    if optval.is_null() || optlen.is_null() || (unsafe { *optlen } as usize) < mem::size_of::<c_int>() {
+        return libc::EINVAL;
+    }
+
+    // Issue operation.
+    let ret: Result<i32, Fail> = do_syscall(|libos| match libos.get_send_buffer_size(qd.into()) {
+        Ok(size) => {
+            unsafe {
+                *(optval as *mut c_int) = size.min(c_int::MAX as usize) as c_int;
+                *optlen = mem::size_of::<c_int>() as socklen_t;
+            }
+            0
+        },
+        Err(e) => {
+            warn!("getsockopt() failed: {:?}", e);
+            e.errno
+        },
+    });
+
+    match ret {
+        Ok(ret) => ret,
+        Err(e) => e.errno,
+    }
+}
+
+//======================================================================================================================
+// set_log_spec
+//======================================================================================================================
+
+#[no_mangle]
+pub extern "C" fn demi_set_log_spec(spec: *const c_char) -> c_int {
+    trace!("demi_set_log_spec()");
+
+    // Check arguments.
+    if spec.is_null() {
+        return libc::EINVAL;
+    }
+    let spec: &str = match unsafe { CStr::from_ptr(spec) }.to_str() {
+        Ok(spec) => spec,
+        Err(_) => return libc::EINVAL,
+    };
+
+    // Issue operation. This does not need the LibOS, so it works before demi_init() as well.
+    match logging::set_log_spec(spec) {
+        Ok(()) => 0,
+        Err(e) => {
+            warn!("set_log_spec() failed: {:?}", e);
+            e.errno
+        },
+    }
 }
 
 //======================================================================================================================
@@ -5,9 +5,15 @@
 // Imports
 //======================================================================================================================
 
+use crate::{
+    demikernel::libos::name::LibOSName,
+    runtime::fail::Fail,
+};
 use ::std::{
+    env,
     fs::File,
     io::Read,
+    str::FromStr,
 };
 use ::yaml_rust::{
     Yaml,
@@ -41,6 +47,32 @@ impl Config {
         Self { 0: config_obj.clone() }
     }
 
+    /// Reads the names of the LibOSes to try, in order, when the requested one cannot be instantiated. They are read
+    /// from the comma-separated "FALLBACK_LIBOS" environment variable or, if that is not set, from the "fallback_libos"
+    /// parameter, which is either a single name or a list of names. Unknown names are reported as errors.
+    pub fn fallback_libos(&self) -> Result<Vec<LibOSName>, Fail> {
+        if let Ok(names) = env::var("FALLBACK_LIBOS") {
+            return names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(LibOSName::from_str)
+                .collect();
+        }
+        match &self.0["demikernel"]["fallback_libos"] {
+            Yaml::String(name) => Ok(vec![LibOSName::from_str(name)?]),
+            Yaml::Array(names) => names
+                .iter()
+                .map(|name| match name.as_str() {
+                    Some(name) => LibOSName::from_str(name),
+                    None => Err(Fail::new(libc::EINVAL, "invalid fallback_libos in config")),
+                })
+                .collect(),
+            Yaml::BadValue | Yaml::Null => Ok(Vec::new()),
+            _ => Err(Fail::new(libc::EINVAL, "invalid fallback_libos in config")),
+        }
+    }
+
     /// Reads the local IPv4 address parameter from the underlying configuration file.
     #[cfg(any(feature = "catnip-libos", feature = "catpowder-libos"))]
     pub fn local_ipv4_addr(&self) -> ::std::net::Ipv4Addr {
@@ -60,3 +92,38 @@ impl Config {
         local_ipv4_addr
     }
 }
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::demikernel::libos::name::LibOSName;
+    use ::yaml_rust::YamlLoader;
+
+    #[test]
+    fn fallback_libos() {
+        let config = |s: &str| Config(YamlLoader::load_from_str(s).unwrap().remove(0));
+        assert!(config("demikernel: {}\n").fallback_libos().unwrap().is_empty());
+        assert_eq!(
+            config("demikernel:\n  fallback_libos: \"catnap\"\n")
+                .fallback_libos()
+                .unwrap(),
+            [LibOSName::Catnap]
+        );
+        assert_eq!(
+            config("demikernel:\n  fallback_libos: [\"catpowder\", \"catnap\"]\n")
+                .fallback_libos()
+                .unwrap(),
+            [LibOSName::Catpowder, LibOSName::Catnap]
+        );
+
+        // Unknown names are rejected up front.
+        assert!(config("demikernel:\n  fallback_libos: [\"catnap\", \"catmint\"]\n")
+            .fallback_libos()
+            .is_err());
+        assert!(LibOSName::try_from("catmint".to_string()).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use super::LibOS;
+use crate::runtime::{
+    fail::{
+        ErrorKind,
+        Fail,
+    },
+    types::{
+        demi_qresult_t,
+        demi_sgarray_t,
+    },
+    QDesc,
+    QToken,
+};
+use ::futures::task::noop_waker_ref;
+use ::std::{
+    cell::RefCell,
+    future::Future,
+    net::SocketAddrV4,
+    pin::Pin,
+    rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Asynchronous LibOS
+///
+/// Wraps a [LibOS] so that I/O operations return futures instead of queue tokens. Futures drive the underlying
+/// scheduler whenever they are polled, so they make progress under any executor, including [AsyncLibOS::block_on].
+#[derive(Clone)]
+pub struct AsyncLibOS {
+    /// Underlying LibOS.
+    libos: Rc<RefCell<LibOS>>,
+}
+
+/// Operation Future
+///
+/// Completes with the result of the operation associated with a queue token. Dropping an operation future before it
+/// completes cancels the operation.
+pub struct OperationFuture {
+    /// Underlying LibOS.
+    libos: Rc<RefCell<LibOS>>,
+    /// Queue token of the operation, until it completes.
+    qt: Option<QToken>,
+}
+
+//======================================================================================================================
+// Associate Functions
+//======================================================================================================================
+
+/// Associate Functions for Asynchronous LibOSes
+impl AsyncLibOS {
+    /// Wraps a LibOS.
+    pub fn new(libos: LibOS) -> Self {
+        Self {
+            libos: Rc::new(RefCell::new(libos)),
+        }
+    }
+
+    /// Creates a socket.
+    pub fn socket(&self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        self.libos.borrow_mut().socket(domain, socket_type, protocol)
+    }
+
+    /// Binds a socket to a local address.
+    pub fn bind(&self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+        self.libos.borrow_mut().bind(sockqd, local)
+    }
+
+    /// Marks a socket as a passive one.
+    pub fn listen(&self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        self.libos.borrow_mut().listen(sockqd, backlog)
+    }
+
+    /// Accepts an incoming connection on a TCP socket.
+    pub fn accept(&self, sockqd: QDesc) -> Result<OperationFuture, Fail> {
+        let qt: QToken = self.libos.borrow_mut().accept(sockqd)?;
+        Ok(self.future(qt))
+    }
+
+    /// Initiates a connection with a remote TCP peer.
+    pub fn connect(&self, sockqd: QDesc, remote: SocketAddrV4) -> Result<OperationFuture, Fail> {
+        let qt: QToken = self.libos.borrow_mut().connect(sockqd, remote)?;
+        Ok(self.future(qt))
+    }
+
+    /// Pushes a scatter-gather array to a TCP socket.
+    pub fn push(&self, qd: QDesc, sga: &demi_sgarray_t) -> Result<OperationFuture, Fail> {
+        let qt: QToken = self.libos.borrow_mut().push(qd, sga)?;
+        Ok(self.future(qt))
+    }
+
+    /// Pushes a scatter-gather array to a UDP socket.
+    pub fn pushto(&self, qd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<OperationFuture, Fail> {
+        let qt: QToken = self.libos.borrow_mut().pushto(qd, sga, to)?;
+        Ok(self.future(qt))
+    }
+
+    /// Pops data from a socket.
+    pub fn pop(&self, qd: QDesc) -> Result<OperationFuture, Fail> {
+        let qt: QToken = self.libos.borrow_mut().pop(qd)?;
+        Ok(self.future(qt))
+    }
+
+    /// Closes a socket.
+    pub fn close(&self, qd: QDesc) -> Result<(), Fail> {
+        self.libos.borrow_mut().close(qd)
+    }
+
+    /// Allocates a scatter-gather array.
+    pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        self.libos.borrow().sgaalloc(size)
+    }
+
+    /// Releases a scatter-gather array.
+    pub fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        self.libos.borrow().sgafree(sga)
+    }
+
+    /// Runs a future to completion on the calling thread. Applications that bring their own executor do not need this.
+    ///
+    /// The future is busy-polled, in the same way that [LibOS::wait] busy-polls the scheduler: kernel-bypass LibOSes
+    /// have no interrupts to wake a sleeping thread up, so I/O only makes progress while the scheduler is polled. Each
+    /// poll of an [OperationFuture] drives the scheduler once, so the calling thread spins until the future completes.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let mut future: Pin<Box<F>> = Box::pin(future);
+        let mut ctx: Context = Context::from_waker(noop_waker_ref());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut ctx) {
+                return output;
+            }
+        }
+    }
+
+    /// Wraps a queue token in a future.
+    fn future(&self, qt: QToken) -> OperationFuture {
+        OperationFuture {
+            libos: self.libos.clone(),
+            qt: Some(qt),
+        }
+    }
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Future Trait Implementation for Operation Futures
+impl Future for OperationFuture {
+    type Output = Result<demi_qresult_t, Fail>;
+
+    /// Polls the target [OperationFuture]. This drives the scheduler of the underlying LibOS once.
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let self_: &mut OperationFuture = self.get_mut();
+        let qt: QToken = match self_.qt {
+            Some(qt) => qt,
+            None => return Poll::Ready(Err(Fail::new(libc::EINVAL, "operation already completed"))),
+        };
+
+        // Without a deadline, timedwait() does not block and leaves the operation pending.
+        match self_.libos.borrow_mut().timedwait(qt, None) {
+            Err(e) if e.kind() == ErrorKind::TimedOut => {
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            },
+            result => {
+                self_.qt = None;
+                Poll::Ready(result)
+            },
+        }
+    }
+}
+
+/// Drop Trait Implementation for Operation Futures
+impl Drop for OperationFuture {
+    fn drop(&mut self) {
+        if let Some(qt) = self.qt.take() {
+            if let Err(e) = self.libos.borrow_mut().cancel(qt) {
+                warn!("failed to cancel operation (qt={:?}): {:?}", qt, e);
+            }
+        }
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(all(test, feature = "catnap-libos"))]
+mod tests {
+    use super::{
+        AsyncLibOS,
+        OperationFuture,
+    };
+    use crate::{
+        catnap::CatnapLibOS,
+        demikernel::{
+            config::Config,
+            libos::{
+                network::NetworkLibOS,
+                LibOS,
+            },
+        },
+        runtime::{
+            fail::Fail,
+            types::{
+                demi_opcode_t,
+                demi_qresult_t,
+                demi_sgarray_t,
+            },
+            QDesc,
+            QToken,
+        },
+    };
+    use ::futures::task::noop_waker_ref;
+    use ::std::{
+        future::Future,
+        net::{
+            Ipv4Addr,
+            SocketAddrV4,
+        },
+        pin::Pin,
+        slice,
+        task::Context,
+    };
+    use ::yaml_rust::Yaml;
+
+    /// Size of the data that is sent in each test.
+    const DATA_SIZE: usize = 64;
+
+    /// Wraps a Catnap LibOS, which runs on top of kernel sockets.
+    fn new_libos() -> AsyncLibOS {
+        let libos: CatnapLibOS = CatnapLibOS::new(&Config(Yaml::Null));
+        AsyncLibOS::new(LibOS::NetworkLibOS(NetworkLibOS::Catnap(libos)))
+    }
+
+    /// Establishes a TCP connection over the loopback interface. Returns the queue descriptors of the listening
+    /// socket, of the server end, and of the client end of the connection.
+    fn open_connection(libos: &AsyncLibOS, port: u16) -> (QDesc, QDesc, QDesc) {
+        let local: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
+        let listen_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0).unwrap();
+        libos.bind(listen_qd, local).unwrap();
+        libos.listen(listen_qd, 8).unwrap();
+        let client_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0).unwrap();
+
+        let accept: OperationFuture = libos.accept(listen_qd).unwrap();
+        let connect: OperationFuture = libos.connect(client_qd, local).unwrap();
+        let (accepted, connected): (demi_qresult_t, demi_qresult_t) =
+            libos.block_on(async { (accept.await.unwrap(), connect.await.unwrap()) });
+        assert_eq!(accepted.qr_opcode, demi_opcode_t::DEMI_OPC_ACCEPT);
+        assert_eq!(connected.qr_opcode, demi_opcode_t::DEMI_OPC_CONNECT);
+
+        let server_qd: QDesc = unsafe { accepted.qr_value.ares.qd.into() };
+        (listen_qd, server_qd, client_qd)
+    }
+
+    /// Pushes `DATA_SIZE` bytes filled with `value` to a TCP socket.
+    fn push(libos: &AsyncLibOS, qd: QDesc, value: u8) {
+        let sga: demi_sgarray_t = libos.sgaalloc(DATA_SIZE).unwrap();
+        let ptr: *mut u8 = sga.sga_segs[0].sgaseg_buf as *mut u8;
+        unsafe { slice::from_raw_parts_mut(ptr, DATA_SIZE) }.fill(value);
+        let future: OperationFuture = libos.push(qd, &sga).unwrap();
+        let qr: demi_qresult_t = libos.block_on(future).unwrap();
+        assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_PUSH);
+        libos.sgafree(sga).unwrap();
+    }
+
+    /// Pops from a TCP socket until `DATA_SIZE` bytes filled with `value` are received.
+    fn pop(libos: &AsyncLibOS, qd: QDesc, value: u8) {
+        let mut received: usize = 0;
+        while received < DATA_SIZE {
+            let future: OperationFuture = libos.pop(qd).unwrap();
+            let qr: demi_qresult_t = libos.block_on(future).unwrap();
+            assert_eq!(qr.qr_opcode, demi_opcode_t::DEMI_OPC_POP);
+            let sga: demi_sgarray_t = unsafe { qr.qr_value.sga };
+            let len: usize = sga.sga_segs[0].sgaseg_len as usize;
+            let data: &[u8] = unsafe { slice::from_raw_parts(sga.sga_segs[0].sgaseg_buf as *const u8, len) };
+            assert!(len > 0);
+            assert!(data.iter().all(|byte| *byte == value));
+            received += len;
+            libos.sgafree(sga).unwrap();
+        }
+        assert_eq!(received, DATA_SIZE);
+    }
+
+    #[test]
+    fn push_pop_loopback() {
+        let libos: AsyncLibOS = new_libos();
+        let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(&libos, 24311);
+
+        push(&libos, client_qd, 0x11);
+        pop(&libos, server_qd, 0x11);
+        push(&libos, server_qd, 0x22);
+        pop(&libos, client_qd, 0x22);
+
+        libos.close(client_qd).unwrap();
+        libos.close(server_qd).unwrap();
+        libos.close(listen_qd).unwrap();
+    }
+
+    #[test]
+    fn drop_cancels_pop() {
+        let libos: AsyncLibOS = new_libos();
+        let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(&libos, 24312);
+
+        // Nothing is sent, so the pop operation is still pending when its future is dropped.
+        let mut future: OperationFuture = libos.pop(server_qd).unwrap();
+        let mut ctx: Context = Context::from_waker(noop_waker_ref());
+        assert!(Pin::new(&mut future).poll(&mut ctx).is_pending());
+        let qt: QToken = future.qt.unwrap();
+        drop(future);
+        let e: Fail = libos.libos.borrow_mut().timedwait(qt, None).unwrap_err();
+        assert_eq!(e.errno, libc::EINVAL);
+
+        // The cancelled operation did not consume any data.
+        push(&libos, client_qd, 0x33);
+        pop(&libos, server_qd, 0x33);
+
+        libos.close(client_qd).unwrap();
+        libos.close(server_qd).unwrap();
+        libos.close(listen_qd).unwrap();
+    }
+}
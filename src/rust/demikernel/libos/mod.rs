@@ -3,6 +3,10 @@
 
 pub mod name;
 pub mod network;
+pub mod transport;
+
+#[cfg(feature = "async-api")]
+pub mod asynchronous;
 
 //======================================================================================================================
 // Imports
@@ -15,11 +19,27 @@ use self::{
         OperationResult,
     },
 };
+#[cfg(feature = "profiler")]
+use crate::perftools::profiler;
 use crate::{
     demikernel::config::Config,
+    inetstack::protocols::tcp::TcpInfo,
     runtime::{
-        fail::Fail,
+        fail::{
+            ErrorKind,
+            Fail,
+        },
         logging,
+        memory::stats::{
+            MempoolId,
+            MempoolStats,
+        },
+        network::{
+            event::DeviceEvent,
+            preamble::RequestPreamble,
+            stats::TxStats,
+            topology::Topology,
+        },
         types::{
             demi_qresult_t,
             demi_sgarray_t,
@@ -29,9 +49,16 @@ use crate::{
     },
 };
 use ::std::{
+    any::Any,
     env,
-    net::SocketAddrV4,
-    time::SystemTime,
+    net::{
+        Ipv4Addr,
+        SocketAddrV4,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
 };
 
 #[cfg(feature = "catcollar-libos")]
@@ -74,19 +101,40 @@ impl LibOS {
             },
         };
         let config: Config = Config::new(config_path);
+        let fallbacks: Vec<LibOSName> = config.fallback_libos()?;
+
+        // Instantiate LibOS. If that fails, for instance because the machine has no device that the LibOS can drive,
+        // try the configured fallbacks in order.
+        let e: Fail = match Self::instantiate(libos_name, &config) {
+            Ok(libos) => return Ok(libos),
+            Err(e) => e,
+        };
+        for fallback in fallbacks {
+            warn!(
+                "failed to instantiate {:?} LibOS ({:?}), falling back to {:?}",
+                libos_name, e.cause, fallback
+            );
+            match Self::instantiate(fallback, &config) {
+                Ok(libos) => return Ok(libos),
+                Err(e) => warn!("failed to instantiate {:?} LibOS ({:?})", fallback, e.cause),
+            }
+        }
+        Err(e)
+    }
 
-        // Instantiate LibOS.
+    /// Instantiates a LibOS without falling back to another one.
+    fn instantiate(libos_name: LibOSName, config: &Config) -> Result<Self, Fail> {
         #[allow(unreachable_patterns)]
         let libos: LibOS = match libos_name {
             #[cfg(feature = "catnap-libos")]
-            LibOSName::Catnap => Self::NetworkLibOS(NetworkLibOS::Catnap(CatnapLibOS::new(&config))),
+            LibOSName::Catnap => Self::NetworkLibOS(NetworkLibOS::Catnap(CatnapLibOS::new(config))),
             #[cfg(feature = "catcollar-libos")]
-            LibOSName::Catcollar => Self::NetworkLibOS(NetworkLibOS::Catcollar(CatcollarLibOS::new(&config))),
+            LibOSName::Catcollar => Self::NetworkLibOS(NetworkLibOS::Catcollar(CatcollarLibOS::new(config))),
             #[cfg(feature = "catpowder-libos")]
-            LibOSName::Catpowder => Self::NetworkLibOS(NetworkLibOS::Catpowder(CatpowderLibOS::new(&config))),
+            LibOSName::Catpowder => Self::NetworkLibOS(NetworkLibOS::Catpowder(CatpowderLibOS::new(config))),
             #[cfg(feature = "catnip-libos")]
-            LibOSName::Catnip => Self::NetworkLibOS(NetworkLibOS::Catnip(CatnipLibOS::new(&config))),
-            _ => panic!("unsupported libos"),
+            LibOSName::Catnip => Self::NetworkLibOS(NetworkLibOS::Catnip(CatnipLibOS::new(config)?)),
+            _ => return Err(Fail::new(libc::ENOTSUP, "unsupported libos")),
         };
 
         Ok(libos)
@@ -96,7 +144,11 @@ impl LibOS {
     #[deprecated]
     pub fn wait_any2(&mut self, qts: &[QToken]) -> Result<(usize, QDesc, OperationResult), Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.wait_any2(qts),
+            LibOS::NetworkLibOS(libos) => {
+                let (i, qd, result): (usize, QDesc, OperationResult) = libos.wait_any2(qts)?;
+                completed(qts[i]);
+                Ok((i, qd, result))
+            },
         }
     }
 
@@ -104,7 +156,11 @@ impl LibOS {
     #[deprecated]
     pub fn wait2(&mut self, qt: QToken) -> Result<(QDesc, OperationResult), Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.wait2(qt),
+            LibOS::NetworkLibOS(libos) => {
+                let (qd, result): (QDesc, OperationResult) = libos.wait2(qt)?;
+                completed(qt);
+                Ok((qd, result))
+            },
         }
     }
 
@@ -127,6 +183,95 @@ impl LibOS {
         }
     }
 
+    /// Turns a UDP socket into the default socket, which receives datagrams addressed to ports that are not bound.
+    pub fn bind_default(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.bind_default(sockqd),
+        }
+    }
+
+    /// Returns a description of the datapath instantiated by the target LibOS.
+    pub fn topology(&self) -> Topology {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.topology(),
+        }
+    }
+
+    /// Returns the events reported by the network device since the last call, such as the link going down or frames
+    /// being dropped. Connections are not failed when the link goes down, since TCP recovers from short outages on its
+    /// own. Applications that prefer to give up on them right away may close them upon [DeviceEvent::LinkDown].
+    pub fn poll_events(&self) -> Vec<DeviceEvent> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.poll_events(),
+        }
+    }
+
+    /// Returns how many packets were sent, and how many payload bytes were sent without being copied or were copied
+    /// and why. LibOSes other than Catnip return `ENOTSUP`.
+    pub fn tx_stats(&self) -> Result<TxStats, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.tx_stats(),
+        }
+    }
+
+    /// Resets the transmit statistics.
+    pub fn reset_tx_stats(&self) -> Result<(), Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.reset_tx_stats(),
+        }
+    }
+
+    /// Returns how many buffers of a memory pool are allocated and how many are free. LibOSes other than Catnip return
+    /// `ENOTSUP`.
+    pub fn pool_stats(&self, id: MempoolId) -> Result<MempoolStats, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.pool_stats(id),
+        }
+    }
+
+    /// Attaches an application context, such as per-connection state, to a queue descriptor. The context is dropped
+    /// when the queue descriptor is closed.
+    pub fn set_context(&mut self, qd: QDesc, context: Box<dyn Any>) -> Result<(), Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.set_context(qd, context),
+        }
+    }
+
+    /// Gets the application context attached to a queue descriptor.
+    pub fn get_context(&self, qd: QDesc) -> Result<Option<&dyn Any>, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.get_context(qd),
+        }
+    }
+
+    /// Gets a mutable reference to the application context attached to a queue descriptor.
+    pub fn get_context_mut(&mut self, qd: QDesc) -> Result<Option<&mut dyn Any>, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.get_context_mut(qd),
+        }
+    }
+
+    /// Takes a snapshot of the transport state of a TCP connection.
+    pub fn tcp_info(&self, sockqd: QDesc) -> Result<TcpInfo, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.tcp_info(sockqd),
+        }
+    }
+
+    /// Gets the send buffer size (in bytes) of a TCP socket.
+    pub fn get_send_buffer_size(&self, sockqd: QDesc) -> Result<usize, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.get_send_buffer_size(sockqd),
+        }
+    }
+
+    /// Sets the send buffer size (in bytes) of a TCP socket.
+    pub fn set_send_buffer_size(&mut self, sockqd: QDesc, size: usize) -> Result<(), Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.set_send_buffer_size(sockqd, size),
+        }
+    }
+
     /// Marks a socket as a passive one.
     pub fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
         match self {
@@ -137,14 +282,14 @@ impl LibOS {
     /// Accepts an incoming connection on a TCP socket.
     pub fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.accept(sockqd),
+            LibOS::NetworkLibOS(libos) => submitted("accept", libos.accept(sockqd)),
         }
     }
 
     /// Initiates a connection with a remote TCP pper.
     pub fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.connect(sockqd, remote),
+            LibOS::NetworkLibOS(libos) => submitted("connect", libos.connect(sockqd, remote)),
         }
     }
 
@@ -158,7 +303,7 @@ impl LibOS {
     /// Pushes a scatter-gather array to a TCP socket.
     pub fn push(&mut self, qd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.push(qd, sga),
+            LibOS::NetworkLibOS(libos) => submitted("push", libos.push(qd, sga)),
         }
     }
 
@@ -166,14 +311,60 @@ impl LibOS {
     #[deprecated]
     pub fn push2(&mut self, qd: QDesc, data: &[u8]) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.push2(qd, data),
+            LibOS::NetworkLibOS(libos) => submitted("push", libos.push2(qd, data)),
+        }
+    }
+
+    /// Pushes data that is scattered across several slices, such as a header and a body, to a TCP socket. The slices
+    /// are copied, so they may be reused as soon as this returns.
+    pub fn push_slices(&mut self, qd: QDesc, slices: &[&[u8]]) -> Result<QToken, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => submitted("push", libos.push_slices(qd, slices)),
+        }
+    }
+
+    /// Pushes raw data to a TCP socket, optionally prefixed with a request preamble. The data is copied once, so it may
+    /// be reused as soon as this returns. LibOSes other than Catnip return `ENOTSUP`.
+    pub fn push_slice(&mut self, qd: QDesc, preamble: Option<&RequestPreamble>, data: &[u8]) -> Result<QToken, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => submitted("push", libos.push_slice(qd, preamble, data)),
+        }
+    }
+
+    /// Pushes the same scatter-gather array to several TCP sockets, such as the subscribers of a topic. Pushes are issued
+    /// in the order of `qds`, and one result is returned for each of them, so that a failure on one socket does not
+    /// prevent delivery to the others. The scatter-gather array may be released once this returns.
+    pub fn push_broadcast(&mut self, qds: &[QDesc], sga: &demi_sgarray_t) -> Result<Vec<Result<QToken, Fail>>, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => Ok(libos
+                .push_broadcast(qds, sga)?
+                .into_iter()
+                .map(|result| submitted("push", result))
+                .collect()),
+        }
+    }
+
+    /// Pushes static data, such as a canned response, to a TCP socket.
+    pub fn push_static(&mut self, qd: QDesc, data: &'static [u8]) -> Result<QToken, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => submitted("push", libos.push_static(qd, data)),
+        }
+    }
+
+    /// Pushes several scatter-gather arrays to a TCP socket with a single operation. The queue token completes once all
+    /// of them have been handed to the TCP stack, in order. If they do not all fit in the send buffer, the operation
+    /// fails and none of them is sent. The scatter-gather arrays may be released once this returns. LibOSes that are not
+    /// built on the Demikernel network stack return `ENOTSUP`.
+    pub fn push_batch(&mut self, qd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => submitted("push", libos.push_batch(qd, sgas)),
         }
     }
 
     /// Pushes a scatter-gather array to a UDP socket.
     pub fn pushto(&mut self, qd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.pushto(qd, sga, to),
+            LibOS::NetworkLibOS(libos) => submitted("pushto", libos.pushto(qd, sga, to)),
         }
     }
 
@@ -181,35 +372,97 @@ impl LibOS {
     #[deprecated]
     pub fn pushto2(&mut self, qd: QDesc, data: &[u8], remote: SocketAddrV4) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.pushto2(qd, data, remote),
+            LibOS::NetworkLibOS(libos) => submitted("pushto", libos.pushto2(qd, data, remote)),
         }
     }
 
     /// Pops data from a socket.
     pub fn pop(&mut self, qd: QDesc) -> Result<QToken, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.pop(qd),
+            LibOS::NetworkLibOS(libos) => submitted("pop", libos.pop(qd)),
+        }
+    }
+
+    /// Peeks data from a socket, without consuming it.
+    pub fn peek(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => submitted("peek", libos.peek(qd)),
         }
     }
 
     /// Waits for a pending operation in an I/O queue.
     pub fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.wait(qt),
+            LibOS::NetworkLibOS(libos) => {
+                let qr: demi_qresult_t = libos.wait(qt)?;
+                completed(qr.qr_qt.into());
+                Ok(qr)
+            },
         }
     }
 
     /// Waits for an I/O operation to complete or a timeout to expire.
     pub fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.timedwait(qt, abstime),
+            LibOS::NetworkLibOS(libos) => match libos.timedwait(qt, abstime) {
+                Ok(qr) => {
+                    completed(qt);
+                    Ok(qr)
+                },
+                // An expired deadline cancels the operation on some LibOSes.
+                Err(e) if e.kind() == ErrorKind::TimedOut && abstime.is_some() && libos.cancels_on_timeout() => {
+                    cancelled(qt);
+                    Err(e)
+                },
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    /// Cancels a pending operation in an I/O queue.
+    pub fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => {
+                libos.cancel(qt)?;
+                cancelled(qt);
+                Ok(())
+            },
+        }
+    }
+
+    /// Sends an ICMP echo request to a remote host and returns the round-trip time.
+    pub fn ping(&mut self, addr: Ipv4Addr, timeout: Option<Duration>) -> Result<Duration, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.ping(addr, timeout),
         }
     }
 
     /// Waits for any operation in an I/O queue.
     pub fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
         match self {
-            LibOS::NetworkLibOS(libos) => libos.wait_any(qts),
+            LibOS::NetworkLibOS(libos) => {
+                let (i, qr): (usize, demi_qresult_t) = libos.wait_any(qts)?;
+                completed(qr.qr_qt.into());
+                Ok((i, qr))
+            },
+        }
+    }
+
+    /// Waits for some operations in an I/O queue to complete, returning up to `max_results` of them at once.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        match self {
+            LibOS::NetworkLibOS(libos) => {
+                let qrs: Vec<(usize, demi_qresult_t)> = libos.wait_many(qts, max_results, abstime)?;
+                for (_, qr) in &qrs {
+                    completed(qr.qr_qt.into());
+                }
+                Ok(qrs)
+            },
         }
     }
 
@@ -226,4 +479,57 @@ impl LibOS {
             LibOS::NetworkLibOS(libos) => libos.sgafree(sga),
         }
     }
+
+    /// Checks if scatter-gather arrays allocated with [LibOS::sgaalloc] are handed to the device without being copied.
+    pub fn supports_zero_copy(&self) -> bool {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.supports_zero_copy(),
+        }
+    }
+
+    /// Checks if push and pop operations take scatter-gather arrays.
+    pub fn supports_sga(&self) -> bool {
+        match self {
+            LibOS::NetworkLibOS(libos) => libos.supports_sga(),
+        }
+    }
+}
+
+//======================================================================================================================
+// Standalone Functions
+//======================================================================================================================
+
+/// Records the submission of an operation, so that the profiler measures its latency under `opcode`.
+#[cfg(feature = "profiler")]
+fn submitted(opcode: &'static str, result: Result<QToken, Fail>) -> Result<QToken, Fail> {
+    if let Ok(qt) = result {
+        profiler::operation_submitted(qt.into(), opcode);
+    }
+    result
 }
+
+/// Records the submission of an operation, so that the profiler measures its latency under `opcode`.
+#[cfg(not(feature = "profiler"))]
+fn submitted(_opcode: &'static str, result: Result<QToken, Fail>) -> Result<QToken, Fail> {
+    result
+}
+
+/// Records the completion of an operation, successful or not.
+#[cfg(feature = "profiler")]
+fn completed(qt: QToken) {
+    profiler::operation_completed(qt.into());
+}
+
+/// Records the completion of an operation, successful or not.
+#[cfg(not(feature = "profiler"))]
+fn completed(_qt: QToken) {}
+
+/// Records the cancellation of an operation, which will never complete.
+#[cfg(feature = "profiler")]
+fn cancelled(qt: QToken) {
+    profiler::operation_cancelled(qt.into());
+}
+
+/// Records the cancellation of an operation, which will never complete.
+#[cfg(not(feature = "profiler"))]
+fn cancelled(_qt: QToken) {}
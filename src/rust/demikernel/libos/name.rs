@@ -6,13 +6,17 @@
 //======================================================================================================================
 
 use crate::runtime::fail::Fail;
-use ::std::env;
+use ::std::{
+    env,
+    str::FromStr,
+};
 
 //======================================================================================================================
 // Structures
 //======================================================================================================================
 
 /// Names of LibOSes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum LibOSName {
     Catpowder,
     Catnap,
@@ -28,7 +32,7 @@ pub enum LibOSName {
 impl LibOSName {
     pub fn from_env() -> Result<Self, Fail> {
         match env::var("LIBOS") {
-            Ok(name) => Ok(name.into()),
+            Ok(name) => LibOSName::try_from(name),
             Err(_) => Err(Fail::new(libc::EINVAL, "missing value for LIBOS environment variable")),
         }
     }
@@ -39,14 +43,25 @@ impl LibOSName {
 //======================================================================================================================
 
 /// Conversion trait implementation for LibOSName.
-impl From<String> for LibOSName {
-    fn from(str: String) -> Self {
+impl TryFrom<String> for LibOSName {
+    type Error = Fail;
+
+    fn try_from(str: String) -> Result<Self, Fail> {
+        str.parse()
+    }
+}
+
+/// Parsing trait implementation for LibOSName.
+impl FromStr for LibOSName {
+    type Err = Fail;
+
+    fn from_str(str: &str) -> Result<Self, Fail> {
         match str.to_lowercase().as_str() {
-            "catpowder" => LibOSName::Catpowder,
-            "catnap" => LibOSName::Catnap,
-            "catcollar" => LibOSName::Catcollar,
-            "catnip" => LibOSName::Catnip,
-            _ => panic!("unkown libos"),
+            "catpowder" => Ok(LibOSName::Catpowder),
+            "catnap" => Ok(LibOSName::Catnap),
+            "catcollar" => Ok(LibOSName::Catcollar),
+            "catnip" => Ok(LibOSName::Catnip),
+            _ => Err(Fail::new(libc::EINVAL, "unknown libos")),
         }
     }
 }
@@ -5,18 +5,39 @@
 // Imports
 //======================================================================================================================
 
-use crate::runtime::{
-    fail::Fail,
-    types::{
-        demi_qresult_t,
-        demi_sgarray_t,
+use super::transport::NetworkTransport;
+use crate::{
+    inetstack::protocols::tcp::TcpInfo,
+    runtime::{
+        fail::Fail,
+        memory::stats::{
+            MempoolId,
+            MempoolStats,
+        },
+        network::{
+            event::DeviceEvent,
+            preamble::RequestPreamble,
+            stats::TxStats,
+            topology::Topology,
+        },
+        types::{
+            demi_qresult_t,
+            demi_sgarray_t,
+        },
+        QDesc,
+        QToken,
     },
-    QDesc,
-    QToken,
 };
 use ::std::{
-    net::SocketAddrV4,
-    time::SystemTime,
+    any::Any,
+    net::{
+        Ipv4Addr,
+        SocketAddrV4,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
 };
 
 #[cfg(feature = "catcollar-libos")]
@@ -119,6 +140,212 @@ impl NetworkLibOS {
         }
     }
 
+    /// Turns a UDP socket into the default socket, which receives datagrams addressed to ports that are not bound.
+    pub fn bind_default(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.bind_default(sockqd),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "default socket not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "default socket not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.bind_default(sockqd),
+        }
+    }
+
+    /// Returns a description of the datapath instantiated by the target LibOS.
+    pub fn topology(&self) -> Topology {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.topology(),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.topology(),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.topology(),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.topology(),
+        }
+    }
+
+    /// Returns the device events that occurred since the last call. Kernel-based LibOSes leave the device to the kernel
+    /// and never report any.
+    pub fn poll_events(&self) -> Vec<DeviceEvent> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(_) => Vec::new(),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => Vec::new(),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => Vec::new(),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.poll_events(),
+        }
+    }
+
+    /// Returns the transmit statistics collected so far.
+    pub fn tx_stats(&self) -> Result<TxStats, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(_) => Err(Fail::new(libc::ENOTSUP, "transmit statistics not supported")),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => Err(Fail::new(libc::ENOTSUP, "transmit statistics not supported")),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => Err(Fail::new(libc::ENOTSUP, "transmit statistics not supported")),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => Ok(libos.tx_stats()),
+        }
+    }
+
+    /// Resets the transmit statistics.
+    pub fn reset_tx_stats(&self) -> Result<(), Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(_) => Err(Fail::new(libc::ENOTSUP, "transmit statistics not supported")),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => Err(Fail::new(libc::ENOTSUP, "transmit statistics not supported")),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => Err(Fail::new(libc::ENOTSUP, "transmit statistics not supported")),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => {
+                libos.reset_tx_stats();
+                Ok(())
+            },
+        }
+    }
+
+    /// Returns the utilization of a memory pool.
+    pub fn pool_stats(&self, id: MempoolId) -> Result<MempoolStats, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(_) => {
+                let _ = id;
+                Err(Fail::new(libc::ENOTSUP, "memory pool statistics not supported"))
+            },
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = id;
+                Err(Fail::new(libc::ENOTSUP, "memory pool statistics not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = id;
+                Err(Fail::new(libc::ENOTSUP, "memory pool statistics not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => Ok(libos.pool_stats(id)),
+        }
+    }
+
+    /// Attaches an application context to a queue descriptor. The context is dropped when the queue descriptor is
+    /// closed.
+    pub fn set_context(&mut self, qd: QDesc, context: Box<dyn Any>) -> Result<(), Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.set_context(qd, context),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.set_context(qd, context),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.set_context(qd, context),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.set_context(qd, context),
+        }
+    }
+
+    /// Gets the application context attached to a queue descriptor.
+    pub fn get_context(&self, qd: QDesc) -> Result<Option<&dyn Any>, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.get_context(qd),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.get_context(qd),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.get_context(qd),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.get_context(qd),
+        }
+    }
+
+    /// Gets a mutable reference to the application context attached to a queue descriptor.
+    pub fn get_context_mut(&mut self, qd: QDesc) -> Result<Option<&mut dyn Any>, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.get_context_mut(qd),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.get_context_mut(qd),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.get_context_mut(qd),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.get_context_mut(qd),
+        }
+    }
+
+    /// Takes a snapshot of the transport state of a TCP connection.
+    pub fn tcp_info(&self, sockqd: QDesc) -> Result<TcpInfo, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.tcp_info(sockqd),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "tcp info not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "tcp info not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.tcp_info(sockqd),
+        }
+    }
+
+    /// Gets the send buffer size (in bytes) of a TCP socket.
+    pub fn get_send_buffer_size(&self, sockqd: QDesc) -> Result<usize, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.get_send_buffer_size(sockqd),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "send buffer size not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "send buffer size not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.get_send_buffer_size(sockqd),
+        }
+    }
+
+    /// Sets the send buffer size (in bytes) of a TCP socket.
+    pub fn set_send_buffer_size(&mut self, sockqd: QDesc, size: usize) -> Result<(), Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.set_send_buffer_size(sockqd, size),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = (sockqd, size);
+                Err(Fail::new(libc::ENOTSUP, "send buffer size not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = (sockqd, size);
+                Err(Fail::new(libc::ENOTSUP, "send buffer size not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.set_send_buffer_size(sockqd, size),
+        }
+    }
+
     /// Marks a socket as a passive one.
     pub fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
         match self {
@@ -204,6 +431,102 @@ impl NetworkLibOS {
         }
     }
 
+    /// Pushes data that is scattered across several slices to a TCP socket.
+    pub fn push_slices(&mut self, sockqd: QDesc, slices: &[&[u8]]) -> Result<QToken, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.push_slices(sockqd, slices),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.push_slices(sockqd, slices),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.push_slices(sockqd, slices),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.push_slices(sockqd, slices),
+        }
+    }
+
+    /// Pushes raw data to a TCP socket, optionally prefixed with a request preamble, with a single copy.
+    pub fn push_slice(
+        &mut self,
+        sockqd: QDesc,
+        preamble: Option<&RequestPreamble>,
+        data: &[u8],
+    ) -> Result<QToken, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(_) => {
+                let _ = (sockqd, preamble, data);
+                Err(Fail::new(libc::ENOTSUP, "push slice not supported"))
+            },
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = (sockqd, preamble, data);
+                Err(Fail::new(libc::ENOTSUP, "push slice not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = (sockqd, preamble, data);
+                Err(Fail::new(libc::ENOTSUP, "push slice not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.push_slice(sockqd, preamble, data),
+        }
+    }
+
+    /// Pushes static data to a TCP socket. LibOSes that cannot send it without a copy fall back to copying it on every
+    /// push.
+    pub fn push_static(&mut self, sockqd: QDesc, data: &'static [u8]) -> Result<QToken, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.push2(sockqd, data),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.push2(sockqd, data),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.push2(sockqd, data),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.push_static(sockqd, data),
+        }
+    }
+
+    /// Pushes the same scatter-gather array to several TCP sockets. LibOSes that cannot share the payload between
+    /// connections fall back to pushing it to each socket in turn.
+    pub fn push_broadcast(
+        &mut self,
+        sockqds: &[QDesc],
+        sga: &demi_sgarray_t,
+    ) -> Result<Vec<Result<QToken, Fail>>, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => Ok(sockqds.iter().map(|sockqd| libos.push(*sockqd, sga)).collect()),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => Ok(sockqds.iter().map(|sockqd| libos.push(*sockqd, sga)).collect()),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => Ok(sockqds.iter().map(|sockqd| libos.push(*sockqd, sga)).collect()),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.push_broadcast(sockqds, sga),
+        }
+    }
+
+    /// Pushes several scatter-gather arrays to a TCP socket with a single operation.
+    pub fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.push_batch(sockqd, sgas),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = (sockqd, sgas);
+                Err(Fail::new(libc::ENOTSUP, "push batch not supported"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = (sockqd, sgas);
+                Err(Fail::new(libc::ENOTSUP, "push batch not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.push_batch(sockqd, sgas),
+        }
+    }
+
     /// Pushes a scatter-gather array to a UDP socket.
     pub fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
         match self {
@@ -247,6 +570,23 @@ impl NetworkLibOS {
         }
     }
 
+    /// Peeks data from a socket, without consuming it.
+    pub fn peek(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.peek(sockqd),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.peek(sockqd),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = sockqd;
+                Err(Fail::new(libc::ENOTSUP, "peek not supported"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.peek(sockqd),
+        }
+    }
+
     /// Waits for a pending operation in an I/O queue.
     pub fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
         match self {
@@ -275,6 +615,84 @@ impl NetworkLibOS {
         }
     }
 
+    /// Cancels a pending operation in an I/O queue.
+    pub fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.cancel(qt),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.cancel(qt),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.cancel(qt),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.cancel(qt),
+        }
+    }
+
+    /// Checks if the target LibOS cancels operations whose deadline expires in [NetworkLibOS::timedwait]. Otherwise,
+    /// they are left pending.
+    pub fn cancels_on_timeout(&self) -> bool {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(_) => true,
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => true,
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => false,
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(_) => true,
+        }
+    }
+
+    /// Checks if scatter-gather arrays allocated with [NetworkLibOS::sgaalloc] are handed to the device without being
+    /// copied.
+    pub fn supports_zero_copy(&self) -> bool {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => NetworkTransport::supports_zero_copy(libos),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => NetworkTransport::supports_zero_copy(libos),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => NetworkTransport::supports_zero_copy(libos),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => NetworkTransport::supports_zero_copy(libos),
+        }
+    }
+
+    /// Checks if push and pop operations take scatter-gather arrays.
+    pub fn supports_sga(&self) -> bool {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => NetworkTransport::supports_sga(libos),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => NetworkTransport::supports_sga(libos),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => NetworkTransport::supports_sga(libos),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => NetworkTransport::supports_sga(libos),
+        }
+    }
+
+    /// Sends an ICMP echo request to a remote host and returns the round-trip time.
+    pub fn ping(&mut self, addr: Ipv4Addr, timeout: Option<Duration>) -> Result<Duration, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.ping(addr, timeout),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(_) => {
+                let _ = (addr, timeout);
+                Err(Fail::new(libc::ENOTSUP, "ping is not supported on this libos"))
+            },
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(_) => {
+                let _ = (addr, timeout);
+                Err(Fail::new(libc::ENOTSUP, "ping is not supported on this libos"))
+            },
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.ping(addr, timeout),
+        }
+    }
+
     /// Waits for any operation in an I/O queue.
     pub fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
         match self {
@@ -289,6 +707,25 @@ impl NetworkLibOS {
         }
     }
 
+    /// Waits for some operations in an I/O queue to complete, returning up to `max_results` of them at once.
+    pub fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        match self {
+            #[cfg(feature = "catpowder-libos")]
+            NetworkLibOS::Catpowder(libos) => libos.wait_many(qts, max_results, abstime),
+            #[cfg(feature = "catnap-libos")]
+            NetworkLibOS::Catnap(libos) => libos.wait_many(qts, max_results, abstime),
+            #[cfg(feature = "catcollar-libos")]
+            NetworkLibOS::Catcollar(libos) => libos.wait_many(qts, max_results, abstime),
+            #[cfg(feature = "catnip-libos")]
+            NetworkLibOS::Catnip(libos) => libos.wait_many(qts, max_results, abstime),
+        }
+    }
+
     /// Allocates a scatter-gather array.
     pub fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
         match self {
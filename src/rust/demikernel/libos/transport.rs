@@ -0,0 +1,555 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use super::LibOS;
+use crate::runtime::{
+    fail::Fail,
+    types::{
+        demi_qresult_t,
+        demi_sgarray_t,
+    },
+    QDesc,
+    QToken,
+};
+use ::std::{
+    net::SocketAddrV4,
+    time::SystemTime,
+};
+
+#[cfg(feature = "catcollar-libos")]
+use crate::catcollar::CatcollarLibOS;
+#[cfg(feature = "catnap-libos")]
+use crate::catnap::CatnapLibOS;
+#[cfg(feature = "catnip-libos")]
+use crate::catnip::CatnipLibOS;
+#[cfg(feature = "catpowder-libos")]
+use crate::catpowder::CatpowderLibOS;
+#[cfg(any(feature = "catnip-libos", feature = "catpowder-libos"))]
+use crate::inetstack::InetStack;
+
+//======================================================================================================================
+// Traits
+//======================================================================================================================
+
+/// Network Transport
+///
+/// Common interface that is exposed by all network LibOSes. Applications may hold a `&mut dyn NetworkTransport` to
+/// stay agnostic of the LibOS that was compiled in, and query capabilities at runtime to pick a data path. It is also
+/// implemented by [LibOS], which dispatches each call to the LibOS that was selected at runtime.
+pub trait NetworkTransport {
+    /// Creates a socket.
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail>;
+
+    /// Binds a socket to a local address.
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail>;
+
+    /// Marks a socket as a passive one.
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail>;
+
+    /// Accepts an incoming connection on a TCP socket.
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail>;
+
+    /// Initiates a connection with a remote TCP peer.
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail>;
+
+    /// Closes a socket.
+    fn close(&mut self, sockqd: QDesc) -> Result<(), Fail>;
+
+    /// Pushes a scatter-gather array to a TCP socket.
+    fn push(&mut self, sockqd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail>;
+
+    /// Pushes several scatter-gather arrays to a TCP socket with a single operation. Either all of them are sent or
+    /// none of them is. LibOSes that are not built on the Demikernel network stack return `ENOTSUP`.
+    fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail>;
+
+    /// Pushes the same scatter-gather array to several TCP sockets, returning one result for each of them. Unless the
+    /// LibOS can share the payload between connections, it is pushed to each socket in turn.
+    fn push_broadcast(&mut self, sockqds: &[QDesc], sga: &demi_sgarray_t) -> Result<Vec<Result<QToken, Fail>>, Fail> {
+        Ok(sockqds.iter().map(|sockqd| self.push(*sockqd, sga)).collect())
+    }
+
+    /// Pushes a scatter-gather array to a UDP socket.
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail>;
+
+    /// Pops data from a socket.
+    fn pop(&mut self, sockqd: QDesc) -> Result<QToken, Fail>;
+
+    /// Waits for a pending operation in an I/O queue.
+    fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail>;
+
+    /// Waits for an I/O operation to complete or a timeout to expire.
+    fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail>;
+
+    /// Cancels a pending operation in an I/O queue.
+    fn cancel(&mut self, qt: QToken) -> Result<(), Fail>;
+
+    /// Waits for any operation in an I/O queue.
+    fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail>;
+
+    /// Waits for some operations in an I/O queue to complete, returning up to `max_results` of them at once.
+    fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail>;
+
+    /// Allocates a scatter-gather array.
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail>;
+
+    /// Releases a scatter-gather array.
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail>;
+
+    /// Checks if scatter-gather arrays allocated with [NetworkTransport::sgaalloc] are handed to the device without
+    /// being copied.
+    fn supports_zero_copy(&self) -> bool;
+
+    /// Checks if push and pop operations take scatter-gather arrays.
+    fn supports_sga(&self) -> bool;
+}
+
+//======================================================================================================================
+// Trait Implementations
+//======================================================================================================================
+
+/// Network Transport Trait Implementation for Catnap LibOS
+#[cfg(feature = "catnap-libos")]
+impl NetworkTransport for CatnapLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        CatnapLibOS::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+        CatnapLibOS::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        CatnapLibOS::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatnapLibOS::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+        CatnapLibOS::connect(self, sockqd, remote)
+    }
+
+    fn close(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        CatnapLibOS::close(self, sockqd)
+    }
+
+    fn push(&mut self, sockqd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatnapLibOS::push(self, sockqd, sga)
+    }
+
+    fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        let _ = (sockqd, sgas);
+        Err(Fail::new(libc::ENOTSUP, "push batch not supported"))
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
+        CatnapLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn pop(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatnapLibOS::pop(self, sockqd)
+    }
+
+    fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        CatnapLibOS::wait(self, qt)
+    }
+
+    fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail> {
+        CatnapLibOS::timedwait(self, qt, abstime)
+    }
+
+    fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        CatnapLibOS::cancel(self, qt)
+    }
+
+    fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
+        CatnapLibOS::wait_any(self, qts)
+    }
+
+    fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        CatnapLibOS::wait_many(self, qts, max_results, abstime)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatnapLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatnapLibOS::sgafree(self, sga)
+    }
+
+    fn supports_zero_copy(&self) -> bool {
+        // Data is copied into kernel socket buffers.
+        false
+    }
+
+    fn supports_sga(&self) -> bool {
+        true
+    }
+}
+
+/// Network Transport Trait Implementation for Catcollar LibOS
+#[cfg(feature = "catcollar-libos")]
+impl NetworkTransport for CatcollarLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        CatcollarLibOS::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+        CatcollarLibOS::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        CatcollarLibOS::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatcollarLibOS::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+        CatcollarLibOS::connect(self, sockqd, remote)
+    }
+
+    fn close(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        CatcollarLibOS::close(self, sockqd)
+    }
+
+    fn push(&mut self, sockqd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatcollarLibOS::push(self, sockqd, sga)
+    }
+
+    fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        let _ = (sockqd, sgas);
+        Err(Fail::new(libc::ENOTSUP, "push batch not supported"))
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
+        CatcollarLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn pop(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        CatcollarLibOS::pop(self, sockqd)
+    }
+
+    fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        CatcollarLibOS::wait(self, qt)
+    }
+
+    fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail> {
+        CatcollarLibOS::timedwait(self, qt, abstime)
+    }
+
+    fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        CatcollarLibOS::cancel(self, qt)
+    }
+
+    fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
+        CatcollarLibOS::wait_any(self, qts)
+    }
+
+    fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        CatcollarLibOS::wait_many(self, qts, max_results, abstime)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatcollarLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatcollarLibOS::sgafree(self, sga)
+    }
+
+    fn supports_zero_copy(&self) -> bool {
+        // Data is copied into kernel socket buffers.
+        false
+    }
+
+    fn supports_sga(&self) -> bool {
+        true
+    }
+}
+
+/// Network Transport Trait Implementation for Catpowder LibOS
+#[cfg(feature = "catpowder-libos")]
+impl NetworkTransport for CatpowderLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        InetStack::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+        InetStack::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        InetStack::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        InetStack::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+        InetStack::connect(self, sockqd, remote)
+    }
+
+    fn close(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        InetStack::close(self, sockqd)
+    }
+
+    fn push(&mut self, sockqd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatpowderLibOS::push(self, sockqd, sga)
+    }
+
+    fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        CatpowderLibOS::push_batch(self, sockqd, sgas)
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
+        CatpowderLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn pop(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        InetStack::pop(self, sockqd)
+    }
+
+    fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        CatpowderLibOS::wait(self, qt)
+    }
+
+    fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail> {
+        CatpowderLibOS::timedwait(self, qt, abstime)
+    }
+
+    fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        InetStack::cancel(self, qt)
+    }
+
+    fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
+        CatpowderLibOS::wait_any(self, qts)
+    }
+
+    fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        CatpowderLibOS::wait_many(self, qts, max_results, abstime)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatpowderLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatpowderLibOS::sgafree(self, sga)
+    }
+
+    fn supports_zero_copy(&self) -> bool {
+        // Data is copied into raw socket buffers.
+        false
+    }
+
+    fn supports_sga(&self) -> bool {
+        true
+    }
+}
+
+/// Network Transport Trait Implementation for Catnip LibOS
+#[cfg(feature = "catnip-libos")]
+impl NetworkTransport for CatnipLibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        InetStack::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+        InetStack::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        InetStack::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        InetStack::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+        InetStack::connect(self, sockqd, remote)
+    }
+
+    fn close(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        InetStack::close(self, sockqd)
+    }
+
+    fn push(&mut self, sockqd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        CatnipLibOS::push(self, sockqd, sga)
+    }
+
+    fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        CatnipLibOS::push_batch(self, sockqd, sgas)
+    }
+
+    fn push_broadcast(&mut self, sockqds: &[QDesc], sga: &demi_sgarray_t) -> Result<Vec<Result<QToken, Fail>>, Fail> {
+        CatnipLibOS::push_broadcast(self, sockqds, sga)
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
+        CatnipLibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn pop(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        InetStack::pop(self, sockqd)
+    }
+
+    fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        CatnipLibOS::wait(self, qt)
+    }
+
+    fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail> {
+        CatnipLibOS::timedwait(self, qt, abstime)
+    }
+
+    fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        InetStack::cancel(self, qt)
+    }
+
+    fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
+        CatnipLibOS::wait_any(self, qts)
+    }
+
+    fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        CatnipLibOS::wait_many(self, qts, max_results, abstime)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        CatnipLibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        CatnipLibOS::sgafree(self, sga)
+    }
+
+    fn supports_zero_copy(&self) -> bool {
+        // Buffers allocated from DPDK memory pools are handed to the device as is.
+        true
+    }
+
+    fn supports_sga(&self) -> bool {
+        true
+    }
+}
+
+/// Network Transport Trait Implementation for LibOSes
+impl NetworkTransport for LibOS {
+    fn socket(&mut self, domain: libc::c_int, socket_type: libc::c_int, protocol: libc::c_int) -> Result<QDesc, Fail> {
+        LibOS::socket(self, domain, socket_type, protocol)
+    }
+
+    fn bind(&mut self, sockqd: QDesc, local: SocketAddrV4) -> Result<(), Fail> {
+        LibOS::bind(self, sockqd, local)
+    }
+
+    fn listen(&mut self, sockqd: QDesc, backlog: usize) -> Result<(), Fail> {
+        LibOS::listen(self, sockqd, backlog)
+    }
+
+    fn accept(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        LibOS::accept(self, sockqd)
+    }
+
+    fn connect(&mut self, sockqd: QDesc, remote: SocketAddrV4) -> Result<QToken, Fail> {
+        LibOS::connect(self, sockqd, remote)
+    }
+
+    fn close(&mut self, sockqd: QDesc) -> Result<(), Fail> {
+        LibOS::close(self, sockqd)
+    }
+
+    fn push(&mut self, sockqd: QDesc, sga: &demi_sgarray_t) -> Result<QToken, Fail> {
+        LibOS::push(self, sockqd, sga)
+    }
+
+    fn push_batch(&mut self, sockqd: QDesc, sgas: &[demi_sgarray_t]) -> Result<QToken, Fail> {
+        LibOS::push_batch(self, sockqd, sgas)
+    }
+
+    fn push_broadcast(&mut self, sockqds: &[QDesc], sga: &demi_sgarray_t) -> Result<Vec<Result<QToken, Fail>>, Fail> {
+        LibOS::push_broadcast(self, sockqds, sga)
+    }
+
+    fn pushto(&mut self, sockqd: QDesc, sga: &demi_sgarray_t, to: SocketAddrV4) -> Result<QToken, Fail> {
+        LibOS::pushto(self, sockqd, sga, to)
+    }
+
+    fn pop(&mut self, sockqd: QDesc) -> Result<QToken, Fail> {
+        LibOS::pop(self, sockqd)
+    }
+
+    fn wait(&mut self, qt: QToken) -> Result<demi_qresult_t, Fail> {
+        LibOS::wait(self, qt)
+    }
+
+    fn timedwait(&mut self, qt: QToken, abstime: Option<SystemTime>) -> Result<demi_qresult_t, Fail> {
+        LibOS::timedwait(self, qt, abstime)
+    }
+
+    fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        LibOS::cancel(self, qt)
+    }
+
+    fn wait_any(&mut self, qts: &[QToken]) -> Result<(usize, demi_qresult_t), Fail> {
+        LibOS::wait_any(self, qts)
+    }
+
+    fn wait_many(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, demi_qresult_t)>, Fail> {
+        LibOS::wait_many(self, qts, max_results, abstime)
+    }
+
+    fn sgaalloc(&self, size: usize) -> Result<demi_sgarray_t, Fail> {
+        LibOS::sgaalloc(self, size)
+    }
+
+    fn sgafree(&self, sga: demi_sgarray_t) -> Result<(), Fail> {
+        LibOS::sgafree(self, sga)
+    }
+
+    fn supports_zero_copy(&self) -> bool {
+        LibOS::supports_zero_copy(self)
+    }
+
+    fn supports_sga(&self) -> bool {
+        LibOS::supports_sga(self)
+    }
+}
@@ -0,0 +1,500 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use crate::{
+    demikernel::libos::LibOS,
+    runtime::{
+        fail::{
+            ErrorKind,
+            Fail,
+        },
+        memory::{
+            Buffer,
+            DataBuffer,
+        },
+        network::preamble::{
+            RequestPreamble,
+            REQUEST_PREAMBLE_SIZE,
+        },
+        types::{
+            demi_opcode_t,
+            demi_qresult_t,
+            demi_sgarray_t,
+        },
+        QDesc,
+        QToken,
+    },
+};
+use ::libc::{
+    EBADMSG,
+    ECONNRESET,
+    EINVAL,
+    EIO,
+};
+use ::rand::{
+    rngs::SmallRng,
+    Rng,
+    SeedableRng,
+};
+use ::std::{
+    net::SocketAddrV4,
+    slice,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Arrival Distribution
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ArrivalDistribution {
+    /// Requests are evenly spaced.
+    Uniform,
+    /// Requests follow a Poisson process, that is, gaps between requests are exponentially distributed.
+    Poisson,
+}
+
+/// Arrival Schedule
+///
+/// Generates the gaps between consecutive requests of an open-loop load, for a given mean rate.
+pub struct ArrivalSchedule {
+    /// Distribution of gaps.
+    distribution: ArrivalDistribution,
+    /// Mean gap between requests.
+    mean_gap: Duration,
+    /// Random number generator.
+    rng: SmallRng,
+}
+
+/// Latency Recorder
+#[derive(Default)]
+pub struct LatencyRecorder {
+    /// Recorded latencies, in arrival order.
+    samples: Vec<Duration>,
+}
+
+/// Load Report
+pub struct LoadReport {
+    /// Number of requests sent.
+    pub sent: u64,
+    /// Number of responses received.
+    pub received: u64,
+    /// Time spent generating load.
+    pub elapsed: Duration,
+    /// Per-request latencies.
+    pub latencies: LatencyRecorder,
+}
+
+/// Open-Loop Load Generator
+///
+/// Issues requests over several TCP connections at a target rate, regardless of whether earlier requests have been
+/// answered. Each request starts with a [RequestPreamble] that carries the time at which the request was scheduled,
+/// the index of the connection it was sent on, a per-connection sequence number and the length of the request. The
+/// server is expected to echo the preamble at the front of each response, so that latencies can be measured from the
+/// schedule rather than from the actual send time, and queuing delays on the client are not hidden. The length field
+/// tells where each response ends in the byte stream, so servers that do not echo whole requests must update it.
+pub struct LoadGenerator {
+    /// Remote server.
+    remote: SocketAddrV4,
+    /// Number of connections that requests are spread over.
+    num_connections: usize,
+    /// Size of requests (in bytes).
+    request_size: usize,
+    /// Time to generate load for.
+    duration: Duration,
+    /// Arrival schedule.
+    schedule: ArrivalSchedule,
+}
+
+/// Response Assembler
+///
+/// Splits the byte stream of a connection back into responses. Data popped from a TCP connection may hold part of a
+/// response or several of them, so bytes are held until a whole response has arrived. The length of each response is
+/// read from its preamble.
+#[derive(Default)]
+struct ResponseAssembler {
+    /// Bytes that were received but do not form a whole response yet.
+    pending: Vec<u8>,
+}
+
+//======================================================================================================================
+// Associate Functions
+//======================================================================================================================
+
+/// Associate Functions for Arrival Schedules
+impl ArrivalSchedule {
+    /// Creates an arrival schedule for `rate` requests per second.
+    pub fn new(distribution: ArrivalDistribution, rate: u64, seed: u64) -> Result<Self, Fail> {
+        if rate == 0 {
+            return Err(Fail::new(EINVAL, "invalid request rate"));
+        }
+        Ok(Self {
+            distribution,
+            mean_gap: Duration::from_nanos(1_000_000_000 / rate),
+            rng: SmallRng::seed_from_u64(seed),
+        })
+    }
+
+    /// Returns the gap between the last request and the next one.
+    pub fn next_gap(&mut self) -> Duration {
+        match self.distribution {
+            ArrivalDistribution::Uniform => self.mean_gap,
+            ArrivalDistribution::Poisson => {
+                let u: f64 = self.rng.gen::<f64>();
+                self.mean_gap.mul_f64(-(1.0 - u).ln())
+            },
+        }
+    }
+}
+
+/// Associate Functions for Latency Recorders
+impl LatencyRecorder {
+    /// Records a latency sample.
+    pub fn record(&mut self, latency: Duration) {
+        self.samples.push(latency);
+    }
+
+    /// Returns the number of recorded samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Checks if no sample was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the recorded samples, in arrival order.
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+
+    /// Returns the mean latency.
+    pub fn mean(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<Duration>() / self.samples.len() as u32)
+    }
+
+    /// Returns the `p`-th percentile latency, with `p` in the range 0 to 100.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.samples.is_empty() || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.samples.clone();
+        sorted.sort_unstable();
+        let rank: usize = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+        Some(sorted[rank])
+    }
+}
+
+/// Associate Functions for Load Reports
+impl LoadReport {
+    /// Returns the rate at which responses were received, in responses per second.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.received as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Associate Functions for Load Generators
+impl LoadGenerator {
+    /// Creates a load generator.
+    pub fn new(
+        remote: SocketAddrV4,
+        num_connections: usize,
+        request_size: usize,
+        duration: Duration,
+        schedule: ArrivalSchedule,
+    ) -> Result<Self, Fail> {
+        if num_connections == 0 {
+            return Err(Fail::new(EINVAL, "invalid number of connections"));
+        }
+        if request_size < REQUEST_PREAMBLE_SIZE {
+            return Err(Fail::new(EINVAL, "request too small for preamble"));
+        }
+        if request_size > u32::MAX as usize {
+            return Err(Fail::new(EINVAL, "request too large for preamble"));
+        }
+        Ok(Self {
+            remote,
+            num_connections,
+            request_size,
+            duration,
+            schedule,
+        })
+    }
+
+    /// Connects to the remote server and generates load on it.
+    pub fn run(&mut self, libos: &mut LibOS) -> Result<LoadReport, Fail> {
+        // Open connections.
+        let mut qds: Vec<QDesc> = Vec::with_capacity(self.num_connections);
+        for _ in 0..self.num_connections {
+            let qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0)?;
+            let qt: QToken = libos.connect(qd, self.remote)?;
+            let qr: demi_qresult_t = libos.wait(qt)?;
+            if qr.qr_opcode != demi_opcode_t::DEMI_OPC_CONNECT {
+                return Err(Fail::new(EIO, "failed to connect"));
+            }
+            qds.push(qd);
+        }
+
+        // Keep one pop operation outstanding on each connection.
+        let mut pops: Vec<QToken> = Vec::with_capacity(self.num_connections);
+        for qd in qds.iter() {
+            pops.push(libos.pop(*qd)?);
+        }
+        let mut pushes: Vec<QToken> = Vec::new();
+        let mut assemblers: Vec<ResponseAssembler> = (0..self.num_connections)
+            .map(|_| ResponseAssembler::default())
+            .collect();
+        let mut sequences: Vec<u64> = vec![0; self.num_connections];
+        let mut request: Vec<u8> = vec![0; self.request_size];
+
+        let mut report: LoadReport = LoadReport {
+            sent: 0,
+            received: 0,
+            elapsed: Duration::ZERO,
+            latencies: LatencyRecorder::default(),
+        };
+        let start: Instant = Instant::now();
+        let end: Instant = start + self.duration;
+        let mut next_send: Instant = start;
+        let mut flow: usize = 0;
+
+        loop {
+            let now: Instant = Instant::now();
+            if now >= end {
+                break;
+            }
+
+            // Issue requests that are due, without waiting for earlier ones to be answered.
+            while next_send <= now {
+                let timestamp: u64 = (next_send - start).as_nanos() as u64;
+                RequestPreamble::new(timestamp, flow as u64, sequences[flow])
+                    .with_length(self.request_size as u32)
+                    .serialize(&mut request);
+                pushes.push(libos.push2(qds[flow], &request)?);
+                sequences[flow] += 1;
+                flow = (flow + 1) % self.num_connections;
+                report.sent += 1;
+                next_send += self.schedule.next_gap();
+            }
+
+            // Reap completions until the next request is due. Requests are paced by the timed wait of the LibOS, rather
+            // than by spinning on the clock here.
+            let qts: Vec<QToken> = pops.iter().chain(pushes.iter()).copied().collect();
+            let timeout: Duration = next_send.min(end).saturating_duration_since(Instant::now());
+            let abstime: SystemTime = SystemTime::now() + timeout;
+            let results: Vec<(usize, demi_qresult_t)> = match libos.wait_many(&qts, qts.len(), Some(abstime)) {
+                Ok(results) => results,
+                Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+                Err(e) => return Err(e),
+            };
+
+            let mut completed_pushes: Vec<usize> = Vec::new();
+            for (i, qr) in results {
+                match qr.qr_opcode {
+                    demi_opcode_t::DEMI_OPC_POP => {
+                        let sga: demi_sgarray_t = unsafe { qr.qr_value.sga };
+                        let preambles: Result<Vec<RequestPreamble>, Fail> =
+                            Self::parse_responses(&mut assemblers[i], &sga);
+                        libos.sgafree(sga)?;
+                        let elapsed: Duration = Instant::now() - start;
+                        for preamble in preambles? {
+                            let latency: Duration = elapsed.saturating_sub(Duration::from_nanos(preamble.timestamp()));
+                            report.latencies.record(latency);
+                            report.received += 1;
+                        }
+                        pops[i] = libos.pop(qds[i])?;
+                    },
+                    demi_opcode_t::DEMI_OPC_PUSH => completed_pushes.push(i - pops.len()),
+                    _ => return Err(Fail::new(EIO, "operation failed")),
+                }
+            }
+            completed_pushes.sort_unstable();
+            for i in completed_pushes.into_iter().rev() {
+                pushes.swap_remove(i);
+            }
+        }
+        report.elapsed = start.elapsed();
+
+        // Let pending requests go out before closing connections.
+        for qt in pushes {
+            libos.wait(qt)?;
+        }
+        for qd in qds {
+            libos.close(qd)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Feeds popped data to the assembler of its connection, and returns the preambles of responses that are now
+    /// complete.
+    fn parse_responses(assembler: &mut ResponseAssembler, sga: &demi_sgarray_t) -> Result<Vec<RequestPreamble>, Fail> {
+        let ptr: *const u8 = sga.sga_segs[0].sgaseg_buf as *const u8;
+        let len: usize = sga.sga_segs[0].sgaseg_len as usize;
+        if len == 0 {
+            return Err(Fail::new(ECONNRESET, "connection closed by server"));
+        }
+        let bytes: &[u8] = unsafe { slice::from_raw_parts(ptr, len) };
+        assembler.push(bytes)
+    }
+}
+
+/// Associate Functions for Response Assemblers
+impl ResponseAssembler {
+    /// Appends received bytes, and returns the preambles of the responses that are now complete, in order.
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<RequestPreamble>, Fail> {
+        self.pending.extend_from_slice(bytes);
+        let mut preambles: Vec<RequestPreamble> = Vec::new();
+        let mut offset: usize = 0;
+        while self.pending.len() - offset >= REQUEST_PREAMBLE_SIZE {
+            let header: &[u8] = &self.pending[offset..(offset + REQUEST_PREAMBLE_SIZE)];
+            let (preamble, _): (RequestPreamble, Buffer) =
+                RequestPreamble::parse(Buffer::Heap(DataBuffer::from_slice(header)))?;
+            let length: usize = preamble.length() as usize;
+            if length < REQUEST_PREAMBLE_SIZE {
+                return Err(Fail::new(EBADMSG, "invalid response length"));
+            }
+            if self.pending.len() - offset < length {
+                break;
+            }
+            offset += length;
+            preambles.push(preamble);
+        }
+        self.pending.drain(..offset);
+        Ok(preambles)
+    }
+}
+
+//======================================================================================================================
+// Unit Tests
+//======================================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ArrivalDistribution,
+        ArrivalSchedule,
+        LatencyRecorder,
+        LoadGenerator,
+        ResponseAssembler,
+    };
+    use crate::runtime::network::preamble::{
+        RequestPreamble,
+        REQUEST_PREAMBLE_SIZE,
+    };
+    use ::std::{
+        net::{
+            Ipv4Addr,
+            SocketAddrV4,
+        },
+        time::Duration,
+    };
+
+    #[test]
+    fn schedule_uniform() {
+        let mut schedule: ArrivalSchedule = ArrivalSchedule::new(ArrivalDistribution::Uniform, 1000, 0).unwrap();
+        for _ in 0..16 {
+            assert_eq!(schedule.next_gap(), Duration::from_millis(1));
+        }
+        assert!(ArrivalSchedule::new(ArrivalDistribution::Uniform, 0, 0).is_err());
+    }
+
+    #[test]
+    fn schedule_poisson() {
+        const NUM_SAMPLES: u32 = 100_000;
+        let mut schedule: ArrivalSchedule = ArrivalSchedule::new(ArrivalDistribution::Poisson, 1000, 42).unwrap();
+        let total: Duration = (0..NUM_SAMPLES).map(|_| schedule.next_gap()).sum();
+        let mean: Duration = total / NUM_SAMPLES;
+        assert!(mean > Duration::from_micros(950) && mean < Duration::from_micros(1050));
+    }
+
+    #[test]
+    fn latency_percentiles() {
+        let mut recorder: LatencyRecorder = LatencyRecorder::default();
+        assert!(recorder.percentile(50.0).is_none());
+        assert!(recorder.mean().is_none());
+
+        for us in (1..=100).rev() {
+            recorder.record(Duration::from_micros(us));
+        }
+        assert_eq!(recorder.len(), 100);
+        assert_eq!(recorder.percentile(0.0), Some(Duration::from_micros(1)));
+        assert_eq!(recorder.percentile(50.0), Some(Duration::from_micros(51)));
+        assert_eq!(recorder.percentile(99.0), Some(Duration::from_micros(99)));
+        assert_eq!(recorder.percentile(100.0), Some(Duration::from_micros(100)));
+        assert_eq!(recorder.mean(), Some(Duration::from_nanos(50_500)));
+        assert!(recorder.percentile(101.0).is_none());
+    }
+
+    #[test]
+    fn load_generator_bad_config() {
+        let remote: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 12345);
+        let schedule = || ArrivalSchedule::new(ArrivalDistribution::Uniform, 1000, 0).unwrap();
+        let duration: Duration = Duration::from_secs(1);
+
+        assert!(LoadGenerator::new(remote, 0, REQUEST_PREAMBLE_SIZE, duration, schedule()).is_err());
+        assert!(LoadGenerator::new(remote, 4, REQUEST_PREAMBLE_SIZE - 1, duration, schedule()).is_err());
+        assert!(LoadGenerator::new(remote, 4, REQUEST_PREAMBLE_SIZE, duration, schedule()).is_ok());
+    }
+
+    /// Serializes a response of `length` bytes.
+    fn response(sequence: u64, length: usize) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![0xab; length];
+        RequestPreamble::new(sequence * 1000, 0, sequence)
+            .with_length(length as u32)
+            .serialize(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn assemble_coalesced_responses() {
+        let mut assembler: ResponseAssembler = ResponseAssembler::default();
+        let mut bytes: Vec<u8> = response(0, 64);
+        bytes.extend_from_slice(&response(1, 64));
+
+        // Both responses arrive in a single pop.
+        let preambles: Vec<RequestPreamble> = assembler.push(&bytes).unwrap();
+        assert_eq!(preambles.len(), 2);
+        assert_eq!(preambles[0].sequence(), 0);
+        assert_eq!(preambles[1].sequence(), 1);
+        assert!(assembler.pending.is_empty());
+    }
+
+    #[test]
+    fn assemble_split_response() {
+        let mut assembler: ResponseAssembler = ResponseAssembler::default();
+        let bytes: Vec<u8> = response(7, 64);
+
+        // The first pop is shorter than the preamble, and the second one completes the response.
+        assert!(assembler.push(&bytes[..REQUEST_PREAMBLE_SIZE - 8]).unwrap().is_empty());
+        assert!(assembler
+            .push(&bytes[(REQUEST_PREAMBLE_SIZE - 8)..40])
+            .unwrap()
+            .is_empty());
+        let preambles: Vec<RequestPreamble> = assembler.push(&bytes[40..]).unwrap();
+        assert_eq!(preambles.len(), 1);
+        assert_eq!(preambles[0].sequence(), 7);
+        assert!(assembler.pending.is_empty());
+
+        // A response with an invalid length is rejected.
+        let mut bytes: Vec<u8> = response(8, 64);
+        RequestPreamble::new(0, 0, 8).with_length(1).serialize(&mut bytes);
+        assert!(assembler.push(&bytes).is_err());
+    }
+}
@@ -45,6 +45,10 @@ impl SchedulerFuture for FutureOperation {
         self
     }
 
+    fn as_any_ref(&self) -> &dyn Any {
+        self
+    }
+
     fn get_future(&self) -> &dyn Future<Output = ()> {
         todo!()
     }
@@ -15,7 +15,10 @@ use crate::{
                 EtherType2,
                 Ethernet2Header,
             },
-            tcp::operations::ConnectFuture,
+            tcp::{
+                operations::ConnectFuture,
+                TcpInfo,
+            },
             udp::UdpOperation,
             Peer,
         },
@@ -47,6 +50,7 @@ use crate::{
         SchedulerHandle,
     },
 };
+use ::futures::task::noop_waker_ref;
 use ::libc::{
     c_int,
     EBADF,
@@ -56,12 +60,19 @@ use ::libc::{
 use ::std::{
     any::Any,
     convert::TryFrom,
+    future::Future,
     net::{
         Ipv4Addr,
         SocketAddrV4,
     },
+    pin::Pin,
     rc::Rc,
+    task::{
+        Context,
+        Poll,
+    },
     time::{
+        Duration,
         Instant,
         SystemTime,
     },
@@ -224,6 +235,86 @@ impl InetStack {
         }
     }
 
+    ///
+    /// **Brief**
+    ///
+    /// Turns the UDP socket referred to by `qd` into the default socket. Datagrams
+    /// that are addressed to ports with no bound socket are delivered to the
+    /// default socket, along with their UDP header, instead of being dropped.
+    /// This is intended for diagnostic tools.
+    ///
+    /// **Return Value**
+    ///
+    /// Upon successful completion, `Ok(())` is returned. Upon failure, `Fail` is
+    /// returned instead.
+    ///
+    pub fn bind_default(&mut self, qd: QDesc) -> Result<(), Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::bind_default");
+        trace!("bind_default(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::UdpSocket) => self.ipv4.udp.do_bind_default(qd),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    /// Gets the send buffer size (in bytes) of the TCP connection referred to by `qd`.
+    pub fn get_send_buffer_size(&self, qd: QDesc) -> Result<usize, Fail> {
+        trace!("get_send_buffer_size(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.get_send_buffer_size(qd),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    /// Attaches an application context to an I/O queue descriptor. The context is dropped when the I/O queue
+    /// descriptor is closed.
+    pub fn set_context(&mut self, qd: QDesc, context: Box<dyn Any>) -> Result<(), Fail> {
+        self.file_table.set_context(qd, context)
+    }
+
+    /// Gets the application context attached to an I/O queue descriptor.
+    pub fn get_context(&self, qd: QDesc) -> Result<Option<&dyn Any>, Fail> {
+        self.file_table.get_context(qd)
+    }
+
+    /// Gets a mutable reference to the application context attached to an I/O queue descriptor.
+    pub fn get_context_mut(&mut self, qd: QDesc) -> Result<Option<&mut dyn Any>, Fail> {
+        self.file_table.get_context_mut(qd)
+    }
+
+    /// Takes a snapshot of the transport state (round-trip time, retransmissions, congestion window and so on) of the
+    /// TCP connection referred to by `qd`.
+    pub fn tcp_info(&self, qd: QDesc) -> Result<TcpInfo, Fail> {
+        trace!("tcp_info(): qd={:?}", qd);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.tcp_info(qd),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
+    /// Sets the send buffer size (in bytes) of the TCP connection referred to by `qd`. Pushes that would queue more
+    /// data than this fail with `EAGAIN`.
+    pub fn set_send_buffer_size(&mut self, qd: QDesc, size: usize) -> Result<(), Fail> {
+        trace!("set_send_buffer_size(): qd={:?} size={:?}", qd, size);
+        match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => self.ipv4.tcp.set_send_buffer_size(qd, size),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }
+    }
+
     ///
     /// **Brief**
     ///
@@ -399,6 +490,58 @@ impl InetStack {
         Ok(qt)
     }
 
+    /// Pushes data that is scattered across several slices to a TCP socket. The slices are gathered into a single
+    /// buffer.
+    pub fn push_slices(&mut self, qd: QDesc, slices: &[&[u8]]) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::push_slices");
+        trace!("push_slices(): qd={:?}, nslices={:?}", qd, slices.len());
+
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slices(slices));
+        if buf.is_empty() {
+            return Err(Fail::new(EINVAL, "zero-length buffer"));
+        }
+
+        // Issue operation.
+        let future: FutureOperation = self.do_push(qd, buf)?;
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        trace!("push_slices() qt={:?}", qt);
+        Ok(qt)
+    }
+
+    /// Pushes several buffers to a TCP socket with a single operation. The returned queue token completes once all
+    /// buffers have been handed to the TCP stack, in order.
+    pub fn push_batch<I: IntoIterator<Item = Buffer>>(&mut self, qd: QDesc, bufs: I) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::push_batch");
+        trace!("push_batch(): qd={:?}", qd);
+
+        let bufs: Vec<Buffer> = bufs.into_iter().collect();
+        if bufs.is_empty() || bufs.iter().any(|buf| buf.is_empty()) {
+            return Err(Fail::new(EINVAL, "zero-length buffer"));
+        }
+
+        // Issue operation.
+        let future: FutureOperation = match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => FutureOperation::from(self.ipv4.tcp.push_batch(qd, bufs)),
+                _ => return Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        trace!("push_batch() qt={:?}", qt);
+        Ok(qt)
+    }
+
     /// Pushes a buffer to a UDP socket.
     /// TODO: Rename this function to pushto() once we have a common buffer representation across all libOSes.
     pub fn do_pushto(&mut self, qd: QDesc, buf: Buffer, to: SocketAddrV4) -> Result<FutureOperation, Fail> {
@@ -467,6 +610,31 @@ impl InetStack {
         Ok(qt)
     }
 
+    /// Creates a peek request, which completes with the data buffered in the TCP connection represented by `qd`, like
+    /// a pop would, but does not consume it.
+    pub fn peek(&mut self, qd: QDesc) -> Result<QToken, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::peek");
+        trace!("peek(): qd={:?}", qd);
+
+        let future: FutureOperation = match self.file_table.get(qd) {
+            Some(qtype) => match QType::try_from(qtype) {
+                Ok(QType::TcpSocket) => Ok(FutureOperation::from(self.ipv4.tcp.peek(qd))),
+                Ok(QType::UdpSocket) => Err(Fail::new(ENOTSUP, "peek not supported on UDP sockets")),
+                _ => Err(Fail::new(EINVAL, "invalid queue type")),
+            },
+            _ => Err(Fail::new(EBADF, "bad queue descriptor")),
+        }?;
+
+        let handle: SchedulerHandle = match self.scheduler.insert(future) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EAGAIN, "cannot schedule co-routine")),
+        };
+        let qt: QToken = handle.into_raw().into();
+        trace!("peek() qt={:?}", qt);
+        Ok(qt)
+    }
+
     /// Waits for an operation to complete.
     pub fn wait2(&mut self, qt: QToken) -> Result<(QDesc, OperationResult), Fail> {
         #[cfg(feature = "profiler")]
@@ -512,12 +680,60 @@ impl InetStack {
                 return Ok(self.take_operation(handle));
             }
 
-            if abstime.is_none() || SystemTime::now() >= abstime.unwrap() {
-                // Return this operation to the scheduling queue by removing the associated key
+            match abstime {
+                // No timeout was set, so return this operation to the scheduling queue by removing the associated key
                 // (which would otherwise cause the operation to be freed).
-                handle.take_key();
-                return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                None => {
+                    handle.take_key();
+                    return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                },
+                // The deadline has passed, so abort the operation.
+                Some(abstime) if SystemTime::now() >= abstime => {
+                    self.cancel_operation(handle)?;
+                    return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                },
+                Some(_) => (),
+            }
+        }
+    }
+
+    /// Cancels a pending operation. The queue token is invalidated and the resources held by the operation are
+    /// released. Operations that have already completed are left untouched, so that their result can be waited on.
+    pub fn cancel(&mut self, qt: QToken) -> Result<(), Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::cancel");
+        trace!("cancel(): qt={:?}", qt);
+
+        // Retrieve associated schedule handle.
+        let mut handle: SchedulerHandle = match self.scheduler.from_raw_handle(qt.into()) {
+            Some(handle) => handle,
+            None => return Err(Fail::new(libc::EINVAL, "invalid queue token")),
+        };
+
+        if handle.has_completed() {
+            // Return this operation to the scheduling queue by removing the associated key
+            // (which would otherwise cause the operation to be freed).
+            handle.take_key();
+            return Err(Fail::new(libc::EALREADY, "operation has already completed"));
+        }
+
+        self.cancel_operation(handle)
+    }
+
+    /// Sends an ICMP echo request to a remote host and waits for the matching reply. On success, the round-trip time
+    /// is returned. If `timeout` is `None`, a default timeout of five seconds applies.
+    pub fn ping(&mut self, addr: Ipv4Addr, timeout: Option<Duration>) -> Result<Duration, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::ping");
+        trace!("ping(): addr={:?}, timeout={:?}", addr, timeout);
+
+        let mut ctx: Context = Context::from_waker(noop_waker_ref());
+        let mut future: Pin<Box<dyn Future<Output = Result<Duration, Fail>>>> = Box::pin(self.ipv4.ping(addr, timeout));
+        loop {
+            if let Poll::Ready(result) = Future::poll(future.as_mut(), &mut ctx) {
+                return result;
             }
+            self.poll_bg_work();
         }
     }
 
@@ -553,6 +769,31 @@ impl InetStack {
         }
     }
 
+    /// Waits for up to `max_results` operations to complete and returns all of them at once, along with their offset
+    /// in `qts`. If `abstime` is set and no operation completes before it, ETIMEDOUT is returned. If `qts` is empty,
+    /// `abstime` must be set, or EINVAL is returned. Operations that are still pending are left untouched.
+    pub fn wait_many2(
+        &mut self,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<(usize, QDesc, OperationResult)>, Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("inetstack::wait_many2");
+        trace!("wait_many2(): qts={:?}, max_results={:?}", qts, max_results);
+
+        let scheduler: Scheduler = self.scheduler.clone();
+        let results: Vec<(usize, (QDesc, OperationResult))> = scheduler.wait_many(
+            self,
+            qts,
+            max_results,
+            abstime,
+            Self::poll_bg_work,
+            Self::take_operation,
+        )?;
+        Ok(results.into_iter().map(|(i, (qd, r))| (i, qd, r)).collect())
+    }
+
     /// Given a handle representing a task in our scheduler. Return the results of this future
     /// and the file descriptor for this connection.
     ///
@@ -586,6 +827,38 @@ impl InetStack {
         }
     }
 
+    /// Removes a pending operation from the scheduler and releases the resources that it holds.
+    fn cancel_operation(&mut self, mut handle: SchedulerHandle) -> Result<(), Fail> {
+        let is_background: bool = self.scheduler.inspect(&handle, |future| {
+            matches!(
+                future.downcast_ref::<FutureOperation>(),
+                Some(FutureOperation::Background(..))
+            )
+        });
+        if is_background {
+            // Return this task to the scheduling queue by removing the associated key
+            // (which would otherwise cause the task to be freed).
+            handle.take_key();
+            return Err(Fail::new(libc::EINVAL, "cannot cancel a background task"));
+        }
+
+        let boxed_future: Box<dyn Any> = self.scheduler.take(handle).as_any();
+        let boxed_concrete_type: FutureOperation = *boxed_future.downcast::<FutureOperation>().expect("Wrong type!");
+
+        match boxed_concrete_type {
+            FutureOperation::Tcp(f) => {
+                // Release queue descriptor reserved by an accept operation.
+                if let Some(new_qd) = f.get_new_qd() {
+                    self.file_table.free(new_qd);
+                }
+            },
+            FutureOperation::Udp(..) => (),
+            FutureOperation::Background(..) => unreachable!("background tasks are not cancelled"),
+        }
+
+        Ok(())
+    }
+
     /// New incoming data has arrived. Route it to the correct parse out the Ethernet header and
     /// allow the correct protocol to handle it. The underlying protocol will futher parse the data
     /// and inform the correct task that its data has arrived.
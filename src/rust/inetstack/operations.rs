@@ -23,6 +23,8 @@ pub enum OperationResult {
     Connect,
     Accept(QDesc),
     Push,
+    /// Received data. The buffer starts at the first byte of the payload and ends at its last byte: headers are
+    /// stripped by each layer of the network stack, and link-layer padding is trimmed by the IPv4 layer.
     // TODO: Drop wrapping Option.
     Pop(Option<SocketAddrV4>, Buffer),
     Failed(Fail),
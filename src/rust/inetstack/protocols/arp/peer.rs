@@ -45,6 +45,7 @@ use ::futures::{
 };
 use ::libc::{
     EBADMSG,
+    EHOSTUNREACH,
     ETIMEDOUT,
 };
 use ::std::{
@@ -92,9 +93,15 @@ impl ArpPeer {
         local_ipv4_addr: Ipv4Addr,
         arp_config: ArpConfig,
     ) -> Result<ArpPeer, Fail> {
+        // Static entries never expire.
+        let cache_ttl: Option<Duration> = if arp_config.get_static_arp() {
+            None
+        } else {
+            Some(arp_config.get_cache_ttl())
+        };
         let cache = Rc::new(RefCell::new(ArpCache::new(
             clock.clone(),
-            Some(arp_config.get_cache_ttl()),
+            cache_ttl,
             Some(arp_config.get_initial_values()),
             arp_config.get_disable_arp(),
         )));
@@ -164,6 +171,12 @@ impl ArpPeer {
     }
 
     pub fn receive(&mut self, buf: Buffer) -> Result<(), Fail> {
+        // In static mode, the translation table is never updated.
+        if self.is_static() {
+            debug!("Dropping ARP packet in static mode");
+            return Ok(());
+        }
+
         // from RFC 826:
         // > ?Do I have the hardware type in ar$hrd?
         // > [optionally check the hardware length ar$hln]
@@ -237,11 +250,36 @@ impl ArpPeer {
         }
     }
 
+    /// Checks if link addresses are only resolved from the initial values of the translation table.
+    pub fn is_static(&self) -> bool {
+        self.arp_config.get_static_arp()
+    }
+
+    /// Returns the address of the next hop towards a destination.
+    pub fn next_hop(&self, ipv4_addr: Ipv4Addr) -> Ipv4Addr {
+        if ipv4_addr == self.local_ipv4_addr {
+            return ipv4_addr;
+        }
+        self.arp_config.get_routing_table().next_hop(ipv4_addr)
+    }
+
+    /// Returns the link address of the next hop towards a destination, if it is in the translation table.
     pub fn try_query(&self, ipv4_addr: Ipv4Addr) -> Option<MacAddress> {
-        self.cache.borrow().get(ipv4_addr).cloned()
+        self.try_query_next_hop(self.next_hop(ipv4_addr))
+    }
+
+    /// Returns the link address of a next hop, if it is in the translation table.
+    pub fn try_query_next_hop(&self, next_hop: Ipv4Addr) -> Option<MacAddress> {
+        self.cache.borrow().get(next_hop).cloned()
     }
 
+    /// Resolves the link address of the next hop towards a destination.
     pub fn query(&self, ipv4_addr: Ipv4Addr) -> impl Future<Output = Result<MacAddress, Fail>> {
+        self.query_next_hop(self.next_hop(ipv4_addr))
+    }
+
+    /// Resolves the link address of a next hop.
+    pub fn query_next_hop(&self, ipv4_addr: Ipv4Addr) -> impl Future<Output = Result<MacAddress, Fail>> {
         let rt = self.rt.clone();
         let mut arp = self.clone();
         let cache = self.cache.clone();
@@ -253,6 +291,9 @@ impl ArpPeer {
             if let Some(&link_addr) = cache.borrow().get(ipv4_addr) {
                 return Ok(link_addr);
             }
+            if arp_options.get_static_arp() {
+                return Err(Fail::new(EHOSTUNREACH, "no static link address for destination"));
+            }
             let msg = ArpMessage::new(
                 Ethernet2Header::new(MacAddress::broadcast(), local_link_addr, EtherType2::Arp),
                 ArpHeader::new(
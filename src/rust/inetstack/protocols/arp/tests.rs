@@ -8,7 +8,9 @@ use super::packet::{
 use crate::{
     inetstack::{
         protocols::ethernet2::Ethernet2Header,
-        test_helpers::{self,},
+        test_helpers::{
+            self,
+        },
     },
     runtime::network::types::MacAddress,
 };
@@ -21,6 +23,7 @@ use ::futures::{
 };
 use ::libc::{
     EBADMSG,
+    EHOSTUNREACH,
     ETIMEDOUT,
 };
 use ::std::{
@@ -175,3 +178,25 @@ fn no_reply() {
     }
     .unwrap();
 }
+
+/// Tests that static mode resolves addresses from the ARP table and fails fast otherwise, without sending requests.
+#[test]
+fn static_table() {
+    let now = Instant::now();
+    let alice = test_helpers::new_alice2_static(now);
+    let mut ctx = Context::from_waker(noop_waker_ref());
+
+    let mut fut = alice.arp_query(test_helpers::BOB_IPV4).boxed_local();
+    match Future::poll(fut.as_mut(), &mut ctx) {
+        Poll::Ready(Ok(link_addr)) => assert_eq!(link_addr, test_helpers::BOB_MAC),
+        _ => panic!("static address should be resolved immediately"),
+    }
+
+    let mut fut = alice.arp_query(test_helpers::CARRIE_IPV4).boxed_local();
+    match Future::poll(fut.as_mut(), &mut ctx) {
+        Poll::Ready(Err(error)) if error.errno == EHOSTUNREACH => Ok(()),
+        _ => Err(()),
+    }
+    .unwrap();
+    assert!(alice.rt.pop_frame_unchecked().is_none());
+}
@@ -105,3 +105,30 @@ fn ipv4_ping_loop() {
         assert_eq!(latency, Duration::from_secs(2));
     }
 }
+
+#[test]
+fn ipv4_ping_timeout() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    let mut alice = test_helpers::new_alice2(now);
+
+    // Alice pings Bob, who never replies.
+    let mut ping_fut = Box::pin(alice.ipv4_ping(test_helpers::BOB_IPV4, Some(Duration::from_secs(1))));
+    match Future::poll(Pin::new(&mut ping_fut), &mut ctx) {
+        Poll::Pending => Ok(()),
+        _ => Err(()),
+    }
+    .unwrap();
+    alice.rt.pop_frame();
+
+    now += Duration::from_secs(2);
+    alice.clock.advance_clock(now);
+
+    // Alice gives up.
+    match Future::poll(Pin::new(&mut ping_fut), &mut ctx) {
+        Poll::Ready(Err(_)) => Ok(()),
+        _ => Err(()),
+    }
+    .unwrap();
+}
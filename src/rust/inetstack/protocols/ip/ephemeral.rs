@@ -6,6 +6,10 @@ use ::rand::prelude::{
     SliceRandom,
     SmallRng,
 };
+use ::std::{
+    collections::VecDeque,
+    time::Instant,
+};
 
 //==============================================================================
 // Constants
@@ -14,12 +18,40 @@ use ::rand::prelude::{
 const FIRST_PRIVATE_PORT: u16 = 49152;
 const LAST_PRIVATE_PORT: u16 = 65535;
 
+//==============================================================================
+// Enumerations
+//==============================================================================
+
+/// State of an Ephemeral Port
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PortState {
+    /// The port may be allocated.
+    Free,
+    /// The port is allocated.
+    InUse,
+    /// The port was released by a connection in TIME-WAIT, and may only be reused once the deadline has passed.
+    TimeWait(Instant),
+}
+
 //==============================================================================
 // Structures
 //==============================================================================
 
+/// Ephemeral Port Allocator
+///
+/// Free ports are handed out in FIFO order, so a released port is only reused after all other free ports. Ports
+/// released by connections that enter TIME-WAIT are held back until their deadline expires, so that segments of an
+/// old connection are not mistaken for segments of a new one.
 pub struct EphemeralPorts {
-    ports: Vec<u16>,
+    /// Free ports, in allocation order. Ports that are allocated with [EphemeralPorts::alloc_port] remain in this queue
+    /// and are skipped.
+    free: VecDeque<u16>,
+    /// Ports in TIME-WAIT, in deadline order.
+    time_wait: VecDeque<(Instant, u16)>,
+    /// State of each port, indexed by offset from the first private port.
+    states: Vec<PortState>,
+    /// Is each port in the free queue? Indexed by offset from the first private port.
+    queued: Vec<bool>,
 }
 
 //==============================================================================
@@ -29,11 +61,17 @@ pub struct EphemeralPorts {
 impl EphemeralPorts {
     pub fn new(rng: &mut SmallRng) -> Self {
         let mut ports: Vec<u16> = Vec::<u16>::new();
-        for port in FIRST_PRIVATE_PORT..LAST_PRIVATE_PORT {
+        for port in FIRST_PRIVATE_PORT..=LAST_PRIVATE_PORT {
             ports.push(port);
         }
         ports.shuffle(rng);
-        Self { ports }
+        let num_ports: usize = ports.len();
+        Self {
+            free: ports.into(),
+            time_wait: VecDeque::new(),
+            states: vec![PortState::Free; num_ports],
+            queued: vec![true; num_ports],
+        }
     }
 
     pub fn first_private_port() -> u16 {
@@ -44,27 +82,201 @@ impl EphemeralPorts {
         port >= FIRST_PRIVATE_PORT
     }
 
+    /// Allocates any free port from the pool. Ports in TIME-WAIT are not handed out until they are reclaimed.
     pub fn alloc_any(&mut self) -> Result<u16, Fail> {
-        self.ports.pop().ok_or(Fail::new(
+        while let Some(port) = self.free.pop_front() {
+            let index: usize = Self::index(port);
+            self.queued[index] = false;
+            if self.states[index] == PortState::Free {
+                self.states[index] = PortState::InUse;
+                return Ok(port);
+            }
+        }
+        Err(Fail::new(
             libc::EADDRINUSE,
             "all port numbers in the ephemeral port range are currently in use",
         ))
     }
 
-    /// Allocates the specified port from the pool.
+    /// Allocates the specified port from the pool. Like binds with `SO_REUSEADDR`, this succeeds for ports in
+    /// TIME-WAIT.
     pub fn alloc_port(&mut self, port: u16) -> Result<(), Fail> {
         // Check if port is not in the pool.
-        if !self.ports.contains(&port) {
+        if !Self::is_private(port) {
             return Err(Fail::new(libc::ENOENT, "port number not found"));
         }
+        let index: usize = Self::index(port);
+        if self.states[index] == PortState::InUse {
+            return Err(Fail::new(libc::EADDRINUSE, "port number is in use"));
+        }
 
-        // Remove port from the pool.
-        self.ports.retain(|&p| p != port);
+        // The port may still be queued. In that case, it is skipped when it reaches the head of the queue.
+        self.states[index] = PortState::InUse;
 
         Ok(())
     }
 
+    /// Returns a port to the pool.
     pub fn free(&mut self, port: u16) {
-        self.ports.push(port);
+        let index: usize = Self::index(port);
+        self.states[index] = PortState::Free;
+        self.enqueue(port);
+    }
+
+    /// Returns a port to the pool, once a connection that used it leaves TIME-WAIT at `deadline`. Deadlines are
+    /// expected to be non-decreasing.
+    pub fn free_after(&mut self, port: u16, deadline: Instant) {
+        let index: usize = Self::index(port);
+        self.states[index] = PortState::TimeWait(deadline);
+        self.time_wait.push_back((deadline, port));
+    }
+
+    /// Returns ports whose TIME-WAIT deadline has passed to the pool.
+    pub fn reclaim(&mut self, now: Instant) {
+        while let Some(&(deadline, port)) = self.time_wait.front() {
+            if deadline > now {
+                break;
+            }
+            self.time_wait.pop_front();
+
+            // Skip ports that were allocated again with alloc_port() since then.
+            let index: usize = Self::index(port);
+            if self.states[index] == PortState::TimeWait(deadline) {
+                self.states[index] = PortState::Free;
+                self.enqueue(port);
+            }
+        }
+    }
+
+    /// Appends a port to the free queue, unless it is already there.
+    fn enqueue(&mut self, port: u16) {
+        let index: usize = Self::index(port);
+        if !self.queued[index] {
+            self.queued[index] = true;
+            self.free.push_back(port);
+        }
+    }
+
+    /// Returns the index of a private port in the state tables.
+    fn index(port: u16) -> usize {
+        debug_assert!(Self::is_private(port));
+        (port - FIRST_PRIVATE_PORT) as usize
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::EphemeralPorts;
+    use ::rand::{
+        prelude::SmallRng,
+        SeedableRng,
+    };
+    use ::std::{
+        collections::{
+            HashMap,
+            HashSet,
+            VecDeque,
+        },
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    /// Length of TIME-WAIT in tests.
+    const TIME_WAIT: Duration = Duration::from_secs(60);
+
+    fn ephemeral_ports() -> EphemeralPorts {
+        EphemeralPorts::new(&mut SmallRng::from_seed([0; 32]))
+    }
+
+    /// Tests that all ports can be allocated once, and that the pool is exhausted afterwards.
+    #[test]
+    fn alloc_all() {
+        let mut ports: EphemeralPorts = ephemeral_ports();
+        let mut allocated: HashSet<u16> = HashSet::new();
+        while let Ok(port) = ports.alloc_any() {
+            assert!(EphemeralPorts::is_private(port));
+            assert!(allocated.insert(port), "port {} allocated twice", port);
+        }
+        assert_eq!(allocated.len(), ports.states.len());
+    }
+
+    /// Tests that a released port is reused last.
+    #[test]
+    fn free_is_fifo() {
+        let mut ports: EphemeralPorts = ephemeral_ports();
+        let first: u16 = ports.alloc_any().unwrap();
+        ports.free(first);
+        for _ in 0..ports.states.len() - 1 {
+            assert_ne!(ports.alloc_any().unwrap(), first);
+        }
+        assert_eq!(ports.alloc_any().unwrap(), first);
+    }
+
+    /// Tests that explicitly allocated ports are skipped, and that ports in TIME-WAIT may be bound explicitly.
+    #[test]
+    fn alloc_port() {
+        let now: Instant = Instant::now();
+        let mut ports: EphemeralPorts = ephemeral_ports();
+        let port: u16 = EphemeralPorts::first_private_port();
+        ports.alloc_port(port).unwrap();
+        assert!(ports.alloc_port(port).is_err());
+        while let Ok(other) = ports.alloc_any() {
+            assert_ne!(other, port);
+        }
+        ports.free_after(port, now + TIME_WAIT);
+        assert!(ports.alloc_port(port).is_ok());
+        assert!(ports.alloc_port(1024).is_err());
+    }
+
+    /// Tests that ports in TIME-WAIT are only handed out again once their deadline has passed.
+    #[test]
+    fn time_wait() {
+        let now: Instant = Instant::now();
+        let mut ports: EphemeralPorts = ephemeral_ports();
+        let mut allocated: Vec<u16> = Vec::new();
+        while let Ok(port) = ports.alloc_any() {
+            allocated.push(port);
+        }
+        ports.free_after(allocated[0], now + TIME_WAIT);
+        ports.reclaim(now + TIME_WAIT / 2);
+        assert!(ports.alloc_any().is_err());
+        ports.reclaim(now + TIME_WAIT);
+        assert_eq!(ports.alloc_any().unwrap(), allocated[0]);
+    }
+
+    /// Churns through many more connections than there are ports, and checks that ports are never handed out while in
+    /// use or in TIME-WAIT.
+    #[test]
+    fn churn() {
+        const NUM_CONNECTIONS: usize = 4096;
+        const NUM_ITERATIONS: usize = 100_000;
+        let mut now: Instant = Instant::now();
+        let mut ports: EphemeralPorts = ephemeral_ports();
+        let mut open: VecDeque<u16> = VecDeque::new();
+        let mut in_use: HashSet<u16> = HashSet::new();
+        let mut time_wait: HashMap<u16, Instant> = HashMap::new();
+        for _ in 0..NUM_ITERATIONS {
+            now += Duration::from_millis(10);
+            ports.reclaim(now);
+            let port: u16 = ports.alloc_any().unwrap();
+            assert!(in_use.insert(port), "port {} is in use", port);
+            if let Some(deadline) = time_wait.remove(&port) {
+                assert!(deadline <= now, "port {} is in TIME-WAIT", port);
+            }
+            open.push_back(port);
+
+            if open.len() > NUM_CONNECTIONS {
+                let port: u16 = open.pop_front().unwrap();
+                in_use.remove(&port);
+                ports.free_after(port, now + TIME_WAIT);
+                time_wait.insert(port, now + TIME_WAIT);
+            }
+        }
     }
 }
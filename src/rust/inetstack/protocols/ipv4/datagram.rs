@@ -310,7 +310,7 @@ impl Ipv4Header {
         self.protocol
     }
 
-    /// Computes the checksum of the target IPv4 header.
+    /// Computes the checksum of an IPv4 header, including its options.
     pub fn compute_checksum(buf: &[u8]) -> u16 {
         let mut state: u32 = 0xffffu32;
         for i in 0..5 {
@@ -318,7 +318,7 @@ impl Ipv4Header {
         }
         // Skip the 5th u16 since octets 10-12 are the header checksum, whose value should be zero when
         // computing a checksum.
-        for i in 6..10 {
+        for i in 6..(buf.len() / 2) {
             state += NetworkEndian::read_u16(&buf[(2 * i)..(2 * i + 2)]) as u32;
         }
         while state > 0xffff {
@@ -77,7 +77,7 @@ fn build_ipv4_header(
 
     // Header checksum.
     if checksum.is_none() {
-        checksum = Some(Ipv4Header::compute_checksum(&buf[..20]));
+        checksum = Some(Ipv4Header::compute_checksum(&buf[..]));
     }
     NetworkEndian::write_u16(&mut buf[10..12], checksum.unwrap());
 }
@@ -55,14 +55,14 @@ impl Peer {
         arp: ArpPeer,
         rng_seed: [u8; 32],
     ) -> Result<Peer, Fail> {
-        let udp_offload_checksum: bool = udp_config.get_tx_checksum_offload();
         let udp: UdpPeer = UdpPeer::new(
             rt.clone(),
             scheduler.clone(),
             rng_seed,
             local_link_addr,
             local_ipv4_addr,
-            udp_offload_checksum,
+            udp_config.get_rx_checksum_offload(),
+            udp_config.get_tx_checksum_offload(),
             arp.clone(),
         )?;
         let icmpv4: Icmpv4Peer = Icmpv4Peer::new(
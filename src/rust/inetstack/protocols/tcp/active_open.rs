@@ -194,7 +194,7 @@ impl ActiveOpenSocket {
             ipv4_hdr: Ipv4Header::new(self.local.ip().clone(), self.remote.ip().clone(), IpProtocol::TCP),
             tcp_hdr,
             data: None,
-            tx_checksum_offload: self.tcp_config.get_rx_checksum_offload(),
+            tx_checksum_offload: self.tcp_config.get_tx_checksum_offload(),
         };
         self.rt.transmit(Box::new(segment));
 
@@ -305,7 +305,7 @@ impl ActiveOpenSocket {
                     ipv4_hdr: Ipv4Header::new(local.ip().clone(), remote.ip().clone(), IpProtocol::TCP),
                     tcp_hdr,
                     data: None,
-                    tx_checksum_offload: tcp_config.get_rx_checksum_offload(),
+                    tx_checksum_offload: tcp_config.get_tx_checksum_offload(),
                 };
                 rt.transmit(Box::new(segment));
                 clock.wait(clock.clone(), handshake_timeout).await;
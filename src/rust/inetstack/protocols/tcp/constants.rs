@@ -7,3 +7,7 @@ pub use crate::runtime::network::consts::{
     MAX_MSS,
     MIN_MSS,
 };
+use ::std::time::Duration;
+
+/// How long the local port of a closed connection is held back before it is reused (2 MSL, with an MSL of 30 seconds).
+pub const TIME_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
@@ -62,7 +62,7 @@ async fn retransmit(cause: RetransmitCause, cb: &Rc<ControlBlock>) -> Result<(),
     };
 
     // Our retransmission timer fired, so we need to resend a packet.
-    let remote_link_addr: MacAddress = cb.arp().query(cb.get_remote().ip().clone()).await?;
+    let remote_link_addr: MacAddress = cb.remote_link_addr().await?;
 
     // Unset the initial timestamp so we don't use this for RTT estimation.
     segment.initial_tx.take();
@@ -72,6 +72,7 @@ async fn retransmit(cause: RetransmitCause, cb: &Rc<ControlBlock>) -> Result<(),
     let mut header: TcpHeader = cb.tcp_header();
     header.seq_num = seq_no;
     cb.emit(header, Some(segment.bytes), remote_link_addr);
+    cb.record_retransmission();
 
     // Set new retransmit deadline.
     // ToDo: Review this.  Shouldn't we only do this for RetransmitCause::Timeout?
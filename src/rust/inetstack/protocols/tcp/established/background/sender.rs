@@ -10,6 +10,7 @@ use crate::{
         segment::TcpHeader,
         SeqNumber,
     },
+    perftools::clock,
     runtime::{
         fail::Fail,
         memory::Buffer,
@@ -48,7 +49,7 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
         // repeatedly send window probes until window opens up.
         if win_sz == 0 {
             // Send a window probe (this is a one-byte packet designed to elicit a window update from our peer).
-            let remote_link_addr = cb.arp().query(cb.get_remote().ip().clone()).await?;
+            let remote_link_addr = cb.remote_link_addr().await?;
             let buf: Buffer = cb
                 .pop_one_unsent_byte()
                 .unwrap_or_else(|| panic!("No unsent data? {}, {}", send_next, unsent_seq));
@@ -59,7 +60,7 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
             // Add the probe byte (as a new separate buffer) to our unacknowledged queue.
             let unacked_segment = UnackedSegment {
                 bytes: buf.clone(),
-                initial_tx: Some(cb.clock.now()),
+                initial_tx: Some(clock::now_ns()),
             };
             cb.push_unacked_segment(unacked_segment);
 
@@ -120,7 +121,7 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
         // TODO: Silly window syndrome - See RFC 1122's discussion of the SWS avoidance algorithm.
 
         // ToDo: Link-level concerns don't belong here, we should call an IP-level send routine below.
-        let remote_link_addr = cb.arp().query(cb.get_remote().ip().clone()).await?;
+        let remote_link_addr = cb.remote_link_addr().await?;
 
         // Form an outgoing packet.
         let max_size: usize = cmp::min(
@@ -153,7 +154,7 @@ pub async fn sender(cb: Rc<ControlBlock>) -> Result<!, Fail> {
         // Put this segment on the unacknowledged list.
         let unacked_segment = UnackedSegment {
             bytes: segment_data,
-            initial_tx: Some(cb.clock.now()),
+            initial_tx: Some(clock::now_ns()),
         };
         cb.push_unacked_segment(unacked_segment);
 
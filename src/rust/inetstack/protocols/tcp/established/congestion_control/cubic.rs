@@ -279,6 +279,10 @@ impl SlowStartCongestionAvoidance for Cubic {
         self.cwnd.get()
     }
 
+    fn get_ssthresh(&self) -> u32 {
+        self.ssthresh.get()
+    }
+
     fn watch_cwnd(&self) -> (u32, WatchFuture<'_, u32>) {
         self.cwnd.watch()
     }
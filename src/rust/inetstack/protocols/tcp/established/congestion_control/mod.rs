@@ -30,6 +30,9 @@ pub trait SlowStartCongestionAvoidance {
     fn watch_cwnd(&self) -> (u32, WatchFuture<'_, u32>) {
         (u32::MAX, WatchFuture::Pending)
     }
+    fn get_ssthresh(&self) -> u32 {
+        u32::MAX
+    }
 
     // Called immediately before the cwnd check is performed before data is sent.
     fn on_cwnd_check_before_send(&self) {}
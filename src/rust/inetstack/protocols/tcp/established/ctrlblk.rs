@@ -55,7 +55,10 @@ use ::std::{
     },
     collections::VecDeque,
     convert::TryInto,
-    net::SocketAddrV4,
+    net::{
+        Ipv4Addr,
+        SocketAddrV4,
+    },
     rc::Rc,
     task::{
         Context,
@@ -74,11 +77,6 @@ use ::std::{
 // mechanism used to manage the receive queue (a VecDeque) than anything else.
 const RECV_QUEUE_SZ: usize = 2048;
 
-// ToDo: Review this value (and its purpose).  It (16 segments) seems awfully small (would make fast retransmit less
-// useful), and this mechanism isn't the best way to protect ourselves against deliberate out-of-order segment attacks.
-// Ideally, we'd limit out-of-order data to that which (along with the unread data) will fit in the receive window.
-const MAX_OUT_OF_ORDER: usize = 16;
-
 // TCP Connection State.
 // Note: This ControlBlock structure is only used after we've reached the ESTABLISHED state, so states LISTEN,
 // SYN_RCVD, and SYN_SENT aren't included here.
@@ -94,6 +92,31 @@ pub enum State {
     Closed,
 }
 
+/// Connection-Level Statistics
+///
+/// Snapshot of the transport state of a connection, meant to help attribute latency to TCP behavior.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TcpInfo {
+    /// Connection state.
+    pub state: State,
+    /// Smoothed round-trip time, if it was ever measured.
+    pub srtt: Option<Duration>,
+    /// Current retransmission timeout.
+    pub rto: Duration,
+    /// Number of segments that were retransmitted.
+    pub retransmissions: u64,
+    /// Congestion window (in bytes).
+    pub cwnd: u32,
+    /// Slow start threshold (in bytes).
+    pub ssthresh: u32,
+    /// Send window advertised by the peer (in bytes).
+    pub send_window: u32,
+    /// Bytes that were sent but not acknowledged yet.
+    pub bytes_in_flight: u32,
+    /// Bytes that were pushed by the application but not sent yet.
+    pub unsent_bytes: usize,
+}
+
 // ToDo: Consider incorporating this directly into ControlBlock.
 struct Receiver {
     //
@@ -137,6 +160,24 @@ impl Receiver {
         Some(buf)
     }
 
+    // Returns all data in the receive queue, without consuming it.  Data is only copied if it spans several segments.
+    pub fn peek(&self) -> Option<Buffer> {
+        let recv_queue = self.recv_queue.borrow();
+        let front: &Buffer = recv_queue.front()?;
+
+        // Stop at the end-of-stream marker, if any.
+        let segments: Vec<&Buffer> = recv_queue.iter().take_while(|buf| buf.len() > 0).collect();
+        if segments.len() <= 1 {
+            return Some(front.clone());
+        }
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(segments.iter().map(|buf| buf.len()).sum());
+        for buf in segments {
+            bytes.extend_from_slice(&buf[..]);
+        }
+        Some(Buffer::Heap(DataBuffer::from_slice(&bytes)))
+    }
+
     pub fn push(&self, buf: Buffer) {
         let buf_len: u32 = buf.len() as u32;
         self.recv_queue.borrow_mut().push_back(buf);
@@ -161,6 +202,12 @@ pub struct ControlBlock {
     // this along with other remote IP information (such as routing, path MTU, etc).
     arp: Rc<ArpPeer>,
 
+    // Address of the next hop towards the remote peer, which is looked up once when the connection is established.
+    next_hop: Ipv4Addr,
+
+    // Link address of the next hop, if it was resolved once and for all when the connection was established.
+    remote_link_addr: Option<MacAddress>,
+
     // Send-side state information.  ToDo: Consider incorporating this directly into ControlBlock.
     sender: Sender,
 
@@ -182,7 +229,9 @@ pub struct ControlBlock {
     // ToDo: Keep this as a u8?
     window_scale: u32,
 
-    waker: RefCell<Option<Waker>>,
+    /// Receive operations (pops and peeks) waiting for data. There may be several of them outstanding on the same
+    /// connection.
+    recv_wakers: RefCell<Vec<Waker>>,
 
     // Queue of out-of-order segments.  This is where we hold onto data that we've received (because it was within our
     // receive window) but can't yet present to the user because we're missing some other data that comes between this
@@ -210,6 +259,9 @@ pub struct ControlBlock {
 
     // Retransmission Timeout (RTO) calculator.
     rto: RefCell<RtoCalculator>,
+
+    // Number of segments that were retransmitted.
+    retransmissions: Cell<u64>,
 }
 
 //==============================================================================
@@ -235,7 +287,19 @@ impl ControlBlock {
         cc_constructor: CongestionControlConstructor,
         congestion_control_options: Option<congestion_control::Options>,
     ) -> Self {
-        let sender = Sender::new(sender_seq_no, sender_window_size, sender_window_scale, sender_mss);
+        let sender = Sender::new(
+            sender_seq_no,
+            sender_window_size,
+            sender_window_scale,
+            sender_mss,
+            tcp_config.get_send_buffer_size(),
+        );
+        let next_hop: Ipv4Addr = arp.next_hop(remote.ip().clone());
+        let remote_link_addr: Option<MacAddress> = if arp.is_static() {
+            arp.try_query_next_hop(next_hop)
+        } else {
+            None
+        };
         Self {
             local,
             remote,
@@ -245,13 +309,15 @@ impl ControlBlock {
             local_link_addr,
             tcp_config,
             arp: Rc::new(arp),
+            next_hop,
+            remote_link_addr,
             sender: sender,
             state: Cell::new(State::Established),
             ack_delay_timeout,
             ack_deadline: WatchedValue::new(None),
             receive_buffer_size: receiver_window_size,
             window_scale: receiver_window_scale,
-            waker: RefCell::new(None),
+            recv_wakers: RefCell::new(Vec::new()),
             out_of_order: RefCell::new(VecDeque::new()),
             out_of_order_fin: Cell::new(Option::None),
             receiver: Receiver::new(receiver_seq_no, receiver_seq_no),
@@ -259,6 +325,7 @@ impl ControlBlock {
             cc: cc_constructor(sender_mss, sender_seq_no, congestion_control_options),
             retransmit_deadline: WatchedValue::new(None),
             rto: RefCell::new(RtoCalculator::new()),
+            retransmissions: Cell::new(0),
         }
     }
 
@@ -270,15 +337,37 @@ impl ControlBlock {
         self.remote
     }
 
-    // ToDo: Remove this.  ARP doesn't belong at this layer.
-    pub fn arp(&self) -> Rc<ArpPeer> {
-        self.arp.clone()
+    /// Returns the link address of the next hop towards the remote peer, if it is known without sending an ARP
+    /// request.
+    pub fn try_remote_link_addr(&self) -> Option<MacAddress> {
+        self.remote_link_addr
+            .or_else(|| self.arp.try_query_next_hop(self.next_hop))
+    }
+
+    /// Resolves the link address of the next hop towards the remote peer.
+    pub async fn remote_link_addr(&self) -> Result<MacAddress, Fail> {
+        match self.remote_link_addr {
+            Some(remote_link_addr) => Ok(remote_link_addr),
+            None => self.arp.query_next_hop(self.next_hop).await,
+        }
     }
 
     pub fn send(&self, buf: Buffer) -> Result<(), Fail> {
         self.sender.send(buf, self)
     }
 
+    pub fn send_batch(&self, bufs: Vec<Buffer>) -> Result<(), Fail> {
+        self.sender.send_batch(bufs, self)
+    }
+
+    pub fn get_send_buffer_size(&self) -> usize {
+        self.sender.get_send_buffer_size()
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> Result<(), Fail> {
+        self.sender.set_send_buffer_size(size)
+    }
+
     pub fn congestion_control_watch_retransmit_now_flag(&self) -> (bool, WatchFuture<bool>) {
         self.cc.watch_retransmit_now_flag()
     }
@@ -371,6 +460,29 @@ impl ControlBlock {
         self.rto.borrow_mut().record_failure()
     }
 
+    pub fn record_retransmission(&self) {
+        self.retransmissions.set(self.retransmissions.get() + 1);
+    }
+
+    /// Takes a snapshot of the transport state of this connection.
+    pub fn tcp_info(&self) -> TcpInfo {
+        let (send_unacked, _): (SeqNumber, _) = self.get_send_unacked();
+        let (send_next, _): (SeqNumber, _) = self.get_send_next();
+        let (send_window, _): (u32, _) = self.get_send_window();
+        let rto = self.rto.borrow();
+        TcpInfo {
+            state: self.state.get(),
+            srtt: rto.smoothed_rtt(),
+            rto: rto.estimate(),
+            retransmissions: self.retransmissions.get(),
+            cwnd: self.cc.get_cwnd(),
+            ssthresh: self.cc.get_ssthresh(),
+            send_window,
+            bytes_in_flight: u32::from(send_next - send_unacked),
+            unsent_bytes: self.sender.get_unsent_bytes(),
+        }
+    }
+
     pub fn unsent_top_size(&self) -> Option<usize> {
         self.sender.top_size_unsent()
     }
@@ -602,7 +714,7 @@ impl ControlBlock {
                 let bytes_acknowledged: u32 = (header.ack_num - send_unacknowledged).into();
 
                 // Remove the now acknowledged data from the unacknowledged queue.
-                self.sender.remove_acknowledged_data(self, bytes_acknowledged, now);
+                self.sender.remove_acknowledged_data(self, bytes_acknowledged);
 
                 // Update SND.UNA to SEG.ACK.
                 self.sender.send_unacked.set(header.ack_num);
@@ -810,7 +922,7 @@ impl ControlBlock {
 
         // ToDo: Remove this if clause once emit() is fixed to not require the remote hardware addr (this should be
         // left to the ARP layer and not exposed to TCP).
-        if let Some(remote_link_addr) = self.arp().try_query(self.remote.ip().clone()) {
+        if let Some(remote_link_addr) = self.try_remote_link_addr() {
             self.emit(header, None, remote_link_addr);
         }
     }
@@ -905,7 +1017,7 @@ impl ControlBlock {
         // But that will think data is available to be read once we've received a FIN, because FINs consume sequence
         // number space.  Now we call is_empty() on the receive queue instead.
         if self.receiver.recv_queue.borrow().is_empty() {
-            *self.waker.borrow_mut() = Some(ctx.waker().clone());
+            self.register_recv_waker(ctx);
             return Poll::Pending;
         }
 
@@ -917,6 +1029,25 @@ impl ControlBlock {
         Poll::Ready(Ok(segment))
     }
 
+    /// Same as [Self::poll_recv], but leaves data in the receive queue.
+    pub fn poll_peek(&self, ctx: &mut Context) -> Poll<Result<Buffer, Fail>> {
+        match self.receiver.peek() {
+            Some(buf) => Poll::Ready(Ok(buf)),
+            None => {
+                self.register_recv_waker(ctx);
+                Poll::Pending
+            },
+        }
+    }
+
+    /// Registers a receive operation that waits for data, unless it is already registered.
+    fn register_recv_waker(&self, ctx: &mut Context) {
+        let mut wakers = self.recv_wakers.borrow_mut();
+        if !wakers.iter().any(|w| w.will_wake(ctx.waker())) {
+            wakers.push(ctx.waker().clone());
+        }
+    }
+
     // This routine remembers that we have received an out-of-order FIN.
     //
     pub fn store_out_of_order_fin(&self, fin: SeqNumber) {
@@ -1016,7 +1147,7 @@ impl ControlBlock {
         // If the out-of-order store now contains too many entries, delete the later entries.
         // ToDo: The out-of-order store is already limited (in size) by our receive window, while the below check
         // imposes a limit on the number of entries.  Do we need this?  Presumably for attack mitigation?
-        while out_of_order.len() > MAX_OUT_OF_ORDER {
+        while out_of_order.len() > self.tcp_config.get_max_out_of_order() {
             out_of_order.pop_back();
         }
     }
@@ -1072,7 +1203,8 @@ impl ControlBlock {
         // This appears to be checking if something is waiting on the receive queue, and if so, wakes that thing up.
         // Note: unlike updating receive_next (see above comment) we only do this once (i.e. outside the while loop).
         // ToDo: Verify that this is the right place and time to do this.
-        if let Some(w) = self.waker.borrow_mut().take() {
+        // Wake up all of them, since a pop and a peek may both be waiting. Those that find no data register again.
+        for w in self.recv_wakers.borrow_mut().drain(..) {
             w.wake()
         }
 
@@ -10,6 +10,7 @@ mod sender;
 pub use self::ctrlblk::{
     ControlBlock,
     State,
+    TcpInfo,
 };
 
 use self::background::background;
@@ -69,10 +70,18 @@ impl EstablishedSocket {
         self.cb.send(buf)
     }
 
+    pub fn send_batch(&self, bufs: Vec<Buffer>) -> Result<(), Fail> {
+        self.cb.send_batch(bufs)
+    }
+
     pub fn poll_recv(&self, ctx: &mut Context) -> Poll<Result<Buffer, Fail>> {
         self.cb.poll_recv(ctx)
     }
 
+    pub fn poll_peek(&self, ctx: &mut Context) -> Poll<Result<Buffer, Fail>> {
+        self.cb.poll_peek(ctx)
+    }
+
     pub fn close(&self) -> Result<(), Fail> {
         self.cb.close()
     }
@@ -85,6 +94,18 @@ impl EstablishedSocket {
         self.cb.rto_estimate()
     }
 
+    pub fn tcp_info(&self) -> TcpInfo {
+        self.cb.tcp_info()
+    }
+
+    pub fn get_send_buffer_size(&self) -> usize {
+        self.cb.get_send_buffer_size()
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> Result<(), Fail> {
+        self.cb.set_send_buffer_size(size)
+    }
+
     pub fn endpoints(&self) -> (SocketAddrV4, SocketAddrV4) {
         (self.cb.get_local(), self.cb.get_remote())
     }
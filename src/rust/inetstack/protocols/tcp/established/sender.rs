@@ -7,6 +7,7 @@ use crate::{
         segment::TcpHeader,
         SeqNumber,
     },
+    perftools::clock,
     runtime::{
         fail::Fail,
         memory::Buffer,
@@ -17,7 +18,7 @@ use crate::{
     },
 };
 use ::libc::{
-    EBUSY,
+    EAGAIN,
     EINVAL,
 };
 use ::std::{
@@ -28,10 +29,7 @@ use ::std::{
     collections::VecDeque,
     convert::TryInto,
     fmt,
-    time::{
-        Duration,
-        Instant,
-    },
+    time::Duration,
 };
 
 // Structure of entries on our unacknowledged queue.
@@ -40,15 +38,11 @@ use ::std::{
 //
 pub struct UnackedSegment {
     pub bytes: Buffer,
-    // Set to `None` on retransmission to implement Karn's algorithm.
-    pub initial_tx: Option<Instant>,
+    // Time of the initial transmission, as read from `perftools::clock::now_ns()`.  Set to `None` on retransmission to
+    // implement Karn's algorithm.
+    pub initial_tx: Option<u64>,
 }
 
-/// Hard limit for unsent queue.
-/// ToDo: Remove this.  We should limit the unsent queue by either having a (configurable) send buffer size (in bytes,
-/// not segments) and rejecting send requests that exceed that, or by limiting the user's send buffer allocations.
-const UNSENT_QUEUE_CUTOFF: usize = 1024;
-
 // ToDo: Consider moving retransmit timer and congestion control fields out of this structure.
 // ToDo: Make all public fields in this structure private.
 pub struct Sender {
@@ -77,6 +71,12 @@ pub struct Sender {
     // This is the send buffer (user data we do not yet have window to send).
     unsent_queue: RefCell<VecDeque<Buffer>>,
 
+    // Number of bytes in the send buffer.
+    unsent_bytes: Cell<usize>,
+
+    // Maximum number of bytes in the send buffer.  Sends that would go over this limit are rejected with EAGAIN.
+    send_buffer_size: Cell<usize>,
+
     // ToDo: Remove this as soon as sender.rs is fixed to not use it to tell if there is unsent data.
     unsent_seq_no: WatchedValue<SeqNumber>,
 
@@ -107,12 +107,14 @@ impl fmt::Debug for Sender {
 }
 
 impl Sender {
-    pub fn new(seq_no: SeqNumber, send_window: u32, window_scale: u8, mss: usize) -> Self {
+    pub fn new(seq_no: SeqNumber, send_window: u32, window_scale: u8, mss: usize, send_buffer_size: usize) -> Self {
         Self {
             send_unacked: WatchedValue::new(seq_no),
             unacked_queue: RefCell::new(VecDeque::new()),
             send_next: WatchedValue::new(seq_no),
             unsent_queue: RefCell::new(VecDeque::new()),
+            unsent_bytes: Cell::new(0),
+            send_buffer_size: Cell::new(send_buffer_size),
             unsent_seq_no: WatchedValue::new(seq_no),
 
             send_window: WatchedValue::new(send_window),
@@ -128,6 +130,29 @@ impl Sender {
         self.mss
     }
 
+    pub fn get_send_buffer_size(&self) -> usize {
+        self.send_buffer_size.get()
+    }
+
+    pub fn get_unsent_bytes(&self) -> usize {
+        self.unsent_bytes.get()
+    }
+
+    // Bytes that occupy the send buffer, i.e. everything from SND.UNA to the end of the unsent queue, be it sent or
+    // not.
+    pub fn get_buffered_bytes(&self) -> usize {
+        let buffered_bytes: u32 = (self.unsent_seq_no.get() - self.send_unacked.get()).into();
+        buffered_bytes as usize
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> Result<(), Fail> {
+        if size == 0 {
+            return Err(Fail::new(EINVAL, "invalid send buffer size"));
+        }
+        self.send_buffer_size.set(size);
+        Ok(())
+    }
+
     pub fn get_send_window(&self) -> (u32, WatchFuture<u32>) {
         self.send_window.watch()
     }
@@ -193,6 +218,14 @@ impl Sender {
         // it on the unsent queue and that's it.
         //
 
+        // Reject the send if it would overflow the send buffer, which holds unacknowledged data as well as unsent data.
+        // A buffer is always accepted when the send buffer is empty, so that sends larger than the send buffer still
+        // make progress.
+        let buffered_bytes: usize = self.get_buffered_bytes();
+        if buffered_bytes > 0 && buffered_bytes + buf.len() > self.send_buffer_size.get() {
+            return Err(Fail::new(EAGAIN, "send buffer full"));
+        }
+
         // Check for unsent data.
         if self.unsent_queue.borrow().is_empty() {
             // No unsent data queued up, so we can try to send this new buffer immediately.
@@ -215,7 +248,7 @@ impl Sender {
             let win_sz: u32 = self.send_window.get();
 
             if win_sz > 0 && win_sz >= in_flight_after_send && effective_cwnd >= in_flight_after_send {
-                if let Some(remote_link_addr) = cb.arp().try_query(cb.get_remote().ip().clone()) {
+                if let Some(remote_link_addr) = cb.try_remote_link_addr() {
                     // This hook is primarily intended to record the last time we sent data, so we can later tell if
                     // the connection has been idle.
                     let rto: Duration = cb.rto_estimate();
@@ -242,7 +275,7 @@ impl Sender {
                     // Put the segment we just sent on the retransmission queue.
                     let unacked_segment = UnackedSegment {
                         bytes: buf,
-                        initial_tx: Some(cb.clock.now()),
+                        initial_tx: Some(clock::now_ns()),
                     };
                     self.unacked_queue.borrow_mut().push_back(unacked_segment);
 
@@ -259,23 +292,43 @@ impl Sender {
             }
         }
 
-        // Too fast.
-        // ToDo: We need to fix this the correct way: limit our send buffer size to the amount we're willing to buffer.
-        if self.unsent_queue.borrow().len() > UNSENT_QUEUE_CUTOFF {
-            return Err(Fail::new(EBUSY, "too many packets to send"));
-        }
-
         // Slow path: Delegating sending the data to background processing.
         trace!("Queueing Send for background processing");
+        self.unsent_bytes.set(self.unsent_bytes.get() + buf.len());
         self.unsent_queue.borrow_mut().push_back(buf);
         self.unsent_seq_no.modify(|s| s + SeqNumber::from(buf_len));
 
         Ok(())
     }
 
+    // Sends several buffers, in order.  The batch is checked against the send buffer up front, so that it is either
+    // accepted as a whole or rejected without queueing any of its buffers.
+    //
+    pub fn send_batch(&self, bufs: Vec<Buffer>, cb: &ControlBlock) -> Result<(), Fail> {
+        if cb.user_is_done_sending.get() {
+            return Err(Fail::new(EINVAL, "Connection is closing"));
+        }
+
+        let mut buffered_bytes: usize = self.get_buffered_bytes();
+        for buf in &bufs {
+            if u32::try_from(buf.len()).is_err() {
+                return Err(Fail::new(EINVAL, "buffer too large"));
+            }
+            if buffered_bytes > 0 && buffered_bytes + buf.len() > self.send_buffer_size.get() {
+                return Err(Fail::new(EAGAIN, "send buffer full"));
+            }
+            buffered_bytes += buf.len();
+        }
+
+        for buf in bufs {
+            self.send(buf, cb)?;
+        }
+        Ok(())
+    }
+
     // Remove acknowledged data from the unacknowledged (a.k.a. retransmission) queue.
     //
-    pub fn remove_acknowledged_data(&self, cb: &ControlBlock, bytes_acknowledged: u32, now: Instant) {
+    pub fn remove_acknowledged_data(&self, cb: &ControlBlock, bytes_acknowledged: u32) {
         let mut bytes_remaining: usize = bytes_acknowledged as usize;
 
         while bytes_remaining != 0 {
@@ -284,7 +337,7 @@ impl Sender {
                 // Note that in the case of repacketization, an ack for the first byte is enough for the time sample.
                 // ToDo: TCP timestamp support.
                 if let Some(initial_tx) = segment.initial_tx {
-                    cb.rto_add_sample(now - initial_tx);
+                    cb.rto_add_sample(Duration::from_nanos(clock::now_ns().saturating_sub(initial_tx)));
                 }
 
                 if segment.bytes.len() > bytes_remaining {
@@ -324,6 +377,7 @@ impl Sender {
         // Pop one byte off the buf still in the queue and all but one of the bytes on our clone.
         buf.adjust(1);
         cloned_buf.trim(buf_len - 1);
+        self.unsent_bytes.set(self.unsent_bytes.get() - cloned_buf.len());
 
         Some(cloned_buf)
     }
@@ -343,6 +397,7 @@ impl Sender {
             unsent_queue.push_front(buf);
             buf = cloned_buf;
         }
+        self.unsent_bytes.set(self.unsent_bytes.get() - buf.len());
         Some(buf)
     }
 
@@ -15,7 +15,10 @@ mod sequence_number;
 mod tests;
 
 pub use self::{
-    established::congestion_control,
+    established::{
+        congestion_control,
+        TcpInfo,
+    },
     peer::TcpPeer,
     segment::{
         MAX_TCP_HEADER_SIZE,
@@ -116,6 +116,14 @@ impl TcpOperation {
             _ => panic!("Future not ready"),
         }
     }
+
+    /// Returns the queue descriptor reserved for the connection of an accept operation.
+    pub fn get_new_qd(&self) -> Option<QDesc> {
+        match self {
+            TcpOperation::Accept(FutureResult { future, .. }) => Some(future.new_qd),
+            _ => None,
+        }
+    }
 }
 
 pub struct ConnectFuture {
@@ -203,6 +211,8 @@ impl Future for PushFuture {
 pub struct PopFuture {
     pub fd: QDesc,
     pub inner: Rc<RefCell<Inner>>,
+    /// Leave received data in the receive queue?
+    pub peek: bool,
 }
 
 impl fmt::Debug for PopFuture {
@@ -219,6 +229,6 @@ impl Future for PopFuture {
         let peer = TcpPeer {
             inner: self_.inner.clone(),
         };
-        peer.poll_recv(self_.fd, ctx)
+        peer.poll_recv(self_.fd, ctx, self_.peek)
     }
 }
@@ -84,21 +84,25 @@ struct InflightAccept {
 struct ReadySockets {
     ready: VecDeque<Result<ControlBlock, Fail>>,
     endpoints: HashSet<SocketAddrV4>,
-    waker: Option<Waker>,
+    /// Accept operations waiting for a connection. There may be several of them outstanding on the same socket.
+    wakers: Vec<Waker>,
 }
 
 impl ReadySockets {
     fn push_ok(&mut self, cb: ControlBlock) {
         assert!(self.endpoints.insert(cb.get_remote()));
         self.ready.push_back(Ok(cb));
-        if let Some(w) = self.waker.take() {
-            w.wake()
-        }
+        self.wake_all();
     }
 
     fn push_err(&mut self, err: Fail) {
         self.ready.push_back(Err(err));
-        if let Some(w) = self.waker.take() {
+        self.wake_all();
+    }
+
+    /// Wakes up all waiting accept operations. Those that do not get a connection register themselves again.
+    fn wake_all(&mut self) {
+        for w in self.wakers.drain(..) {
             w.wake()
         }
     }
@@ -107,7 +111,9 @@ impl ReadySockets {
         let r = match self.ready.pop_front() {
             Some(r) => r,
             None => {
-                self.waker.replace(ctx.waker().clone());
+                if !self.wakers.iter().any(|w| w.will_wake(ctx.waker())) {
+                    self.wakers.push(ctx.waker().clone());
+                }
                 return Poll::Pending;
             },
         };
@@ -150,14 +156,15 @@ impl PassiveSocket {
         arp: ArpPeer,
         nonce: u32,
     ) -> Self {
+        // Pre-allocate state for a full backlog, so that bursts of connection requests do not hit the allocator.
         let ready = ReadySockets {
-            ready: VecDeque::new(),
-            endpoints: HashSet::new(),
-            waker: None,
+            ready: VecDeque::with_capacity(max_backlog),
+            endpoints: HashSet::with_capacity(max_backlog),
+            wakers: Vec::new(),
         };
         let ready = Rc::new(RefCell::new(ready));
         Self {
-            inflight: HashMap::new(),
+            inflight: HashMap::with_capacity(max_backlog),
             ready,
             max_backlog,
             isn_generator: IsnGenerator::new(nonce),
@@ -346,7 +353,7 @@ impl PassiveSocket {
                     ipv4_hdr: Ipv4Header::new(local.ip().clone(), remote.ip().clone(), IpProtocol::TCP),
                     tcp_hdr,
                     data: None,
-                    tx_checksum_offload: tcp_config.get_rx_checksum_offload(),
+                    tx_checksum_offload: tcp_config.get_tx_checksum_offload(),
                 };
                 rt.transmit(Box::new(segment));
                 clock.wait(clock.clone(), handshake_timeout).await;
@@ -7,7 +7,10 @@
 
 use super::{
     active_open::ActiveOpenSocket,
-    established::EstablishedSocket,
+    established::{
+        EstablishedSocket,
+        TcpInfo,
+    },
     isn_generator::IsnGenerator,
     passive_open::PassiveSocket,
 };
@@ -24,6 +27,7 @@ use crate::{
         },
         ipv4::Ipv4Header,
         tcp::{
+            constants::TIME_WAIT_TIMEOUT,
             established::ControlBlock,
             operations::{
                 AcceptFuture,
@@ -82,7 +86,10 @@ use ::std::{
         Context,
         Poll,
     },
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 #[cfg(feature = "profiler")]
@@ -204,8 +211,7 @@ impl TcpPeer {
         // Check if we have to handle wildcard port binding.
         if addr.port() == 0 {
             // Allocate ephemeral port.
-            // TODO: we should free this when closing.
-            let new_port: u16 = inner.ephemeral_ports.alloc_any()?;
+            let new_port: u16 = inner.alloc_ephemeral_port()?;
             addr.set_port(new_port);
         }
 
@@ -329,12 +335,16 @@ impl TcpPeer {
     pub fn connect(&self, qd: QDesc, remote: SocketAddrV4) -> Result<ConnectFuture, Fail> {
         let mut inner: RefMut<Inner> = self.inner.borrow_mut();
 
+        // In static mode, fail now rather than after the handshake retries.
+        if inner.arp.is_static() && inner.arp.try_query(remote.ip().clone()).is_none() {
+            return Err(Fail::new(libc::EHOSTUNREACH, "no static link address for remote"));
+        }
+
         // Get local address bound to socket.
         let local: SocketAddrV4 = match inner.sockets.get_mut(&qd) {
             // Handle unbound socket.
             Some(Socket::Inactive { local: None }) => {
-                // TODO: we should free this when closing.
-                let local_port: u16 = inner.ephemeral_ports.alloc_any()?;
+                let local_port: u16 = inner.alloc_ephemeral_port()?;
                 SocketAddrV4::new(inner.local_ipv4_addr, local_port)
             },
             // Handle bound socket.
@@ -383,7 +393,8 @@ impl TcpPeer {
         })
     }
 
-    pub fn poll_recv(&self, fd: QDesc, ctx: &mut Context) -> Poll<Result<Buffer, Fail>> {
+    /// Receives data from an established connection. If `peek` is set, data is left in the receive queue.
+    pub fn poll_recv(&self, fd: QDesc, ctx: &mut Context, peek: bool) -> Poll<Result<Buffer, Fail>> {
         let inner = self.inner.borrow_mut();
         let key = match inner.sockets.get(&fd) {
             Some(Socket::Established { local, remote }) => (*local, *remote),
@@ -393,6 +404,7 @@ impl TcpPeer {
             None => return Poll::Ready(Err(Fail::new(EBADF, "bad queue descriptor"))),
         };
         match inner.established.get(&key) {
+            Some(ref s) if peek => s.poll_peek(ctx),
             Some(ref s) => s.poll_recv(ctx),
             None => Poll::Ready(Err(Fail::new(ENOTCONN, "connection not established"))),
         }
@@ -406,10 +418,30 @@ impl TcpPeer {
         PushFuture { fd, err }
     }
 
+    /// Pushes several buffers to a TCP socket, in order. Either all buffers are accepted, or the returned future fails
+    /// and none of them is sent.
+    pub fn push_batch<I: IntoIterator<Item = Buffer>>(&self, fd: QDesc, bufs: I) -> PushFuture {
+        let err = match self.send_batch(fd, bufs.into_iter().collect()) {
+            Ok(()) => None,
+            Err(e) => Some(e),
+        };
+        PushFuture { fd, err }
+    }
+
     pub fn pop(&self, fd: QDesc) -> PopFuture {
         PopFuture {
             fd,
             inner: self.inner.clone(),
+            peek: false,
+        }
+    }
+
+    /// Same as [Self::pop], but received data is not consumed, and will be returned again by the next pop.
+    pub fn peek(&self, fd: QDesc) -> PopFuture {
+        PopFuture {
+            fd,
+            inner: self.inner.clone(),
+            peek: true,
         }
     }
 
@@ -426,6 +458,19 @@ impl TcpPeer {
         }
     }
 
+    fn send_batch(&self, fd: QDesc, bufs: Vec<Buffer>) -> Result<(), Fail> {
+        let inner = self.inner.borrow_mut();
+        let key = match inner.sockets.get(&fd) {
+            Some(Socket::Established { local, remote }) => (*local, *remote),
+            Some(..) => return Err(Fail::new(ENOTCONN, "connection not established")),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        match inner.established.get(&key) {
+            Some(ref s) => s.send_batch(bufs),
+            None => Err(Fail::new(ENOTCONN, "connection not established")),
+        }
+    }
+
     /// Closes a TCP socket.
     pub fn do_close(&self, qd: QDesc) -> Result<(), Fail> {
         let mut inner: RefMut<Inner> = self.inner.borrow_mut();
@@ -437,9 +482,23 @@ impl TcpPeer {
                     Some(ref s) => s.close()?,
                     None => return Err(Fail::new(ENOTCONN, "connection not established")),
                 }
+
+                // Hold back the local port until the connection is guaranteed to be gone.
+                inner.release_port(local, true);
             },
 
-            Some(..) => return Err(Fail::new(ENOTSUP, "close not implemented for listening sockets")),
+            // Abort the connection attempt. Dropping the active socket stops its handshake.
+            Some(Socket::Connecting { local, remote }) => {
+                inner.connecting.remove(&(local, remote));
+                inner.release_port(local, false);
+            },
+
+            Some(Socket::Inactive { local: Some(local) }) => inner.release_port(local, false),
+            Some(Socket::Inactive { local: None }) => (),
+
+            Some(Socket::Listening { .. }) => {
+                return Err(Fail::new(ENOTSUP, "close not implemented for listening sockets"))
+            },
             None => return Err(Fail::new(EBADF, "bad queue descriptor")),
         }
 
@@ -472,6 +531,48 @@ impl TcpPeer {
         }
     }
 
+    /// Takes a snapshot of the transport state of an established connection.
+    pub fn tcp_info(&self, fd: QDesc) -> Result<TcpInfo, Fail> {
+        let inner = self.inner.borrow();
+        let key = match inner.sockets.get(&fd) {
+            Some(Socket::Established { local, remote }) => (*local, *remote),
+            Some(..) => return Err(Fail::new(ENOTCONN, "connection not established")),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        match inner.established.get(&key) {
+            Some(ref s) => Ok(s.tcp_info()),
+            None => Err(Fail::new(ENOTCONN, "connection not established")),
+        }
+    }
+
+    /// Gets the send buffer size (in bytes) of an established connection.
+    pub fn get_send_buffer_size(&self, fd: QDesc) -> Result<usize, Fail> {
+        let inner = self.inner.borrow();
+        let key = match inner.sockets.get(&fd) {
+            Some(Socket::Established { local, remote }) => (*local, *remote),
+            Some(..) => return Err(Fail::new(ENOTCONN, "connection not established")),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        match inner.established.get(&key) {
+            Some(ref s) => Ok(s.get_send_buffer_size()),
+            None => Err(Fail::new(ENOTCONN, "connection not established")),
+        }
+    }
+
+    /// Sets the send buffer size (in bytes) of an established connection.
+    pub fn set_send_buffer_size(&self, fd: QDesc, size: usize) -> Result<(), Fail> {
+        let inner = self.inner.borrow();
+        let key = match inner.sockets.get(&fd) {
+            Some(Socket::Established { local, remote }) => (*local, *remote),
+            Some(..) => return Err(Fail::new(ENOTCONN, "connection not established")),
+            None => return Err(Fail::new(EBADF, "bad queue descriptor")),
+        };
+        match inner.established.get(&key) {
+            Some(ref s) => s.set_send_buffer_size(size),
+            None => Err(Fail::new(ENOTCONN, "connection not established")),
+        }
+    }
+
     pub fn endpoints(&self, fd: QDesc) -> Result<(SocketAddrV4, SocketAddrV4), Fail> {
         let inner = self.inner.borrow();
         let key = match inner.sockets.get(&fd) {
@@ -521,6 +622,28 @@ impl Inner {
         }
     }
 
+    /// Allocates an ephemeral port, after reclaiming ports whose TIME-WAIT has expired.
+    fn alloc_ephemeral_port(&mut self) -> Result<u16, Fail> {
+        let now: Instant = self.clock.now();
+        self.ephemeral_ports.reclaim(now);
+        self.ephemeral_ports.alloc_any()
+    }
+
+    /// Returns the local port of a closed or failed socket to the pool of ephemeral ports, unless it is not an ephemeral
+    /// port or a listening socket owns it. If the socket had a connection that may linger in TIME-WAIT, the port is
+    /// held back until TIME-WAIT expires.
+    fn release_port(&mut self, local: SocketAddrV4, time_wait: bool) {
+        if !EphemeralPorts::is_private(local.port()) || self.passive.contains_key(&local) {
+            return;
+        }
+        if time_wait {
+            let deadline: Instant = self.clock.now() + TIME_WAIT_TIMEOUT;
+            self.ephemeral_ports.free_after(local.port(), deadline);
+        } else {
+            self.ephemeral_ports.free(local.port());
+        }
+    }
+
     fn receive(&mut self, ip_hdr: &Ipv4Header, buf: Buffer) -> Result<(), Fail> {
         let (mut tcp_hdr, data) = TcpHeader::parse(ip_hdr, buf, self.tcp_config.get_rx_checksum_offload())?;
         debug!("TCP received {:?}", tcp_hdr);
@@ -569,7 +692,7 @@ impl Inner {
             ipv4_hdr: Ipv4Header::new(local.ip().clone(), remote.ip().clone(), IpProtocol::TCP),
             tcp_hdr,
             data: None,
-            tx_checksum_offload: self.tcp_config.get_rx_checksum_offload(),
+            tx_checksum_offload: self.tcp_config.get_tx_checksum_offload(),
         };
         self.rt.transmit(Box::new(segment));
 
@@ -595,7 +718,15 @@ impl Inner {
         };
         self.connecting.remove(&key);
 
-        let cb = result?;
+        let cb = match result {
+            Ok(cb) => cb,
+            // The connection was refused or timed out, so leave the socket unbound and release its port.
+            Err(e) => {
+                self.sockets.insert(fd, Socket::Inactive { local: None });
+                self.release_port(key.0, false);
+                return Poll::Ready(Err(e));
+            },
+        };
         let socket = EstablishedSocket::new(cb, fd, self.dead_socket_tx.clone());
         assert!(self.established.insert(key, socket).is_none());
         let (local, remote) = key;
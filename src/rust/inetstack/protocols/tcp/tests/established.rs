@@ -4,6 +4,7 @@
 use crate::{
     inetstack::{
         protocols::tcp::{
+            established::State,
             operations::PushFuture,
             tests::{
                 check_packet_data,
@@ -11,9 +12,11 @@ use crate::{
                 setup::{
                     advance_clock,
                     connection_setup,
+                    WakeCounter,
                 },
             },
             SeqNumber,
+            TcpInfo,
         },
         test_helpers::{
             self,
@@ -25,19 +28,31 @@ use crate::{
             Buffer,
             DataBuffer,
         },
+        network::config::TcpConfig,
         QDesc,
     },
 };
-use ::futures::task::noop_waker_ref;
+use ::futures::task::{
+    self,
+    noop_waker_ref,
+};
 use ::rand;
 use ::std::{
     collections::VecDeque,
     future::Future,
     net::SocketAddrV4,
     pin::Pin,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
     task::{
         Context,
         Poll,
+        Waker,
     },
     time::Instant,
 };
@@ -445,3 +460,454 @@ fn test_connect_disconnect() {
 
     connection_hangup(&mut ctx, &mut now, &mut server, &mut client, server_fd, client_fd);
 }
+
+//=============================================================================
+
+/// Pushes one segment per stamp from the client and returns the resulting frames.
+fn send_segments(
+    ctx: &mut Context,
+    now: &mut Instant,
+    server: &mut Engine,
+    client: &mut Engine,
+    client_fd: QDesc,
+    window_size: u16,
+    bufsize: u32,
+    count: u8,
+) -> Vec<Buffer> {
+    let mut frames: Vec<Buffer> = Vec::new();
+    for i in 0..count {
+        let (bytes, _): (Buffer, usize) = send_data(
+            ctx,
+            now,
+            server,
+            client,
+            client_fd,
+            window_size,
+            SeqNumber::from(1 + i as u32 * bufsize),
+            None,
+            cook_buffer(bufsize as usize, Some(i)),
+        );
+        frames.push(bytes);
+    }
+    frames
+}
+
+/// Pops a segment and checks that it carries the expected stamp.
+fn pop_stamped(ctx: &mut Context, receiver: &mut Engine, receiver_fd: QDesc, stamp: u8) {
+    let mut pop_future = receiver.tcp_pop(receiver_fd);
+    match Future::poll(Pin::new(&mut pop_future), ctx) {
+        Poll::Ready(Ok(buf)) => assert!(buf.iter().all(|b| *b == stamp)),
+        _ => panic!("segment {} should have been received", stamp),
+    }
+}
+
+//=============================================================================
+
+/// Tests that segments received out of order are reassembled in order.
+#[test]
+fn test_recv_out_of_order() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    let frames: Vec<Buffer> = send_segments(
+        &mut ctx,
+        &mut now,
+        &mut server,
+        &mut client,
+        client_fd,
+        window_size,
+        64,
+        4,
+    );
+
+    // Deliver segments in reverse order.
+    for bytes in frames.into_iter().rev() {
+        server.receive(bytes).unwrap();
+    }
+
+    for stamp in 0..4 {
+        pop_stamped(&mut ctx, &mut server, server_fd, stamp);
+    }
+}
+
+//=============================================================================
+
+/// Tests that out-of-order segments beyond the configured limit are dropped.
+#[test]
+fn test_recv_out_of_order_limit() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let tcp_config: TcpConfig = TcpConfig::new(None, None, None, None, None, None, None, None, Some(2), None);
+    let mut server: Engine = test_helpers::new_bob2_with_tcp_config(now, tcp_config);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    let frames: Vec<Buffer> = send_segments(
+        &mut ctx,
+        &mut now,
+        &mut server,
+        &mut client,
+        client_fd,
+        window_size,
+        64,
+        4,
+    );
+
+    // Only the first two segments that arrive ahead of the gap are kept.
+    for bytes in frames[1..].iter() {
+        server.receive(bytes.clone()).unwrap();
+    }
+    server.receive(frames[0].clone()).unwrap();
+    for stamp in 0..3 {
+        pop_stamped(&mut ctx, &mut server, server_fd, stamp);
+    }
+    let mut pop_future = server.tcp_pop(server_fd);
+    assert!(Future::poll(Pin::new(&mut pop_future), &mut ctx).is_pending());
+
+    // The dropped segment is accepted once it is retransmitted.
+    server.receive(frames[3].clone()).unwrap();
+    assert!(matches!(
+        Future::poll(Pin::new(&mut pop_future), &mut ctx),
+        Poll::Ready(Ok(_))
+    ));
+}
+
+//=============================================================================
+
+/// Tests that pushes are rejected once the send buffer fills up.
+#[test]
+fn test_send_buffer_limit() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+
+    let (_, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // Shrink the send buffer.
+    let bufsize: usize = 1000;
+    let send_buffer_size: usize = 4 * bufsize;
+    assert!(client.ipv4.tcp.set_send_buffer_size(client_fd, 0).is_err());
+    client
+        .ipv4
+        .tcp
+        .set_send_buffer_size(client_fd, send_buffer_size)
+        .unwrap();
+    assert_eq!(
+        client.ipv4.tcp.get_send_buffer_size(client_fd).unwrap(),
+        send_buffer_size
+    );
+
+    // Push data without ever acknowledging it. The window is larger than the send buffer, so all data is sent right
+    // away, and unacknowledged data alone fills the send buffer.
+    let mut npushes: usize = 0;
+    let errno: i32 = loop {
+        let mut push_future: PushFuture = client.tcp_push(client_fd, cook_buffer(bufsize, None));
+        match Future::poll(Pin::new(&mut push_future), &mut ctx) {
+            Poll::Ready(Ok(())) => npushes += 1,
+            Poll::Ready(Err(e)) => break e.errno,
+            Poll::Pending => panic!("push should complete right away"),
+        }
+        assert!(npushes < 1024, "send buffer is not enforced");
+    };
+    assert_eq!(errno, libc::EAGAIN);
+    assert_eq!(npushes, send_buffer_size / bufsize);
+    let info: TcpInfo = client.ipv4.tcp.tcp_info(client_fd).unwrap();
+    assert_eq!(info.bytes_in_flight as usize, send_buffer_size);
+    assert_eq!(info.unsent_bytes, 0);
+}
+
+//=============================================================================
+
+/// Tests that a batch that does not fit in the send buffer is rejected as a whole.
+#[test]
+fn test_push_batch_send_buffer_limit() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+
+    let (_, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    let bufsize: usize = 1000;
+    client.ipv4.tcp.set_send_buffer_size(client_fd, 4 * bufsize).unwrap();
+
+    // Push a buffer that is never acknowledged.
+    let mut push_future: PushFuture = client.tcp_push(client_fd, cook_buffer(bufsize, None));
+    match Future::poll(Pin::new(&mut push_future), &mut ctx) {
+        Poll::Ready(Ok(())) => (),
+        _ => panic!("push should succeed"),
+    }
+
+    // The send buffer has room for three more buffers, so a batch of four fails partway through and is dropped.
+    let bufs: Vec<Buffer> = (0..4).map(|_| cook_buffer(bufsize, None)).collect();
+    let mut push_future: PushFuture = client.tcp_push_batch(client_fd, bufs);
+    match Future::poll(Pin::new(&mut push_future), &mut ctx) {
+        Poll::Ready(Err(e)) => assert_eq!(e.errno, libc::EAGAIN),
+        _ => panic!("batch should be rejected"),
+    }
+    assert_eq!(
+        client.ipv4.tcp.tcp_info(client_fd).unwrap().bytes_in_flight as usize,
+        bufsize
+    );
+
+    // A batch of three fits.
+    let bufs: Vec<Buffer> = (0..3).map(|_| cook_buffer(bufsize, None)).collect();
+    let mut push_future: PushFuture = client.tcp_push_batch(client_fd, bufs);
+    match Future::poll(Pin::new(&mut push_future), &mut ctx) {
+        Poll::Ready(Ok(())) => (),
+        _ => panic!("batch should be accepted"),
+    }
+    assert_eq!(
+        client.ipv4.tcp.tcp_info(client_fd).unwrap().bytes_in_flight as usize,
+        4 * bufsize
+    );
+}
+
+//=============================================================================
+
+/// Tests that peeking at received data does not consume it.
+#[test]
+fn test_peek() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // Nothing to peek at yet.
+    let mut peek_future = server.tcp_peek(server_fd);
+    assert!(Future::poll(Pin::new(&mut peek_future), &mut ctx).is_pending());
+
+    let (bytes, _): (Buffer, usize) = send_data(
+        &mut ctx,
+        &mut now,
+        &mut server,
+        &mut client,
+        client_fd,
+        window_size,
+        SeqNumber::from(1),
+        None,
+        cook_buffer(32, None),
+    );
+    server.receive(bytes).unwrap();
+
+    // Peeking twice returns the same data.
+    let expected: Buffer = cook_buffer(32, None);
+    for _ in 0..2 {
+        let mut peek_future = server.tcp_peek(server_fd);
+        match Future::poll(Pin::new(&mut peek_future), &mut ctx) {
+            Poll::Ready(Ok(buf)) => assert_eq!(&buf[..], &expected[..]),
+            _ => panic!("peek should complete"),
+        }
+    }
+
+    // The data is still there to be popped.
+    let mut pop_future = server.tcp_pop(server_fd);
+    match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+        Poll::Ready(Ok(buf)) => assert_eq!(&buf[..], &expected[..]),
+        _ => panic!("pop should complete"),
+    }
+    let mut pop_future = server.tcp_pop(server_fd);
+    assert!(Future::poll(Pin::new(&mut pop_future), &mut ctx).is_pending());
+}
+
+//=============================================================================
+
+/// Tests that a pending pop and a pending peek on the same connection are both woken up when data arrives.
+#[test]
+fn test_peek_and_pop_wakeup() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // Wait for data with a pop and a peek, each with its own waker.
+    let counters: Vec<Arc<WakeCounter>> = (0..2).map(|_| Arc::new(WakeCounter(AtomicUsize::new(0)))).collect();
+    let wakers: Vec<Waker> = counters.iter().map(|c| task::waker(c.clone())).collect();
+    let mut pop_future = server.tcp_pop(server_fd);
+    let mut peek_future = server.tcp_peek(server_fd);
+    assert!(Future::poll(Pin::new(&mut pop_future), &mut Context::from_waker(&wakers[0])).is_pending());
+    assert!(Future::poll(Pin::new(&mut peek_future), &mut Context::from_waker(&wakers[1])).is_pending());
+
+    let (bytes, _): (Buffer, usize) = send_data(
+        &mut ctx,
+        &mut now,
+        &mut server,
+        &mut client,
+        client_fd,
+        window_size,
+        SeqNumber::from(1),
+        None,
+        cook_buffer(32, None),
+    );
+    server.receive(bytes).unwrap();
+
+    // Both operations are woken up, and both see the data.
+    for counter in counters.iter() {
+        assert!(counter.0.load(Ordering::SeqCst) > 0);
+    }
+    let expected: Buffer = cook_buffer(32, None);
+    match Future::poll(Pin::new(&mut peek_future), &mut Context::from_waker(&wakers[1])) {
+        Poll::Ready(Ok(buf)) => assert_eq!(&buf[..], &expected[..]),
+        _ => panic!("peek should complete"),
+    }
+    match Future::poll(Pin::new(&mut pop_future), &mut Context::from_waker(&wakers[0])) {
+        Poll::Ready(Ok(buf)) => assert_eq!(&buf[..], &expected[..]),
+        _ => panic!("pop should complete"),
+    }
+}
+
+//=============================================================================
+
+/// Tests that popped data starts at the first payload byte, regardless of IPv4 options and link-layer padding.
+#[test]
+fn test_pop_payload_offset() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    // No byte of the payload matches its offset, so any misplaced header boundary shows up.
+    let payload: Vec<u8> = (0..5).map(|i| 0xa1 + i).collect::<Vec<u8>>();
+    for (i, &(options_len, padding_len)) in [(0, 0), (0, 11), (4, 0), (40, 7)].iter().enumerate() {
+        let (bytes, _): (Buffer, usize) = send_data(
+            &mut ctx,
+            &mut now,
+            &mut server,
+            &mut client,
+            client_fd,
+            window_size,
+            SeqNumber::from(1 + (i * payload.len()) as u32),
+            None,
+            Buffer::Heap(DataBuffer::from_slice(&payload)),
+        );
+        let frame: Buffer = test_helpers::add_ipv4_options_and_padding(&bytes, options_len, padding_len);
+        server.receive(frame).unwrap();
+
+        let mut pop_future = server.tcp_pop(server_fd);
+        match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+            Poll::Ready(Ok(buf)) => {
+                assert_eq!(buf[0], payload[0]);
+                assert_eq!(&buf[..], &payload[..]);
+            },
+            _ => panic!(
+                "pop should complete (options_len={}, padding_len={})",
+                options_len, padding_len
+            ),
+        }
+    }
+}
+
+//=============================================================================
+
+/// Tests that connection-level statistics track data in flight and round-trip time samples.
+#[test]
+fn test_tcp_info() {
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server: Engine = test_helpers::new_bob2(now);
+    let mut client: Engine = test_helpers::new_alice2(now);
+    let window_size: u16 = client.rt.tcp_config.get_receive_window_size();
+    let (server_fd, client_fd): (QDesc, QDesc) =
+        connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
+
+    let info: TcpInfo = client.ipv4.tcp.tcp_info(client_fd).unwrap();
+    assert_eq!(info.state, State::Established);
+    assert_eq!(info.bytes_in_flight, 0);
+    assert_eq!(info.retransmissions, 0);
+
+    // Data that was sent but not acknowledged is in flight.
+    let bufsize: usize = 32;
+    let (bytes, _): (Buffer, usize) = send_data(
+        &mut ctx,
+        &mut now,
+        &mut server,
+        &mut client,
+        client_fd,
+        window_size,
+        SeqNumber::from(1),
+        None,
+        cook_buffer(bufsize, None),
+    );
+    let info: TcpInfo = client.ipv4.tcp.tcp_info(client_fd).unwrap();
+    assert_eq!(info.bytes_in_flight, bufsize as u32);
+
+    // Once acknowledged, the round-trip time is known.
+    recv_data(&mut ctx, &mut server, &mut client, server_fd, bytes);
+    recv_pure_ack(&mut now, &mut server, &mut client, SeqNumber::from(1 + bufsize as u32));
+    let info: TcpInfo = client.ipv4.tcp.tcp_info(client_fd).unwrap();
+    assert_eq!(info.bytes_in_flight, 0);
+    assert!(info.srtt.is_some());
+
+    // Only established connections have statistics.
+    let fd: QDesc = client.tcp_socket().unwrap();
+    assert!(client.ipv4.tcp.tcp_info(fd).is_err());
+}
@@ -2,6 +2,7 @@
 // Licensed under the MIT license.
 
 pub mod established;
+pub mod segment;
 pub mod setup;
 
 use crate::{
@@ -0,0 +1,166 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+use crate::{
+    inetstack::{
+        protocols::{
+            ethernet2::Ethernet2Header,
+            ip::IpProtocol,
+            ipv4::Ipv4Header,
+            tcp::{
+                operations::ConnectFuture,
+                segment::{
+                    TcpHeader,
+                    TcpOptions2,
+                },
+                SeqNumber,
+            },
+        },
+        test_helpers::{
+            self,
+            Engine,
+        },
+    },
+    runtime::{
+        memory::{
+            Buffer,
+            DataBuffer,
+        },
+        network::config::TcpConfig,
+        QDesc,
+    },
+};
+use ::byteorder::{
+    ByteOrder,
+    NetworkEndian,
+};
+use ::std::{
+    net::{
+        Ipv4Addr,
+        SocketAddrV4,
+    },
+    time::Instant,
+};
+
+//=============================================================================
+
+/// Builds a TCP header that carries options.
+fn tcp_header() -> TcpHeader {
+    let mut tcp_hdr: TcpHeader = TcpHeader::new(0x1234, 80);
+    tcp_hdr.seq_num = SeqNumber::from(0xdeadbeef);
+    tcp_hdr.ack_num = SeqNumber::from(0x01020304);
+    tcp_hdr.ack = true;
+    tcp_hdr.psh = true;
+    tcp_hdr.window_size = 0xffff;
+    tcp_hdr.push_option(TcpOptions2::MaximumSegmentSize(1460));
+    tcp_hdr.push_option(TcpOptions2::WindowScale(7));
+    tcp_hdr
+}
+
+/// Serializes a TCP header along with a payload, and returns the resulting segment.
+fn serialize(tcp_hdr: &TcpHeader, ipv4_hdr: &Ipv4Header, data: &[u8], tx_checksum_offload: bool) -> Vec<u8> {
+    let mut hdr: Vec<u8> = vec![0; tcp_hdr.compute_size()];
+    tcp_hdr.serialize(&mut hdr, ipv4_hdr, data, tx_checksum_offload);
+    [&hdr[..], data].concat()
+}
+
+/// Computes the checksum of a TCP segment with the reference implementation.
+fn expected_checksum(ipv4_hdr: &Ipv4Header, segment: &[u8]) -> u16 {
+    let mut segment: Vec<u8> = segment.to_vec();
+    segment[16..18].fill(0);
+    test_helpers::reference_checksum(
+        ipv4_hdr.get_src_addr(),
+        ipv4_hdr.get_dest_addr(),
+        IpProtocol::TCP,
+        &segment,
+    )
+}
+
+//=============================================================================
+
+/// Tests TCP checksum computation against a reference implementation, for even and odd payload sizes.
+#[test]
+fn test_tcp_checksum() {
+    let ipv4_hdr: Ipv4Header = Ipv4Header::new(test_helpers::ALICE_IPV4, test_helpers::BOB_IPV4, IpProtocol::TCP);
+    let tcp_hdr: TcpHeader = tcp_header();
+
+    for len in [0, 1, 7, 8, 1000, 1001] {
+        let data: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+        let segment: Vec<u8> = serialize(&tcp_hdr, &ipv4_hdr, &data, false);
+        let checksum: u16 = NetworkEndian::read_u16(&segment[16..18]);
+        assert_eq!(checksum, expected_checksum(&ipv4_hdr, &segment));
+
+        // The segment should be accepted as is.
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&segment));
+        let (parsed, payload): (TcpHeader, Buffer) = TcpHeader::parse(&ipv4_hdr, buf, false).unwrap();
+        assert_eq!(parsed.seq_num, tcp_hdr.seq_num);
+        assert_eq!(&payload[..], &data[..]);
+    }
+}
+
+/// Tests that corrupted TCP segments are rejected, unless checksum verification is offloaded.
+#[test]
+fn test_tcp_checksum_corrupted() {
+    let ipv4_hdr: Ipv4Header = Ipv4Header::new(test_helpers::ALICE_IPV4, test_helpers::BOB_IPV4, IpProtocol::TCP);
+    let mut segment: Vec<u8> = serialize(&tcp_header(), &ipv4_hdr, b"hello", false);
+    let last: usize = segment.len() - 1;
+    segment[last] ^= 0x1;
+
+    let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&segment));
+    assert!(TcpHeader::parse(&ipv4_hdr, buf.clone(), false).is_err());
+    assert!(TcpHeader::parse(&ipv4_hdr, buf, true).is_ok());
+
+    // A segment that was addressed to someone else fails the pseudo-header check.
+    let other_ipv4_hdr: Ipv4Header =
+        Ipv4Header::new(test_helpers::ALICE_IPV4, Ipv4Addr::new(192, 168, 1, 4), IpProtocol::TCP);
+    let segment: Vec<u8> = serialize(&tcp_header(), &ipv4_hdr, b"hello", false);
+    let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&segment));
+    assert!(TcpHeader::parse(&other_ipv4_hdr, buf, false).is_err());
+}
+
+/// Tests that the checksum is left for the hardware to fill when checksum computation is offloaded.
+#[test]
+fn test_tcp_checksum_tx_offload() {
+    let ipv4_hdr: Ipv4Header = Ipv4Header::new(test_helpers::ALICE_IPV4, test_helpers::BOB_IPV4, IpProtocol::TCP);
+    let segment: Vec<u8> = serialize(&tcp_header(), &ipv4_hdr, b"hello", true);
+    assert_eq!(NetworkEndian::read_u16(&segment[16..18]), 0);
+}
+
+/// Tests that segments sent during connection setup honor the transmit checksum offload setting, independently of
+/// the receive one.
+#[test]
+fn test_connect_tx_checksum_offload() {
+    let now: Instant = Instant::now();
+    let remote: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, 80);
+
+    for (rx_checksum_offload, tx_checksum_offload) in [(false, false), (false, true), (true, false), (true, true)] {
+        let tcp_config: TcpConfig = TcpConfig::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(rx_checksum_offload),
+            Some(tx_checksum_offload),
+            None,
+            None,
+        );
+        let mut client: Engine = test_helpers::new_alice2_with_tcp_config(now, tcp_config);
+
+        // Send SYN.
+        let client_fd: QDesc = client.tcp_socket().unwrap();
+        let _connect_future: ConnectFuture = client.tcp_connect(client_fd, remote);
+        client.rt.poll_scheduler();
+        let bytes: Buffer = client.rt.pop_frame();
+
+        let (_, eth2_payload): (Ethernet2Header, Buffer) = Ethernet2Header::parse(bytes).unwrap();
+        let (ipv4_hdr, ipv4_payload): (Ipv4Header, Buffer) = Ipv4Header::parse(eth2_payload).unwrap();
+        let checksum: u16 = NetworkEndian::read_u16(&ipv4_payload[16..18]);
+        if tx_checksum_offload {
+            assert_eq!(checksum, 0);
+        } else {
+            assert_eq!(checksum, expected_checksum(&ipv4_hdr, &ipv4_payload[..]));
+        }
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
                 EtherType2,
                 Ethernet2Header,
             },
+            ip::IpProtocol,
             ipv4::Ipv4Header,
             tcp::{
                 operations::{
@@ -38,9 +39,14 @@ use crate::{
         QDesc,
     },
 };
-use ::futures::task::noop_waker_ref;
+use ::futures::task::{
+    self,
+    noop_waker_ref,
+    ArcWake,
+};
 use ::libc::{
     EBADMSG,
+    ECONNREFUSED,
     ETIMEDOUT,
 };
 use ::std::{
@@ -50,9 +56,17 @@ use ::std::{
         SocketAddrV4,
     },
     pin::Pin,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
     task::{
         Context,
         Poll,
+        Waker,
     },
     time::{
         Duration,
@@ -324,6 +338,53 @@ fn test_refuse_connection_missing_syn() {
 
 //=============================================================================
 
+/// Churns through more connection attempts than there are ephemeral ports. Half of them are refused by the remote, and
+/// the other half are closed before they are established. Both must return their local port to the pool.
+#[test]
+fn test_connect_close_churn() {
+    const NUM_CONNECTIONS: usize = 40_000;
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    let now = Instant::now();
+
+    // Connection parameters
+    let remote_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, 80);
+
+    // Setup client.
+    let mut client: Engine = test_helpers::new_alice2(now);
+
+    for i in 0..NUM_CONNECTIONS {
+        // Client: SYN_SENT state.
+        let (client_fd, mut connect_future, bytes): (QDesc, ConnectFuture, Buffer) =
+            connection_setup_listen_syn_sent(&mut client, remote_addr);
+
+        if i % 2 == 0 {
+            // Refuse the connection.
+            let (_, _, syn_header): (Ethernet2Header, Ipv4Header, TcpHeader) = extract_headers(bytes);
+            let mut tcp_hdr: TcpHeader = TcpHeader::new(syn_header.dst_port, syn_header.src_port);
+            tcp_hdr.ack = true;
+            tcp_hdr.ack_num = syn_header.seq_num + SeqNumber::from(1);
+            tcp_hdr.rst = true;
+            let segment: TcpSegment = TcpSegment {
+                ethernet2_hdr: Ethernet2Header::new(test_helpers::ALICE_MAC, test_helpers::BOB_MAC, EtherType2::Ipv4),
+                ipv4_hdr: Ipv4Header::new(test_helpers::BOB_IPV4, test_helpers::ALICE_IPV4, IpProtocol::TCP),
+                tcp_hdr,
+                data: None,
+                tx_checksum_offload: false,
+            };
+            client.receive(serialize_segment(segment)).unwrap();
+
+            match Future::poll(Pin::new(&mut connect_future), &mut ctx) {
+                Poll::Ready(Err(error)) if error.errno == ECONNREFUSED => (),
+                _ => panic!("connection should have been refused (i={})", i),
+            }
+        }
+
+        client.tcp_close(client_fd).unwrap();
+    }
+}
+
+//=============================================================================
+
 /// Extracts headers of a TCP packet.
 fn extract_headers(bytes: Buffer) -> (Ethernet2Header, Ipv4Header, TcpHeader) {
     let (eth2_header, eth2_payload) = Ethernet2Header::parse(bytes).unwrap();
@@ -578,3 +639,68 @@ fn test_good_connect() {
     let (_, _): (QDesc, QDesc) =
         connection_setup(&mut ctx, &mut now, &mut server, &mut client, listen_port, listen_addr);
 }
+
+/// Waker that counts how many times it was woken up.
+pub struct WakeCounter(pub AtomicUsize);
+
+impl ArcWake for WakeCounter {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Tests that several accept operations may be outstanding on the same listening socket.
+#[test]
+fn test_multiple_accepts() {
+    let mut now = Instant::now();
+
+    // Connection parameters
+    let listen_port: u16 = 80;
+    let listen_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, listen_port);
+
+    // Setup peers.
+    let mut server = test_helpers::new_bob2(now);
+    let mut client = test_helpers::new_alice2(now);
+
+    // Server: issue two ACCEPT operations on the same socket.
+    let socket_fd: QDesc = server.tcp_socket().unwrap();
+    server.tcp_bind(socket_fd, listen_addr).unwrap();
+    server.tcp_listen(socket_fd, 2).unwrap();
+    let counters: Vec<Arc<WakeCounter>> = (0..2).map(|_| Arc::new(WakeCounter(AtomicUsize::new(0)))).collect();
+    let wakers: Vec<Waker> = counters.iter().map(|c| task::waker(c.clone())).collect();
+    let mut accept_futures: Vec<AcceptFuture> = (0..2).map(|_| server.tcp_accept(socket_fd)).collect();
+    for (accept_future, waker) in accept_futures.iter_mut().zip(wakers.iter()) {
+        let mut ctx: Context = Context::from_waker(waker);
+        assert!(Future::poll(Pin::new(accept_future), &mut ctx).is_pending());
+    }
+    server.rt.poll_scheduler();
+
+    // Client: open two connections.
+    advance_clock(Some(&mut server), Some(&mut client), &mut now);
+    let mut connect_futures: Vec<ConnectFuture> = Vec::new();
+    for _ in 0..2 {
+        let (_, connect_future, bytes): (QDesc, ConnectFuture, Buffer) =
+            connection_setup_listen_syn_sent(&mut client, listen_addr);
+        connect_futures.push(connect_future);
+        let bytes: Buffer = connection_setup_listen_syn_rcvd(&mut server, bytes);
+        let bytes: Buffer = connection_setup_syn_sent_established(&mut client, bytes);
+        connection_setup_sync_rcvd_established(&mut server, bytes);
+    }
+
+    // Both accept operations should have been woken up and completed.
+    for ((accept_future, waker), counter) in accept_futures.iter_mut().zip(wakers.iter()).zip(counters.iter()) {
+        assert!(counter.0.load(Ordering::SeqCst) > 0);
+        let mut ctx: Context = Context::from_waker(waker);
+        match Future::poll(Pin::new(accept_future), &mut ctx) {
+            Poll::Ready(Ok(_)) => (),
+            _ => panic!("accept should have completed"),
+        }
+    }
+    let mut ctx = Context::from_waker(noop_waker_ref());
+    for connect_future in connect_futures.iter_mut() {
+        match Future::poll(Pin::new(connect_future), &mut ctx) {
+            Poll::Ready(Ok(_)) => (),
+            _ => panic!("connect should have completed"),
+        }
+    }
+}
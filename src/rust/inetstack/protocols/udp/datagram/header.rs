@@ -144,8 +144,6 @@ impl UdpHeader {
     /// pseudo header of information from the IP header, the UDP header, and the
     /// data,  padded  with zero octets at the end (if  necessary)  to  make  a
     /// multiple of two octets.
-    ///
-    /// TODO: Write a unit test for this function.
     fn checksum(ipv4_hdr: &Ipv4Header, udp_hdr: &[u8], data: &[u8]) -> u16 {
         let mut state: u32 = 0xffffu32;
 
@@ -209,6 +207,7 @@ impl UdpHeader {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::inetstack::test_helpers;
     use ::std::net::Ipv4Addr;
 
     /// Builds a fake Ipv4 Header.
@@ -272,4 +271,42 @@ mod test {
             },
         }
     }
+
+    /// Tests UDP checksum computation against a reference implementation, for even and odd payload sizes.
+    #[test]
+    fn test_udp_checksum() {
+        // Build fake IPv4 header.
+        let ipv4_hdr: Ipv4Header = ipv4_header();
+
+        // Build fake UDP header.
+        let udp_hdr: UdpHeader = UdpHeader::new(0x32, 0x45);
+
+        for len in [0, 1, 7, 8, 1000, 1001] {
+            // Payload.
+            let data: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+
+            // Do it.
+            let mut hdr: [u8; UDP_HEADER_SIZE] = [0; UDP_HEADER_SIZE];
+            udp_hdr.serialize(&mut hdr, &ipv4_hdr, &data, false);
+            let checksum: u16 = NetworkEndian::read_u16(&hdr[6..8]);
+
+            // Compare against the reference implementation.
+            let mut segment: Vec<u8> = [&hdr[..], &data[..]].concat();
+            segment[6..8].fill(0);
+            let expected: u16 = test_helpers::reference_checksum(
+                ipv4_hdr.get_src_addr(),
+                ipv4_hdr.get_dest_addr(),
+                IpProtocol::UDP,
+                &segment,
+            );
+            assert_eq!(checksum, expected);
+
+            // The datagram should be accepted as is, and rejected once corrupted.
+            let mut buf: Vec<u8> = [&hdr[..], &data[..]].concat();
+            assert!(UdpHeader::parse_from_slice(&ipv4_hdr, &buf, false).is_ok());
+            buf[0] ^= 0x1;
+            assert!(UdpHeader::parse_from_slice(&ipv4_hdr, &buf, false).is_err());
+            assert!(UdpHeader::parse_from_slice(&ipv4_hdr, &buf, true).is_ok());
+        }
+    }
 }
@@ -94,14 +94,18 @@ pub struct UdpPeer {
     sockets: HashMap<QDesc, Option<SocketAddrV4>>,
     /// Bound sockets.
     bound: HashMap<SocketAddrV4, SharedQueue<SharedQueueSlot<Buffer>>>,
+    /// Default socket, which receives datagrams addressed to ports that are not bound.
+    default: Option<(QDesc, SharedQueue<SharedQueueSlot<Buffer>>)>,
     /// Queue of unset datagrams. This is shared across fast/slow paths.
     send_queue: SharedQueue<SharedQueueSlot<Buffer>>,
     /// Local link address.
     local_link_addr: MacAddress,
     /// Local IPv4 address.
     local_ipv4_addr: Ipv4Addr,
-    /// Offload checksum to hardware?
-    checksum_offload: bool,
+    /// Offload checksum verification to hardware?
+    rx_checksum_offload: bool,
+    /// Offload checksum computation to hardware?
+    tx_checksum_offload: bool,
 
     /// The background co-routine sends unset UDP packets.
     /// We annotate it as unused because the compiler believes that it is never called which is not the case.
@@ -122,7 +126,8 @@ impl UdpPeer {
         rng_seed: [u8; 32],
         local_link_addr: MacAddress,
         local_ipv4_addr: Ipv4Addr,
-        offload_checksum: bool,
+        rx_checksum_offload: bool,
+        tx_checksum_offload: bool,
         arp: ArpPeer,
     ) -> Result<Self, Fail> {
         let send_queue: SharedQueue<SharedQueueSlot<Buffer>> =
@@ -131,7 +136,7 @@ impl UdpPeer {
             rt.clone(),
             local_ipv4_addr,
             local_link_addr,
-            offload_checksum,
+            tx_checksum_offload,
             arp.clone(),
             send_queue.clone(),
         );
@@ -152,10 +157,12 @@ impl UdpPeer {
             ephemeral_ports,
             sockets: HashMap::new(),
             bound: HashMap::new(),
+            default: None,
             send_queue,
             local_link_addr,
             local_ipv4_addr,
-            checksum_offload: offload_checksum,
+            rx_checksum_offload,
+            tx_checksum_offload,
             background: handle,
         })
     }
@@ -219,6 +226,11 @@ impl UdpPeer {
         #[cfg(feature = "profiler")]
         timer!("udp::bind");
 
+        // The default socket receives datagrams for any unbound port, so it cannot be bound to a single one.
+        if self.is_default(qd) {
+            return Err(Fail::new(libc::EINVAL, "cannot bind default socket"));
+        }
+
         // Local endpoint address in use.
         if self.bound.contains_key(&addr) {
             return Err(Fail::new(libc::EADDRINUSE, "address in use"));
@@ -230,7 +242,6 @@ impl UdpPeer {
             self.ephemeral_ports.alloc_port(addr.port())?
         } else if addr.port() == 0 {
             // Allocate ephemeral port.
-            // TODO: we should free this when closing.
             let new_port: u16 = self.ephemeral_ports.alloc_any()?;
             addr.set_port(new_port);
         }
@@ -266,6 +277,28 @@ impl UdpPeer {
         }
     }
 
+    /// Turns a UDP socket into the default socket. Datagrams that are addressed to ports that are not bound are
+    /// delivered to the default socket as is, with their UDP header, instead of being dropped.
+    pub fn do_bind_default(&mut self, qd: QDesc) -> Result<(), Fail> {
+        #[cfg(feature = "profiler")]
+        timer!("udp::bind_default");
+
+        // Default socket in use.
+        if self.default.is_some() {
+            return Err(Fail::new(libc::EADDRINUSE, "default socket in use"));
+        }
+
+        match self.sockets.get(&qd) {
+            Some(s) if s.is_none() => {
+                let queue: SharedQueue<SharedQueueSlot<Buffer>> =
+                    SharedQueue::<SharedQueueSlot<Buffer>>::new(RECV_QUEUE_MAX_SIZE);
+                self.default = Some((qd, queue));
+                Ok(())
+            },
+            _ => Err(Fail::new(libc::EBADF, "invalid queue descriptor")),
+        }
+    }
+
     /// Closes a UDP socket.
     pub fn do_close(&mut self, qd: QDesc) -> Result<(), Fail> {
         #[cfg(feature = "profiler")]
@@ -277,9 +310,20 @@ impl UdpPeer {
             None => return Err(Fail::new(EBADF, "invalid queue descriptor")),
         };
 
+        // Release the default socket, if this was it.
+        if self.is_default(qd) {
+            self.default = None;
+        }
+
         // Remove endpoint binding.
         match socket {
-            Some(local) if self.bound.remove(&local).is_some() => Ok(()),
+            Some(local) if self.bound.remove(&local).is_some() => {
+                if EphemeralPorts::is_private(local.port()) {
+                    self.ephemeral_ports.free(local.port());
+                }
+                Ok(())
+            },
+            None => Ok(()),
             _ => return Err(Fail::new(EBADF, "invalid queue descriptor")),
         }
     }
@@ -305,7 +349,7 @@ impl UdpPeer {
                 data,
                 &local,
                 &remote,
-                self.checksum_offload,
+                self.tx_checksum_offload,
             );
         }
         // Slow path: Defer send operation to the async path.
@@ -324,6 +368,7 @@ impl UdpPeer {
         // Lookup associated receiver-side shared queue.
         let recv_queue: SharedQueue<SharedQueueSlot<Buffer>> = match self.sockets.get(&qd) {
             Some(s) if s.is_some() => self.bound.get(&s.unwrap()).unwrap().clone(),
+            Some(_) if self.is_default(qd) => self.default.as_ref().unwrap().1.clone(),
             _ => panic!("invalid queue descriptor"),
         };
 
@@ -336,8 +381,11 @@ impl UdpPeer {
         #[cfg(feature = "profiler")]
         timer!("udp::receive");
 
+        // Keep the raw datagram around, in case it should be handed to the default socket.
+        let raw: Option<Buffer> = self.default.as_ref().map(|_| buf.clone());
+
         // Parse datagram.
-        let (hdr, data): (UdpHeader, Buffer) = UdpHeader::parse(ipv4_hdr, buf, self.checksum_offload)?;
+        let (hdr, data): (UdpHeader, Buffer) = UdpHeader::parse(ipv4_hdr, buf, self.rx_checksum_offload)?;
         debug!("UDP received {:?}", hdr);
 
         let local: SocketAddrV4 = SocketAddrV4::new(ipv4_hdr.get_dest_addr(), hdr.dest_port());
@@ -351,8 +399,16 @@ impl UdpPeer {
                 let local: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, hdr.dest_port());
                 match self.bound.get_mut(&local) {
                     Some(q) => q,
-                    // TODO: Send ICMPv4 error in this condition.
-                    None => Err(Fail::new(libc::ENOTCONN, "port not bound"))?,
+                    None => match (self.default.as_ref(), raw) {
+                        // Hand the raw datagram to the default socket.
+                        (Some((_, q)), Some(data)) => {
+                            let local: SocketAddrV4 = SocketAddrV4::new(ipv4_hdr.get_dest_addr(), hdr.dest_port());
+                            q.push(SharedQueueSlot { local, remote, data })?;
+                            return Ok(());
+                        },
+                        // TODO: Send ICMPv4 error in this condition.
+                        _ => Err(Fail::new(libc::ENOTCONN, "port not bound"))?,
+                    },
                 }
             },
         };
@@ -366,6 +422,14 @@ impl UdpPeer {
         Ok(())
     }
 
+    /// Checks if `qd` refers to the default socket.
+    fn is_default(&self, qd: QDesc) -> bool {
+        match self.default {
+            Some((default_qd, _)) => default_qd == qd,
+            None => false,
+        }
+    }
+
     /// Sends a UDP datagram.
     fn do_send(
         rt: Rc<dyn NetworkRuntime>,
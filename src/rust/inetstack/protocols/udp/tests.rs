@@ -21,6 +21,7 @@ use ::futures::task::{
 use ::libc::{
     EADDRINUSE,
     EBADF,
+    EINVAL,
     ENOTCONN,
 };
 use ::std::{
@@ -113,6 +114,52 @@ fn udp_push_pop() {
     bob.udp_close(bob_fd).unwrap();
 }
 
+/// Tests that popped data starts at the first payload byte, regardless of IPv4 options and link-layer padding.
+#[test]
+fn udp_pop_payload_offset() {
+    let mut ctx: Context = Context::from_waker(noop_waker_ref());
+    let now: Instant = Instant::now();
+
+    // Setup Alice.
+    let mut alice: Engine = test_helpers::new_alice2(now);
+    let alice_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::ALICE_IPV4, 80);
+    let alice_fd: QDesc = alice.udp_socket().unwrap();
+    alice.udp_bind(alice_fd, alice_addr).unwrap();
+
+    // Setup Bob.
+    let mut bob: Engine = test_helpers::new_bob2(now);
+    let bob_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, 80);
+    let bob_fd: QDesc = bob.udp_socket().unwrap();
+    bob.udp_bind(bob_fd, bob_addr).unwrap();
+
+    // No byte of the payload matches its offset, so any misplaced header boundary shows up.
+    let payload: Vec<u8> = (0..5).map(|i| 0xa1 + i).collect::<Vec<u8>>();
+    for (options_len, padding_len) in [(0, 0), (0, 27), (4, 0), (40, 13)] {
+        let buf: Buffer = Buffer::Heap(DataBuffer::from(&payload[..]));
+        alice.udp_pushto(alice_fd, buf, bob_addr).unwrap();
+        alice.rt.poll_scheduler();
+        let frame: Buffer = test_helpers::add_ipv4_options_and_padding(&alice.rt.pop_frame(), options_len, padding_len);
+
+        bob.receive(frame).unwrap();
+        let mut pop_future = bob.udp_pop(bob_fd);
+        match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+            Poll::Ready(Ok((remote_addr, received_buf))) => {
+                assert_eq!(remote_addr, alice_addr);
+                assert_eq!(received_buf[0], payload[0]);
+                assert_eq!(received_buf[..], payload[..]);
+            },
+            _ => panic!(
+                "pop should complete (options_len={}, padding_len={})",
+                options_len, padding_len
+            ),
+        }
+    }
+
+    // Close peers.
+    alice.udp_close(alice_fd).unwrap();
+    bob.udp_close(bob_fd).unwrap();
+}
+
 //==============================================================================
 // Push & Pop
 //==============================================================================
@@ -518,6 +565,96 @@ fn udp_pop_not_bound() {
     // Bob does not have a socket.
 }
 
+//==============================================================================
+// Default Socket
+//==============================================================================
+
+#[test]
+fn udp_pop_default_socket() {
+    let mut ctx: Context = Context::from_waker(noop_waker_ref());
+    let now: Instant = Instant::now();
+
+    // Setup Alice.
+    let mut alice: Engine = test_helpers::new_alice2(now);
+    let alice_port: u16 = 80;
+    let alice_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::ALICE_IPV4, alice_port);
+    let alice_fd: QDesc = alice.udp_socket().unwrap();
+    alice.udp_bind(alice_fd, alice_addr).unwrap();
+
+    // Setup Bob. Bob only has a default socket.
+    let mut bob: Engine = test_helpers::new_bob2(now);
+    let bob_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, 8080);
+    let bob_fd: QDesc = bob.udp_socket().unwrap();
+    bob.udp_bind_default(bob_fd).unwrap();
+
+    // There can be only one default socket.
+    let other_fd: QDesc = bob.udp_socket().unwrap();
+    match bob.udp_bind_default(other_fd) {
+        Err(e) if e.errno == EADDRINUSE => Ok(()),
+        _ => Err(()),
+    }
+    .unwrap();
+
+    // Send data to a port that Bob did not bind.
+    let buf: Buffer = Buffer::Heap(DataBuffer::from(&vec![0x5a; 32][..]));
+    alice.udp_pushto(alice_fd, buf.clone(), bob_addr).unwrap();
+    alice.rt.poll_scheduler();
+
+    // The datagram is handed to the default socket, along with its header.
+    bob.receive(alice.rt.pop_frame()).unwrap();
+    let mut pop_future = bob.udp_pop(bob_fd);
+    let (remote_addr, received_buf) = match Future::poll(Pin::new(&mut pop_future), &mut ctx) {
+        Poll::Ready(Ok((remote_addr, received_buf))) => Ok((remote_addr, received_buf)),
+        _ => Err(()),
+    }
+    .unwrap();
+    assert_eq!(remote_addr, alice_addr);
+    assert_eq!(received_buf.len(), 8 + buf.len());
+    assert_eq!(u16::from_be_bytes([received_buf[0], received_buf[1]]), alice_port);
+    assert_eq!(u16::from_be_bytes([received_buf[2], received_buf[3]]), bob_addr.port());
+    assert_eq!(received_buf[8..], buf[..]);
+
+    // Once the default socket is closed, datagrams to unbound ports are dropped again.
+    bob.udp_close(bob_fd).unwrap();
+    alice.udp_pushto(alice_fd, buf, bob_addr).unwrap();
+    alice.rt.poll_scheduler();
+    match bob.receive(alice.rt.pop_frame()) {
+        Err(e) if e.errno == ENOTCONN => Ok(()),
+        _ => Err(()),
+    }
+    .unwrap();
+
+    // Close peers.
+    alice.udp_close(alice_fd).unwrap();
+}
+
+#[test]
+fn udp_close_default_socket() {
+    let now: Instant = Instant::now();
+    let mut bob: Engine = test_helpers::new_bob2(now);
+    let bob_addr: SocketAddrV4 = SocketAddrV4::new(test_helpers::BOB_IPV4, 8080);
+
+    // The default socket cannot be bound to an address.
+    let bob_fd: QDesc = bob.udp_socket().unwrap();
+    bob.udp_bind_default(bob_fd).unwrap();
+    match bob.udp_bind(bob_fd, bob_addr) {
+        Err(e) if e.errno == EINVAL => Ok(()),
+        _ => Err(()),
+    }
+    .unwrap();
+
+    // Closing the default socket releases it, so another one can be created.
+    bob.udp_close(bob_fd).unwrap();
+    let other_fd: QDesc = bob.udp_socket().unwrap();
+    bob.udp_bind_default(other_fd).unwrap();
+    bob.udp_close(other_fd).unwrap();
+
+    // The address was never taken.
+    let bob_fd: QDesc = bob.udp_socket().unwrap();
+    bob.udp_bind(bob_fd, bob_addr).unwrap();
+    bob.udp_close(bob_fd).unwrap();
+}
+
 //==============================================================================
 // Bad Push
 //==============================================================================
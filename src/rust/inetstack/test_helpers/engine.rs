@@ -127,6 +127,10 @@ impl Engine {
         self.ipv4.udp.do_bind(socket_fd, endpoint)
     }
 
+    pub fn udp_bind_default(&mut self, socket_fd: QDesc) -> Result<(), Fail> {
+        self.ipv4.udp.do_bind_default(socket_fd)
+    }
+
     pub fn udp_close(&mut self, socket_fd: QDesc) -> Result<(), Fail> {
         self.ipv4.udp.do_close(socket_fd)
     }
@@ -154,10 +158,18 @@ impl Engine {
         self.ipv4.tcp.push(socket_fd, buf)
     }
 
+    pub fn tcp_push_batch(&mut self, socket_fd: QDesc, bufs: Vec<Buffer>) -> PushFuture {
+        self.ipv4.tcp.push_batch(socket_fd, bufs)
+    }
+
     pub fn tcp_pop(&mut self, socket_fd: QDesc) -> PopFuture {
         self.ipv4.tcp.pop(socket_fd)
     }
 
+    pub fn tcp_peek(&mut self, socket_fd: QDesc) -> PopFuture {
+        self.ipv4.tcp.peek(socket_fd)
+    }
+
     pub fn tcp_close(&mut self, socket_fd: QDesc) -> Result<(), Fail> {
         self.ipv4.tcp.do_close(socket_fd)
     }
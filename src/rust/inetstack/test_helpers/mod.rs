@@ -8,7 +8,16 @@ pub use self::runtime::TestRuntime;
 pub use engine::Engine;
 
 use crate::{
+    inetstack::protocols::{
+        ethernet2::ETHERNET2_HEADER_SIZE,
+        ip::IpProtocol,
+        ipv4::IPV4_HEADER_DEFAULT_SIZE,
+    },
     runtime::{
+        memory::{
+            Buffer,
+            DataBuffer,
+        },
         network::{
             config::{
                 ArpConfig,
@@ -53,6 +62,8 @@ pub fn new_alice(now: Instant) -> Engine {
         Some(2),
         Some(HashMap::new()),
         Some(false),
+        None,
+        None,
     );
     let udp_config = UdpConfig::default();
     let tcp_config = TcpConfig::default();
@@ -69,6 +80,8 @@ pub fn new_bob(now: Instant) -> Engine {
         Some(2),
         Some(HashMap::new()),
         Some(false),
+        None,
+        None,
     );
     let udp_config = UdpConfig::default();
     let tcp_config = TcpConfig::default();
@@ -79,6 +92,31 @@ pub fn new_bob(now: Instant) -> Engine {
 }
 
 pub fn new_alice2(now: Instant) -> Engine {
+    new_alice2_with_tcp_config(now, TcpConfig::default())
+}
+
+pub fn new_alice2_with_tcp_config(now: Instant, tcp_config: TcpConfig) -> Engine {
+    let mut arp: HashMap<Ipv4Addr, MacAddress> = HashMap::<Ipv4Addr, MacAddress>::new();
+    arp.insert(ALICE_IPV4, ALICE_MAC);
+    arp.insert(BOB_IPV4, BOB_MAC);
+    let arp_options = ArpConfig::new(
+        Some(Duration::from_secs(600)),
+        Some(Duration::from_secs(1)),
+        Some(2),
+        Some(arp),
+        Some(false),
+        None,
+        None,
+    );
+    let udp_config = UdpConfig::default();
+    let rt = TestRuntime::new(now, arp_options, udp_config, tcp_config, ALICE_MAC, ALICE_IPV4);
+    let scheduler: Scheduler = rt.scheduler.clone();
+    let clock: TimerRc = rt.clock.clone();
+    Engine::new(rt, scheduler, clock).unwrap()
+}
+
+/// Creates an engine for Alice that only resolves link addresses from a static ARP table, which knows about Bob.
+pub fn new_alice2_static(now: Instant) -> Engine {
     let mut arp: HashMap<Ipv4Addr, MacAddress> = HashMap::<Ipv4Addr, MacAddress>::new();
     arp.insert(ALICE_IPV4, ALICE_MAC);
     arp.insert(BOB_IPV4, BOB_MAC);
@@ -88,6 +126,8 @@ pub fn new_alice2(now: Instant) -> Engine {
         Some(2),
         Some(arp),
         Some(false),
+        Some(true),
+        None,
     );
     let udp_config = UdpConfig::default();
     let tcp_config = TcpConfig::default();
@@ -98,6 +138,10 @@ pub fn new_alice2(now: Instant) -> Engine {
 }
 
 pub fn new_bob2(now: Instant) -> Engine {
+    new_bob2_with_tcp_config(now, TcpConfig::default())
+}
+
+pub fn new_bob2_with_tcp_config(now: Instant, tcp_config: TcpConfig) -> Engine {
     let mut arp: HashMap<Ipv4Addr, MacAddress> = HashMap::<Ipv4Addr, MacAddress>::new();
     arp.insert(BOB_IPV4, BOB_MAC);
     arp.insert(ALICE_IPV4, ALICE_MAC);
@@ -107,9 +151,10 @@ pub fn new_bob2(now: Instant) -> Engine {
         Some(2),
         Some(arp),
         Some(false),
+        None,
+        None,
     );
     let udp_config = UdpConfig::default();
-    let tcp_config = TcpConfig::default();
     let rt = TestRuntime::new(now, arp_options, udp_config, tcp_config, BOB_MAC, BOB_IPV4);
     let scheduler: Scheduler = rt.scheduler.clone();
     let clock: TimerRc = rt.clock.clone();
@@ -123,6 +168,8 @@ pub fn new_carrie(now: Instant) -> Engine {
         Some(2),
         Some(HashMap::new()),
         Some(false),
+        None,
+        None,
     );
     let udp_config = UdpConfig::default();
     let tcp_config = TcpConfig::default();
@@ -132,3 +179,56 @@ pub fn new_carrie(now: Instant) -> Engine {
     let clock: TimerRc = rt.clock.clone();
     Engine::new(rt, scheduler, clock).unwrap()
 }
+
+/// Reference implementation of the Internet checksum (RFC 1071) of a transport segment, including the IPv4
+/// pseudo-header. The checksum field in `segment` is expected to be zeroed.
+pub fn reference_checksum(src_addr: Ipv4Addr, dst_addr: Ipv4Addr, protocol: IpProtocol, segment: &[u8]) -> u16 {
+    let mut bytes: Vec<u8> = Vec::new();
+    bytes.extend_from_slice(&src_addr.octets());
+    bytes.extend_from_slice(&dst_addr.octets());
+    bytes.push(0);
+    bytes.push(protocol as u8);
+    bytes.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(segment);
+    if bytes.len() % 2 != 0 {
+        bytes.push(0);
+    }
+
+    let mut sum: u32 = 0;
+    for word in bytes.chunks_exact(2) {
+        sum += u16::from_be_bytes([word[0], word[1]]) as u32;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Rewrites a frame that carries an IPv4 datagram without options, so that its IPv4 header carries `options_len` bytes
+/// of NOP options, and the frame ends with `padding_len` bytes of link-layer padding. The header checksum is computed
+/// independently of the network stack. The transport segment is left untouched.
+pub fn add_ipv4_options_and_padding(frame: &Buffer, options_len: usize, padding_len: usize) -> Buffer {
+    const IPV4_OPTION_NOP: u8 = 1;
+    let ipv4_hdr_start: usize = ETHERNET2_HEADER_SIZE;
+    let ipv4_hdr_end: usize = ipv4_hdr_start + IPV4_HEADER_DEFAULT_SIZE;
+    assert_eq!(options_len % 4, 0, "IPv4 options must be a multiple of 4 bytes long");
+    assert_eq!(frame[ipv4_hdr_start] & 0xf, 5, "IPv4 header already carries options");
+
+    let mut bytes: Vec<u8> = frame[..ipv4_hdr_end].to_vec();
+    bytes.resize(ipv4_hdr_end + options_len, IPV4_OPTION_NOP);
+    bytes.extend_from_slice(&frame[ipv4_hdr_end..]);
+    bytes.resize(bytes.len() + padding_len, 0);
+
+    // Fix up header length, total length, and header checksum.
+    let hdr: &mut [u8] = &mut bytes[ipv4_hdr_start..(ipv4_hdr_end + options_len)];
+    hdr[0] = (hdr[0] & 0xf0) | ((hdr.len() / 4) as u8);
+    let total_length: u16 = u16::from_be_bytes([hdr[2], hdr[3]]) + options_len as u16;
+    hdr[2..4].copy_from_slice(&total_length.to_be_bytes());
+    hdr[10..12].copy_from_slice(&[0, 0]);
+    let mut sum: u32 = 0;
+    for word in hdr.chunks_exact(2) {
+        sum += u16::from_be_bytes([word[0], word[1]]) as u32;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    hdr[10..12].copy_from_slice(&(!(sum as u16)).to_be_bytes());
+
+    Buffer::Heap(DataBuffer::from_slice(&bytes))
+}
@@ -54,12 +54,16 @@ pub use crate::demikernel::libos::network::OperationResult;
 
 pub use self::demikernel::libos::{
     name::LibOSName,
+    transport::NetworkTransport,
     LibOS,
 };
 pub use crate::runtime::{
-    network::types::{
-        MacAddress,
-        Port16,
+    network::{
+        preamble::RequestPreamble,
+        types::{
+            MacAddress,
+            Port16,
+        },
     },
     types::{
         demi_sgarray_t,
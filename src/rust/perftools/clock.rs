@@ -0,0 +1,214 @@
+// Copyright(c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use ::std::time::{
+    Duration,
+    Instant,
+    SystemTime,
+};
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// How long to measure the time stamp counter against the system clock when calibrating.
+const CALIBRATION_PERIOD: Duration = Duration::from_millis(5);
+
+thread_local!(
+    /// Global thread-local instance of the clock.
+    static CLOCK: Clock = Clock::new()
+);
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Clock
+///
+/// Reads time as nanoseconds elapsed since the clock was created. On x86-64 CPUs with an invariant time stamp counter,
+/// readings come from `rdtsc` and are converted with a rate calibrated against the system clock. Elsewhere, readings
+/// come from [Instant].
+pub struct Clock {
+    /// Time stamp counter at creation, if the time stamp counter is used.
+    base_tsc: Option<u64>,
+    /// Nanoseconds per time stamp counter cycle.
+    ns_per_cycle: f64,
+    /// Monotonic time at creation.
+    base_instant: Instant,
+    /// Wall-clock time at creation.
+    base_system: SystemTime,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Clocks
+impl Clock {
+    /// Creates a clock. If the time stamp counter is usable, this blocks for a few milliseconds to calibrate it.
+    pub fn new() -> Self {
+        let base_instant: Instant = Instant::now();
+        let base_system: SystemTime = SystemTime::now();
+        if has_invariant_tsc() {
+            let base_tsc: u64 = read_tsc();
+            while base_instant.elapsed() < CALIBRATION_PERIOD {}
+            let elapsed_ns: u128 = base_instant.elapsed().as_nanos();
+            let elapsed_cycles: u64 = read_tsc().saturating_sub(base_tsc);
+            Self {
+                base_tsc: Some(base_tsc),
+                ns_per_cycle: elapsed_ns as f64 / elapsed_cycles.max(1) as f64,
+                base_instant,
+                base_system,
+            }
+        } else {
+            Self {
+                base_tsc: None,
+                ns_per_cycle: 1.0,
+                base_instant,
+                base_system,
+            }
+        }
+    }
+
+    /// Returns the number of nanoseconds elapsed since the target clock was created.
+    pub fn now_ns(&self) -> u64 {
+        match self.base_tsc {
+            Some(base_tsc) => self.cycles_to_ns(read_tsc().saturating_sub(base_tsc)),
+            None => self.base_instant.elapsed().as_nanos() as u64,
+        }
+    }
+
+    /// Converts a number of time stamp counter cycles into nanoseconds.
+    pub fn cycles_to_ns(&self, cycles: u64) -> u64 {
+        (cycles as f64 * self.ns_per_cycle) as u64
+    }
+
+    /// Checks if the target clock reads the time stamp counter.
+    pub fn uses_tsc(&self) -> bool {
+        self.base_tsc.is_some()
+    }
+
+    /// Translates a reading of the target clock into monotonic time.
+    pub fn to_instant(&self, ns: u64) -> Instant {
+        self.base_instant + Duration::from_nanos(ns)
+    }
+
+    /// Translates a reading of the target clock into wall-clock time.
+    pub fn to_system_time(&self, ns: u64) -> SystemTime {
+        self.base_system + Duration::from_nanos(ns)
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Default Trait Implementation for Clocks
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Returns the number of nanoseconds elapsed on the thread-local clock. Timestamps that are exchanged between threads
+/// should be translated with [to_system_time] first.
+pub fn now_ns() -> u64 {
+    CLOCK.with(|clock| clock.now_ns())
+}
+
+/// Converts a number of time stamp counter cycles into nanoseconds, using the calibration of the thread-local clock.
+pub fn cycles_to_ns(cycles: u64) -> u64 {
+    CLOCK.with(|clock| clock.cycles_to_ns(cycles))
+}
+
+/// Translates a reading of the thread-local clock into wall-clock time.
+pub fn to_system_time(ns: u64) -> SystemTime {
+    CLOCK.with(|clock| clock.to_system_time(ns))
+}
+
+/// Checks if the time stamp counter ticks at a constant rate (CPUID.80000007H:EDX[8]). This is slow, so it is only
+/// done when a clock is created.
+#[cfg(target_arch = "x86_64")]
+fn has_invariant_tsc() -> bool {
+    use ::std::arch::x86_64::__cpuid;
+    unsafe { __cpuid(0x8000_0000).eax >= 0x8000_0007 && (__cpuid(0x8000_0007).edx & (1 << 8)) != 0 }
+}
+
+/// Checks if the time stamp counter ticks at a constant rate.
+#[cfg(not(target_arch = "x86_64"))]
+fn has_invariant_tsc() -> bool {
+    false
+}
+
+/// Reads the time stamp counter.
+#[cfg(target_arch = "x86_64")]
+fn read_tsc() -> u64 {
+    unsafe { ::std::arch::x86_64::_rdtsc() }
+}
+
+/// Reads the time stamp counter. This is never called, since there is no invariant time stamp counter to read.
+#[cfg(not(target_arch = "x86_64"))]
+fn read_tsc() -> u64 {
+    unreachable!("no invariant time stamp counter")
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Clock;
+    use ::std::{
+        thread,
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    #[test]
+    fn clock_tracks_system_clock() {
+        let clock: Clock = Clock::new();
+        let start_ns: u64 = clock.now_ns();
+        let start: Instant = Instant::now();
+        thread::sleep(Duration::from_millis(20));
+        let elapsed_ns: u64 = clock.now_ns() - start_ns;
+        let expected_ns: u64 = start.elapsed().as_nanos() as u64;
+
+        // Allow for a 10% calibration error.
+        let error_ns: u64 = if elapsed_ns > expected_ns {
+            elapsed_ns - expected_ns
+        } else {
+            expected_ns - elapsed_ns
+        };
+        assert!(
+            error_ns < expected_ns / 10,
+            "elapsed={}ns expected={}ns",
+            elapsed_ns,
+            expected_ns
+        );
+    }
+
+    #[test]
+    fn clock_translation() {
+        let clock: Clock = Clock::new();
+        let ns: u64 = 1_500_000;
+        assert_eq!(clock.to_instant(ns) - clock.to_instant(0), Duration::from_nanos(ns));
+        assert_eq!(
+            clock
+                .to_system_time(ns)
+                .duration_since(clock.to_system_time(0))
+                .unwrap(),
+            Duration::from_nanos(ns)
+        );
+    }
+}
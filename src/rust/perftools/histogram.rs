@@ -0,0 +1,197 @@
+// Copyright(c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Number of buckets in a histogram. Bucket 0 holds zeros, and bucket `i` holds values in `[2^(i-1), 2^i)`.
+const NUM_BUCKETS: usize = 65;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Histogram
+///
+/// Records the distribution of a quantity, such as a latency, in buckets whose bounds are powers of two. Recording is
+/// cheap and memory use is constant, at the cost of reporting percentiles within a factor of two.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Histogram {
+    /// Number of values in each bucket.
+    buckets: [u64; NUM_BUCKETS],
+    /// Number of recorded values.
+    count: u64,
+    /// Sum of recorded values.
+    sum: u128,
+    /// Smallest recorded value.
+    min: u64,
+    /// Largest recorded value.
+    max: u64,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Histograms
+impl Histogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Self {
+        Self {
+            buckets: [0; NUM_BUCKETS],
+            count: 0,
+            sum: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    /// Records a value in the target [Histogram].
+    pub fn record(&mut self, value: u64) {
+        self.buckets[Self::bucket(value)] += 1;
+        self.count += 1;
+        self.sum += value as u128;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Returns the number of values recorded in the target [Histogram].
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the smallest value recorded in the target [Histogram], if any.
+    pub fn min(&self) -> Option<u64> {
+        if self.count > 0 {
+            Some(self.min)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the largest value recorded in the target [Histogram], if any.
+    pub fn max(&self) -> Option<u64> {
+        if self.count > 0 {
+            Some(self.max)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the mean of the values recorded in the target [Histogram], if any.
+    pub fn mean(&self) -> Option<u64> {
+        if self.count > 0 {
+            Some((self.sum / self.count as u128) as u64)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an upper bound for the `p`-th percentile (`0.0 <= p <= 100.0`) of the values recorded in the target
+    /// [Histogram], if any. The bound is at most twice the actual percentile, and never exceeds the largest value.
+    pub fn percentile(&self, p: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank: u64 = ((p.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen: u64 = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(Self::upper_bound(i).min(self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Returns the non-empty buckets of the target [Histogram], as (lower bound, upper bound, count) tuples, in
+    /// increasing order. Both bounds are inclusive.
+    pub fn buckets(&self) -> Vec<(u64, u64, u64)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, count)| (Self::lower_bound(i), Self::upper_bound(i), *count))
+            .collect()
+    }
+
+    /// Returns the index of the bucket that holds a value.
+    fn bucket(value: u64) -> usize {
+        (u64::BITS - value.leading_zeros()) as usize
+    }
+
+    /// Returns the smallest value that falls into a bucket.
+    fn lower_bound(bucket: usize) -> u64 {
+        match bucket {
+            0 => 0,
+            i => 1 << (i - 1),
+        }
+    }
+
+    /// Returns the largest value that falls into a bucket.
+    fn upper_bound(bucket: usize) -> u64 {
+        match bucket {
+            0 => 0,
+            64 => u64::MAX,
+            i => (1 << i) - 1,
+        }
+    }
+}
+
+//==============================================================================
+// Trait Implementations
+//==============================================================================
+
+/// Default Trait Implementation for Histograms
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Histogram;
+
+    #[test]
+    fn empty_histogram() {
+        let histogram: Histogram = Histogram::new();
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.min(), None);
+        assert_eq!(histogram.mean(), None);
+        assert_eq!(histogram.percentile(50.0), None);
+        assert!(histogram.buckets().is_empty());
+    }
+
+    #[test]
+    fn histogram_percentiles() {
+        let mut histogram: Histogram = Histogram::new();
+        for value in 1..=1000 {
+            histogram.record(value);
+        }
+        histogram.record(0);
+        histogram.record(u64::MAX);
+
+        assert_eq!(histogram.count(), 1002);
+        assert_eq!(histogram.min(), Some(0));
+        assert_eq!(histogram.max(), Some(u64::MAX));
+        assert_eq!(histogram.percentile(0.0), Some(0));
+        // The median (501) falls into [256, 511].
+        assert_eq!(histogram.percentile(50.0), Some(511));
+        assert_eq!(histogram.percentile(99.0), Some(1023));
+        assert_eq!(histogram.percentile(100.0), Some(u64::MAX));
+
+        let buckets: Vec<(u64, u64, u64)> = histogram.buckets();
+        assert_eq!(buckets[0], (0, 0, 1));
+        assert_eq!(buckets[1], (1, 1, 1));
+        assert_eq!(buckets[2], (2, 3, 2));
+        assert_eq!(buckets[10], (512, 1023, 489));
+        assert_eq!(*buckets.last().unwrap(), (1 << 63, u64::MAX, 1));
+        assert_eq!(buckets.iter().map(|(_, _, count)| count).sum::<u64>(), 1002);
+    }
+}
@@ -1,4 +1,6 @@
 // Copyright(c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+pub mod clock;
+pub mod histogram;
 pub mod profiler;
@@ -4,8 +4,13 @@
 #[cfg(test)]
 mod tests;
 
+use crate::perftools::{
+    clock,
+    histogram::Histogram,
+};
 use ::std::{
     cell::RefCell,
+    collections::HashMap,
     io,
     rc::Rc,
 };
@@ -18,6 +23,11 @@ thread_local!(
     pub static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::new())
 );
 
+thread_local!(
+    /// Global thread-local record of operation latencies.
+    static LATENCIES: RefCell<Latencies> = RefCell::new(Latencies::default())
+);
+
 /// Use this macro to add the current scope to profiling. In effect, the time
 /// taken from entering to leaving the scope will be measured.
 ///
@@ -66,6 +76,46 @@ pub fn write<W: io::Write>(out: &mut W, max_depth: Option<usize>) -> io::Result<
 /// Reset profiling information.
 pub fn reset() {
     PROFILER.with(|p| p.borrow_mut().reset());
+    LATENCIES.with(|l| l.borrow_mut().histograms.clear());
+}
+
+/// Records the submission of the operation identified by `qt`, whose latency is accounted under `opcode`.
+pub fn operation_submitted(qt: u64, opcode: &'static str) {
+    let now: u64 = clock::now_ns();
+    LATENCIES.with(|l| l.borrow_mut().pending.insert(qt, (now, opcode)));
+}
+
+/// Records the completion of the operation identified by `qt`. The time since its submission is added to the latency
+/// histogram of the opcode it was submitted with, whether it succeeded or not. Operations whose submission was not
+/// recorded are ignored.
+pub fn operation_completed(qt: u64) {
+    let now: u64 = clock::now_ns();
+    LATENCIES.with(|l| {
+        let mut latencies = l.borrow_mut();
+        if let Some((submitted, opcode)) = latencies.pending.remove(&qt) {
+            latencies
+                .histograms
+                .entry(opcode)
+                .or_default()
+                .record(now.saturating_sub(submitted));
+        }
+    });
+}
+
+/// Forgets about the operation identified by `qt`, which will never complete.
+pub fn operation_cancelled(qt: u64) {
+    LATENCIES.with(|l| l.borrow_mut().pending.remove(&qt));
+}
+
+/// Returns the distribution of the time (in nanoseconds) between the submission and completion of operations with
+/// `opcode`, if any completed.
+pub fn latency_histogram(opcode: &str) -> Option<Histogram> {
+    LATENCIES.with(|l| l.borrow().histograms.get(opcode).cloned())
+}
+
+/// Print operation latencies, per opcode.
+pub fn write_latencies<W: io::Write>(out: &mut W) -> io::Result<()> {
+    LATENCIES.with(|l| l.borrow().write(out))
 }
 
 //==============================================================================
@@ -143,12 +193,14 @@ impl Scope {
         for _ in 0..depth {
             markers.push('+');
         }
+        let cycles_per_call: f64 = duration_sum_secs / (self.num_calls as f64);
         writeln!(
             out,
-            "{: <60} {: >6.2}%, {: >18.4} cycles",
+            "{: <60} {: >6.2}%, {: >18.4} cycles, {: >12} ns",
             format!(" {}  {}", markers, self.name),
             percent,
-            duration_sum_secs / (self.num_calls as f64),
+            cycles_per_call,
+            clock::cycles_to_ns(cycles_per_call as u64),
         )?;
 
         // Write children
@@ -165,6 +217,41 @@ impl Scope {
 //
 //==============================================================================
 
+/// Latencies of operations, from submission to completion.
+#[derive(Default)]
+struct Latencies {
+    /// Submission time and opcode of pending operations, by queue token.
+    pending: HashMap<u64, (u64, &'static str)>,
+
+    /// Latency histograms, by opcode.
+    histograms: HashMap<&'static str, Histogram>,
+}
+
+impl Latencies {
+    fn write<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut opcodes: Vec<&&'static str> = self.histograms.keys().collect();
+        opcodes.sort();
+        for opcode in opcodes {
+            let histogram: &Histogram = &self.histograms[*opcode];
+            writeln!(
+                out,
+                "{: <12} {: >10} ops, mean {: >12} ns, p50 <= {: >12} ns, p99 <= {: >12} ns, max {: >12} ns",
+                opcode,
+                histogram.count(),
+                histogram.mean().unwrap_or(0),
+                histogram.percentile(50.0).unwrap_or(0),
+                histogram.percentile(99.0).unwrap_or(0),
+                histogram.max().unwrap_or(0),
+            )?;
+        }
+        out.flush()
+    }
+}
+
+//==============================================================================
+//
+//==============================================================================
+
 /// A guard that is created when entering a scope and dropped when leaving it.
 pub struct Guard {
     enter_time: u64,
@@ -97,3 +97,27 @@ fn test_reset_during_frame() {
         assert!(p.current.is_none());
     });
 }
+
+#[test]
+fn test_operation_latencies() {
+    profiler::reset();
+
+    profiler::operation_submitted(1, "push");
+    profiler::operation_submitted(2, "push");
+    profiler::operation_submitted(3, "pop");
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    profiler::operation_completed(1);
+    profiler::operation_completed(2);
+    profiler::operation_cancelled(3);
+    profiler::operation_completed(3);
+    profiler::operation_completed(4);
+
+    let histogram = profiler::latency_histogram("push").unwrap();
+    assert_eq!(histogram.count(), 2);
+    assert!(histogram.min().unwrap() >= 500_000);
+    assert!(profiler::latency_histogram("pop").is_none());
+
+    let mut out: Vec<u8> = Vec::new();
+    profiler::write_latencies(&mut out).unwrap();
+    assert!(String::from_utf8(out).unwrap().starts_with("push"));
+}
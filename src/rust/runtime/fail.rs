@@ -7,7 +7,25 @@
 
 use ::libc::{
     c_int,
+    EADDRINUSE,
+    EAGAIN,
+    EALREADY,
+    EBADF,
+    EBADMSG,
+    EBUSY,
+    ECANCELED,
+    ECONNREFUSED,
+    ECONNRESET,
+    EEXIST,
+    EINPROGRESS,
+    EINVAL,
     EIO,
+    EISCONN,
+    ENODEV,
+    ENOMEM,
+    ENOTCONN,
+    ENOTSUP,
+    ETIMEDOUT,
 };
 use ::std::{
     error,
@@ -15,6 +33,57 @@ use ::std::{
     io,
 };
 
+//==============================================================================
+// Enumerations
+//==============================================================================
+
+/// Error Kind
+///
+/// Category of a [Fail]. Rust callers should match on this instead of raw error codes, which are kept for the C ABI.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// Invalid argument (`EINVAL`).
+    InvalidArgument,
+    /// Bad queue descriptor (`EBADF`).
+    BadDescriptor,
+    /// Resource temporarily unavailable, retry later (`EAGAIN`).
+    WouldBlock,
+    /// Malformed message (`EBADMSG`).
+    BadMessage,
+    /// Operation not supported (`ENOTSUP`).
+    NotSupported,
+    /// Operation timed out (`ETIMEDOUT`).
+    TimedOut,
+    /// Socket is not connected (`ENOTCONN`).
+    NotConnected,
+    /// Socket is already connected (`EISCONN`).
+    AlreadyConnected,
+    /// Address already in use (`EADDRINUSE`).
+    AddressInUse,
+    /// Operation was cancelled (`ECANCELED`).
+    Cancelled,
+    /// Operation already in progress (`EALREADY`).
+    AlreadyInProgress,
+    /// Operation now in progress (`EINPROGRESS`).
+    InProgress,
+    /// Connection refused by the remote peer (`ECONNREFUSED`).
+    ConnectionRefused,
+    /// Connection reset by the remote peer (`ECONNRESET`).
+    ConnectionReset,
+    /// Out of memory, for instance when a memory pool is exhausted (`ENOMEM`).
+    OutOfMemory,
+    /// No such device (`ENODEV`).
+    NoDevice,
+    /// Resource busy (`EBUSY`).
+    Busy,
+    /// Entry already exists (`EEXIST`).
+    AlreadyExists,
+    /// Input/output error (`EIO`).
+    Io,
+    /// Any other error code.
+    Other(c_int),
+}
+
 //==============================================================================
 // Structures
 //==============================================================================
@@ -41,6 +110,71 @@ impl Fail {
             cause: cause.to_string(),
         }
     }
+
+    /// Creates a new Failure of a given kind.
+    pub fn with_kind(kind: ErrorKind, cause: &str) -> Self {
+        Self::new(kind.errno(), cause)
+    }
+
+    /// Returns the kind of the target Failure.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::from_errno(self.errno)
+    }
+}
+
+/// Associate Functions for Error Kinds
+impl ErrorKind {
+    /// Returns the kind of error that is associated with an error code.
+    pub fn from_errno(errno: c_int) -> Self {
+        match errno {
+            EINVAL => ErrorKind::InvalidArgument,
+            EBADF => ErrorKind::BadDescriptor,
+            EAGAIN => ErrorKind::WouldBlock,
+            EBADMSG => ErrorKind::BadMessage,
+            ENOTSUP => ErrorKind::NotSupported,
+            ETIMEDOUT => ErrorKind::TimedOut,
+            ENOTCONN => ErrorKind::NotConnected,
+            EISCONN => ErrorKind::AlreadyConnected,
+            EADDRINUSE => ErrorKind::AddressInUse,
+            ECANCELED => ErrorKind::Cancelled,
+            EALREADY => ErrorKind::AlreadyInProgress,
+            EINPROGRESS => ErrorKind::InProgress,
+            ECONNREFUSED => ErrorKind::ConnectionRefused,
+            ECONNRESET => ErrorKind::ConnectionReset,
+            ENOMEM => ErrorKind::OutOfMemory,
+            ENODEV => ErrorKind::NoDevice,
+            EBUSY => ErrorKind::Busy,
+            EEXIST => ErrorKind::AlreadyExists,
+            EIO => ErrorKind::Io,
+            errno => ErrorKind::Other(errno),
+        }
+    }
+
+    /// Returns the error code that is reported through the C ABI for the target kind of error.
+    pub fn errno(self) -> c_int {
+        match self {
+            ErrorKind::InvalidArgument => EINVAL,
+            ErrorKind::BadDescriptor => EBADF,
+            ErrorKind::WouldBlock => EAGAIN,
+            ErrorKind::BadMessage => EBADMSG,
+            ErrorKind::NotSupported => ENOTSUP,
+            ErrorKind::TimedOut => ETIMEDOUT,
+            ErrorKind::NotConnected => ENOTCONN,
+            ErrorKind::AlreadyConnected => EISCONN,
+            ErrorKind::AddressInUse => EADDRINUSE,
+            ErrorKind::Cancelled => ECANCELED,
+            ErrorKind::AlreadyInProgress => EALREADY,
+            ErrorKind::InProgress => EINPROGRESS,
+            ErrorKind::ConnectionRefused => ECONNREFUSED,
+            ErrorKind::ConnectionReset => ECONNRESET,
+            ErrorKind::OutOfMemory => ENOMEM,
+            ErrorKind::NoDevice => ENODEV,
+            ErrorKind::Busy => EBUSY,
+            ErrorKind::AlreadyExists => EEXIST,
+            ErrorKind::Io => EIO,
+            ErrorKind::Other(errno) => errno,
+        }
+    }
 }
 
 //==============================================================================
@@ -73,3 +207,27 @@ impl From<io::Error> for Fail {
         }
     }
 }
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ErrorKind,
+        Fail,
+    };
+
+    #[test]
+    fn error_kind_round_trip() {
+        for errno in 1..=libc::EHWPOISON {
+            assert_eq!(ErrorKind::from_errno(errno).errno(), errno);
+        }
+        assert_eq!(Fail::new(libc::ETIMEDOUT, "timer expired").kind(), ErrorKind::TimedOut);
+        assert_eq!(
+            Fail::with_kind(ErrorKind::OutOfMemory, "out of buffers").errno,
+            libc::ENOMEM
+        );
+    }
+}
@@ -5,8 +5,15 @@
 // Imports
 //==============================================================================
 
-use ::flexi_logger::Logger;
-use ::std::sync::Once;
+use crate::runtime::fail::Fail;
+use ::flexi_logger::{
+    Logger,
+    LoggerHandle,
+};
+use ::std::sync::{
+    Mutex,
+    Once,
+};
 
 //==============================================================================
 // Static Variables
@@ -15,13 +22,38 @@ use ::std::sync::Once;
 /// Guardian to the logging initialize function.
 static INIT_LOG: Once = Once::new();
 
+/// Handle to the logger, which is used to change the log specification at runtime.
+static LOGGER: Mutex<Option<LoggerHandle>> = Mutex::new(None);
+
 //==============================================================================
 // Standalone Functions
 //==============================================================================
 
-/// Initializes logging features.
+/// Initializes logging features. The initial log specification is read from the RUST_LOG environment variable.
 pub fn initialize() {
     INIT_LOG.call_once(|| {
-        Logger::try_with_env().unwrap().start().unwrap();
+        let handle: LoggerHandle = Logger::try_with_env().unwrap().start().unwrap();
+        *LOGGER.lock().unwrap() = Some(handle);
     });
 }
+
+/// Replaces the log specification at runtime. The specification has the same syntax as the RUST_LOG environment
+/// variable, so per-module filters are supported (e.g. "warn, demikernel::inetstack::protocols::tcp=debug"). Log
+/// statements that are filtered out cost a single comparison against the maximum log level.
+pub fn set_log_spec(spec: &str) -> Result<(), Fail> {
+    initialize();
+    let logger = match LOGGER.lock() {
+        Ok(logger) => logger,
+        Err(_) => return Err(Fail::new(libc::EIO, "logger lock is poisoned")),
+    };
+    match logger.as_ref() {
+        Some(handle) => match handle.parse_new_spec(spec) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let cause: String = format!("invalid log specification: {}", e);
+                Err(Fail::new(libc::EINVAL, &cause))
+            },
+        },
+        None => Err(Fail::new(libc::EIO, "logger is not initialized")),
+    }
+}
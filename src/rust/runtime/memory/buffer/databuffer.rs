@@ -119,6 +119,28 @@ impl DataBuffer {
     pub fn from_slice(src: &[u8]) -> Self {
         src.into()
     }
+
+    /// Creates a data buffer that holds the concatenation of several slices.
+    pub fn from_slices(srcs: &[&[u8]]) -> Self {
+        let len: usize = srcs.iter().map(|src| src.len()).sum();
+        if len == 0 {
+            return Self::empty();
+        }
+
+        let mut data: Arc<[u8]> = unsafe { Arc::new_zeroed_slice(len).assume_init() };
+        let buf: &mut [u8] = Arc::get_mut(&mut data).expect("cannot write to a shared buffer");
+        let mut offset: usize = 0;
+        for src in srcs {
+            buf[offset..(offset + src.len())].copy_from_slice(src);
+            offset += src.len();
+        }
+
+        Self {
+            data: Some(data),
+            offset: 0,
+            len,
+        }
+    }
 }
 
 //==============================================================================
@@ -140,6 +162,14 @@ impl Deref for DataBuffer {
 /// Mutable De-Reference Trait Implementation for Data Buffers
 impl DerefMut for DataBuffer {
     fn deref_mut(&mut self) -> &mut [u8] {
+        // Copy on write: if the underlying data is shared with other buffers, move our view of it into a private copy.
+        if let Some(ref mut data) = self.data {
+            if Arc::get_mut(data).is_none() {
+                *data = data[self.offset..(self.offset + self.len)].into();
+                self.offset = 0;
+            }
+        }
+
         match self.data {
             None => &mut [],
             Some(ref mut data) => {
@@ -161,3 +191,39 @@ impl From<&[u8]> for DataBuffer {
         }
     }
 }
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::DataBuffer;
+
+    #[test]
+    fn write_unique_buffer() {
+        let mut buf: DataBuffer = DataBuffer::from_slice(b"hello");
+        let ptr: *const u8 = buf.as_ptr();
+        buf[0] = b'j';
+        assert_eq!(&buf[..], b"jello");
+        assert_eq!(buf.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn from_slices() {
+        let buf: DataBuffer = DataBuffer::from_slices(&[b"hello", b"", b" ", b"world"]);
+        assert_eq!(&buf[..], b"hello world");
+        assert!(DataBuffer::from_slices(&[b"", b""]).is_empty());
+        assert!(DataBuffer::from_slices(&[]).is_empty());
+    }
+
+    #[test]
+    fn write_shared_buffer() {
+        let buf: DataBuffer = DataBuffer::from_slice(b"hello world");
+        let mut clone: DataBuffer = buf.clone();
+        clone.adjust(6);
+        clone[0] = b'W';
+        assert_eq!(&clone[..], b"World");
+        assert_eq!(&buf[..], b"hello world");
+    }
+}
@@ -5,6 +5,7 @@
 // Imports
 //==============================================================================
 
+use super::poison;
 use crate::runtime::fail::Fail;
 use ::dpdk_rs::{
     rte_mbuf,
@@ -90,6 +91,13 @@ impl DPDKBuffer {
     pub fn get_ptr(&self) -> *mut rte_mbuf {
         self.ptr
     }
+
+    /// Clones the target [Mbuf]. Unlike [Clone::clone], this fails instead of panicking when the memory pool is
+    /// exhausted.
+    pub fn clone_mbuf(&self) -> Result<Self, Fail> {
+        let mbuf_ptr: *mut rte_mbuf = clone_mbuf(self.ptr)?;
+        Ok(DPDKBuffer::new(mbuf_ptr))
+    }
 }
 
 //==============================================================================
@@ -115,12 +123,10 @@ impl DerefMut for DPDKBuffer {
 /// Clone Trait Implementation for DPDK-Managed Buffers
 impl Clone for DPDKBuffer {
     fn clone(&self) -> Self {
-        let mbuf_ptr: *mut rte_mbuf = match clone_mbuf(self.ptr) {
-            Ok(mbuf_ptr) => mbuf_ptr,
+        match self.clone_mbuf() {
+            Ok(mbuf) => mbuf,
             Err(e) => panic!("failed to clone mbuf: {:?}", e.cause),
-        };
-
-        DPDKBuffer::new(mbuf_ptr)
+        }
     }
 }
 
@@ -141,6 +147,7 @@ impl Drop for DPDKBuffer {
 
 /// Releases a mbuf in the target memory pool.
 fn free_mbuf(mbuf_ptr: *mut rte_mbuf) {
+    poison::poison(mbuf_ptr);
     unsafe {
         rte_pktmbuf_free(mbuf_ptr);
     }
@@ -148,11 +155,12 @@ fn free_mbuf(mbuf_ptr: *mut rte_mbuf) {
 
 /// Clones a mbuf into a memory pool.
 fn clone_mbuf(mbuf_ptr: *mut rte_mbuf) -> Result<*mut rte_mbuf, Fail> {
+    poison::check_refcnt(mbuf_ptr);
     unsafe {
         let mempool_ptr: *mut rte_mempool = (*mbuf_ptr).pool;
         let mbuf_ptr_clone: *mut rte_mbuf = rte_pktmbuf_clone(mbuf_ptr, mempool_ptr);
         if mbuf_ptr_clone.is_null() {
-            return Err(Fail::new(libc::EINVAL, "cannot clone mbuf"));
+            return Err(Fail::new(libc::ENOBUFS, "cannot clone mbuf"));
         }
 
         Ok(mbuf_ptr_clone)
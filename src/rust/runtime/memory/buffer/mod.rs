@@ -4,11 +4,14 @@
 mod databuffer;
 #[cfg(feature = "libdpdk")]
 mod dpdkbuffer;
+#[cfg(feature = "libdpdk")]
+pub mod poison;
 
 //==============================================================================
 // Imports
 //==============================================================================
 
+use crate::runtime::fail::Fail;
 use ::core::ops::{
     Deref,
     DerefMut,
@@ -39,7 +42,8 @@ pub enum Buffer {
 //==============================================================================
 
 impl Buffer {
-    /// Removes bytes from the front of the target data buffer.
+    /// Removes `nbytes` bytes from the front of the target buffer, so that it views `buf[nbytes..]`. This is used to
+    /// strip headers off received packets. Panics if the buffer holds fewer than `nbytes` bytes.
     pub fn adjust(&mut self, nbytes: usize) {
         match self {
             Buffer::Heap(dbuf) => dbuf.adjust(nbytes),
@@ -48,7 +52,10 @@ impl Buffer {
         }
     }
 
-    /// Removes bytes from the end of the target data buffer.
+    /// Removes `nbytes` bytes from the end of the target buffer, so that it views `buf[..(len - nbytes)]`. This is used
+    /// to strip padding and trailers off received packets. Panics if the buffer holds fewer than `nbytes` bytes.
+    ///
+    /// Adjusting and trimming compose like slicing, in any order.
     pub fn trim(&mut self, nbytes: usize) {
         match self {
             Buffer::Heap(dbuf) => dbuf.trim(nbytes),
@@ -56,6 +63,16 @@ impl Buffer {
             Buffer::DPDK(mbuf) => mbuf.trim(nbytes),
         }
     }
+
+    /// Clones the target buffer. Unlike [Clone::clone], this fails instead of panicking when a DPDK-managed buffer
+    /// cannot be cloned because its memory pool is exhausted.
+    pub fn try_clone(&self) -> Result<Self, Fail> {
+        match self {
+            Buffer::Heap(dbuf) => Ok(Buffer::Heap(dbuf.clone())),
+            #[cfg(feature = "libdpdk")]
+            Buffer::DPDK(mbuf) => Ok(Buffer::DPDK(mbuf.clone_mbuf()?)),
+        }
+    }
 }
 
 //==============================================================================
@@ -85,3 +102,68 @@ impl DerefMut for Buffer {
         }
     }
 }
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Buffer,
+        DataBuffer,
+    };
+
+    /// Builds a heap-backed buffer with distinct bytes.
+    fn heap_buffer(len: usize) -> (Vec<u8>, Buffer) {
+        let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&data));
+        (data, buf)
+    }
+
+    #[test]
+    fn adjust_and_trim_compose_like_slicing() {
+        let len: usize = 16;
+        for front in 0..=len {
+            for back in 0..=(len - front) {
+                let (data, buf): (Vec<u8>, Buffer) = heap_buffer(len);
+                let expected: &[u8] = &data[front..(len - back)];
+
+                let mut adjusted_first: Buffer = buf.clone();
+                adjusted_first.adjust(front);
+                adjusted_first.trim(back);
+                assert_eq!(&adjusted_first[..], expected);
+
+                let mut trimmed_first: Buffer = buf;
+                trimmed_first.trim(back);
+                trimmed_first.adjust(front);
+                assert_eq!(&trimmed_first[..], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn adjust_and_trim_do_not_affect_clones() {
+        let (data, buf): (Vec<u8>, Buffer) = heap_buffer(8);
+        let mut clone: Buffer = buf.clone();
+        clone.adjust(2);
+        clone.trim(3);
+        assert_eq!(&clone[..], &data[2..5]);
+        assert_eq!(&buf[..], &data[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn adjust_past_end() {
+        let (_, mut buf): (Vec<u8>, Buffer) = heap_buffer(4);
+        buf.adjust(5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn trim_past_beginning() {
+        let (_, mut buf): (Vec<u8>, Buffer) = heap_buffer(4);
+        buf.adjust(1);
+        buf.trim(4);
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Memory poisoning for DPDK-managed buffers.
+//!
+//! When the `mempool-debug` feature is enabled, the data room of a mbuf is filled with a poison pattern when its last
+//! reference is released, and the pattern is checked when the mbuf is allocated again. This catches writes through
+//! stale references to freed buffers, as well as buffers that are freed twice. Which mbufs are poisoned is tracked in a
+//! table keyed by their address, rather than in the data room, so that packet data is never mistaken for poison.
+//! Buffers that are freed or allocated by the device driver are neither poisoned nor checked. Without the feature, all
+//! functions in this module do nothing.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use ::dpdk_rs::{
+    rte_mbuf,
+    rte_mbuf_refcnt_read,
+    IND_ATTACHED_MBUF,
+};
+use ::std::{
+    collections::HashSet,
+    slice,
+    sync::Mutex,
+};
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Byte pattern written over the data room of freed buffers.
+const POISON_BYTE: u8 = 0x6b;
+
+/// Addresses of the mbufs that are poisoned. Memory pools may be shared across threads, so this is global.
+static POISONED: Mutex<Option<HashSet<usize>>> = Mutex::new(None);
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Poisons a mbuf that is about to be freed, if this releases its last reference.
+pub fn poison(mbuf_ptr: *mut rte_mbuf) {
+    if !cfg!(feature = "mempool-debug") {
+        return;
+    }
+    let refcnt: u16 = unsafe { rte_mbuf_refcnt_read(mbuf_ptr) };
+    assert!(refcnt > 0, "freeing mbuf {:?} with no references", mbuf_ptr);
+
+    // Other references, indirect mbufs, and chains free or keep the underlying data rooms on their own terms.
+    if refcnt > 1 || !is_direct_segment(mbuf_ptr) {
+        return;
+    }
+    poison_data_room(mbuf_ptr as usize, unsafe { data_room(mbuf_ptr) });
+}
+
+/// Checks that a freshly allocated mbuf was not written to since it was poisoned.
+pub fn check_poison(mbuf_ptr: *mut rte_mbuf) {
+    if !cfg!(feature = "mempool-debug") || !is_direct_segment(mbuf_ptr) {
+        return;
+    }
+    check_data_room(mbuf_ptr as usize, unsafe { data_room(mbuf_ptr) });
+}
+
+/// Forgets that a mbuf was poisoned, without checking it. This is for mbufs that the device driver allocated and wrote
+/// a received packet to.
+pub fn forget(mbuf_ptr: *mut rte_mbuf) {
+    if !cfg!(feature = "mempool-debug") {
+        return;
+    }
+    set_poisoned(mbuf_ptr as usize, false);
+}
+
+/// Checks that a mbuf that is about to be cloned is still referenced.
+pub fn check_refcnt(mbuf_ptr: *mut rte_mbuf) {
+    if !cfg!(feature = "mempool-debug") {
+        return;
+    }
+    let refcnt: u16 = unsafe { rte_mbuf_refcnt_read(mbuf_ptr) };
+    assert!(refcnt > 0, "cloning mbuf {:?} with no references", mbuf_ptr);
+}
+
+/// Checks if a mbuf owns its data room and is not chained to other segments.
+fn is_direct_segment(mbuf_ptr: *mut rte_mbuf) -> bool {
+    unsafe { ((*mbuf_ptr).ol_flags & IND_ATTACHED_MBUF as u64) == 0 && (*mbuf_ptr).nb_segs == 1 }
+}
+
+/// Returns the whole data room of a mbuf, including its headroom.
+unsafe fn data_room<'a>(mbuf_ptr: *mut rte_mbuf) -> &'a mut [u8] {
+    slice::from_raw_parts_mut((*mbuf_ptr).buf_addr as *mut u8, (*mbuf_ptr).buf_len as usize)
+}
+
+/// Marks the mbuf at `addr` as poisoned or not. Returns `false` if it already was in that state.
+fn set_poisoned(addr: usize, poisoned: bool) -> bool {
+    let mut table = POISONED.lock().unwrap();
+    let table: &mut HashSet<usize> = table.get_or_insert_with(HashSet::new);
+    if poisoned {
+        table.insert(addr)
+    } else {
+        table.remove(&addr)
+    }
+}
+
+/// Poisons the data room of the mbuf at `addr`.
+fn poison_data_room(addr: usize, data_room: &mut [u8]) {
+    assert!(set_poisoned(addr, true), "mbuf {:#x} freed twice", addr);
+    data_room.fill(POISON_BYTE);
+}
+
+/// Checks the data room of the mbuf at `addr`, if it was poisoned, and forgets about the poison.
+fn check_data_room(addr: usize, data_room: &[u8]) {
+    if !set_poisoned(addr, false) {
+        return;
+    }
+    if let Some(offset) = data_room.iter().position(|byte| *byte != POISON_BYTE) {
+        panic!(
+            "mbuf {:#x} was written to after it was freed (offset={:?})",
+            addr, offset
+        );
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_data_room,
+        poison_data_room,
+        POISON_BYTE,
+    };
+    use ::std::panic;
+
+    #[test]
+    fn poison_payload_looks_like_poison() {
+        // A buffer that was never poisoned is not checked, even if its payload starts with the poison pattern.
+        let mut data_room: Vec<u8> = vec![0; 2048];
+        let addr: usize = data_room.as_ptr() as usize;
+        data_room[128..][..8].fill(POISON_BYTE);
+        check_data_room(addr, &data_room);
+
+        // Once poisoned and checked, the buffer is reusable.
+        poison_data_room(addr, &mut data_room);
+        check_data_room(addr, &data_room);
+        data_room[128..][..8].fill(POISON_BYTE);
+        data_room[136] = 0;
+        check_data_room(addr, &data_room);
+    }
+
+    #[test]
+    fn poison_write_after_free() {
+        let mut data_room: Vec<u8> = vec![0; 2048];
+        let addr: usize = data_room.as_ptr() as usize;
+        poison_data_room(addr, &mut data_room);
+        data_room[1000] = 0;
+        assert!(panic::catch_unwind(|| check_data_room(addr, &data_room)).is_err());
+    }
+
+    #[test]
+    fn poison_double_free() {
+        let mut data_room: Vec<u8> = vec![0; 2048];
+        let addr: usize = data_room.as_ptr() as usize;
+        poison_data_room(addr, &mut data_room);
+        assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| poison_data_room(addr, &mut data_room))).is_err());
+
+        // Release the buffer, so that its address can be reused by other tests.
+        check_data_room(addr, &data_room);
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+#[cfg(target_arch = "x86_64")]
+use ::std::arch::x86_64::{
+    __m256i,
+    _mm256_loadu_si256,
+    _mm256_storeu_si256,
+};
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Copies shorter than this (in bytes) always use the standard library routine, since vector setup does not pay off.
+const VECTOR_COPY_THRESHOLD: usize = 256;
+
+//==============================================================================
+// Enumerations
+//==============================================================================
+
+/// Memory Copy Routine
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CopyRoutine {
+    /// Standard library copy.
+    Std,
+    /// Copy with 256-bit AVX2 loads and stores.
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Memory Copy Routines
+impl CopyRoutine {
+    /// Returns the fastest copy routine that is supported by the CPU we are running on.
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            return CopyRoutine::Avx2;
+        }
+        CopyRoutine::Std
+    }
+
+    /// Copies `src` into `dst`. Panics if the two slices have different lengths.
+    pub fn copy(self, dst: &mut [u8], src: &[u8]) {
+        assert_eq!(dst.len(), src.len(), "copy between slices of different lengths");
+        match self {
+            CopyRoutine::Std => dst.copy_from_slice(src),
+            #[cfg(target_arch = "x86_64")]
+            CopyRoutine::Avx2 => unsafe { copy_avx2(dst, src) },
+        }
+    }
+}
+
+//==============================================================================
+// Standalone Functions
+//==============================================================================
+
+/// Copies `src` into `dst` with the fastest routine that the CPU supports. This is used on the paths that copy
+/// application data into device buffers. Panics if the two slices have different lengths.
+pub fn copy(dst: &mut [u8], src: &[u8]) {
+    if src.len() < VECTOR_COPY_THRESHOLD {
+        dst.copy_from_slice(src);
+    } else {
+        CopyRoutine::detect().copy(dst, src);
+    }
+}
+
+/// Copies `src` into `dst` in 32-byte chunks. Both slices must have the same length.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn copy_avx2(dst: &mut [u8], src: &[u8]) {
+    const CHUNK_SIZE: usize = 32;
+    let nchunks: usize = src.len() / CHUNK_SIZE;
+    let src_ptr: *const __m256i = src.as_ptr() as *const __m256i;
+    let dst_ptr: *mut __m256i = dst.as_mut_ptr() as *mut __m256i;
+    for i in 0..nchunks {
+        _mm256_storeu_si256(dst_ptr.add(i), _mm256_loadu_si256(src_ptr.add(i)));
+    }
+    let tail: usize = nchunks * CHUNK_SIZE;
+    dst[tail..].copy_from_slice(&src[tail..]);
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::CopyRoutine;
+    use ::test::{
+        black_box,
+        Bencher,
+    };
+
+    /// Size of the buffers copied in benchmarks.
+    const BENCH_COPY_SIZE: usize = 8192;
+
+    #[test]
+    fn copy_routines_agree() {
+        for len in [0, 1, 31, 32, 33, 255, 256, 257, 1000, 4096, 9001] {
+            let src: Vec<u8> = (0..len).map(|i| (i * 13 + 1) as u8).collect();
+            for routine in [CopyRoutine::Std, CopyRoutine::detect()] {
+                let mut dst: Vec<u8> = vec![0; len];
+                routine.copy(&mut dst, &src);
+                assert_eq!(dst, src, "routine={:?} len={}", routine, len);
+            }
+            let mut dst: Vec<u8> = vec![0; len];
+            super::copy(&mut dst, &src);
+            assert_eq!(dst, src);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn copy_length_mismatch() {
+        let mut dst: [u8; 4] = [0; 4];
+        super::copy(&mut dst, &[1, 2, 3]);
+    }
+
+    #[bench]
+    fn bench_copy_std(b: &mut Bencher) {
+        let src: Vec<u8> = vec![1; BENCH_COPY_SIZE];
+        let mut dst: Vec<u8> = vec![0; BENCH_COPY_SIZE];
+        b.iter(|| {
+            CopyRoutine::Std.copy(&mut dst, &src);
+            black_box(&dst);
+        });
+    }
+
+    #[bench]
+    fn bench_copy_detected(b: &mut Bencher) {
+        let src: Vec<u8> = vec![1; BENCH_COPY_SIZE];
+        let mut dst: Vec<u8> = vec![0; BENCH_COPY_SIZE];
+        let routine: CopyRoutine = CopyRoutine::detect();
+        b.iter(|| {
+            routine.copy(&mut dst, &src);
+            black_box(&dst);
+        });
+    }
+}
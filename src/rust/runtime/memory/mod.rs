@@ -2,6 +2,8 @@
 // Licensed under the MIT license.
 
 mod buffer;
+pub mod copy;
+pub mod stats;
 
 //==============================================================================
 // Imports
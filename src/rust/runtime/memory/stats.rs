@@ -0,0 +1,26 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Memory Pool Identifiers
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MempoolId {
+    /// Pool for protocol headers and inline bodies.
+    Header,
+    /// Pool for large bodies and received packets.
+    Body,
+    /// Reserved pool for control packets.
+    Control,
+}
+
+/// Memory Pool Statistics
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MempoolStats {
+    /// Number of buffers that are currently allocated.
+    pub allocated: usize,
+    /// Number of buffers that are available for allocation, including those held in per-thread caches.
+    pub free: usize,
+}
@@ -5,7 +5,10 @@
 // Imports
 //==============================================================================
 
-use crate::runtime::network::types::MacAddress;
+use crate::runtime::network::{
+    config::RoutingTable,
+    types::MacAddress,
+};
 use ::std::{
     collections::HashMap,
     net::Ipv4Addr,
@@ -29,6 +32,10 @@ pub struct ArpConfig {
     initial_values: HashMap<Ipv4Addr, MacAddress>,
     /// Disable ARP?
     disable_arp: bool,
+    /// Resolve link addresses from the initial values only, without sending ARP requests?
+    static_arp: bool,
+    /// Routes to destinations that are not on-link.
+    routing_table: RoutingTable,
 }
 
 //==============================================================================
@@ -44,6 +51,8 @@ impl ArpConfig {
         retry_count: Option<usize>,
         initial_values: Option<HashMap<Ipv4Addr, MacAddress>>,
         disable_arp: Option<bool>,
+        static_arp: Option<bool>,
+        routing_table: Option<RoutingTable>,
     ) -> Self {
         let mut config: ArpConfig = Self::default();
 
@@ -62,6 +71,12 @@ impl ArpConfig {
         if let Some(disable_arp) = disable_arp {
             config.set_disable_arp(disable_arp);
         }
+        if let Some(static_arp) = static_arp {
+            config.set_static_arp(static_arp);
+        }
+        if let Some(routing_table) = routing_table {
+            config.set_routing_table(routing_table);
+        }
 
         config
     }
@@ -91,6 +106,16 @@ impl ArpConfig {
         self.disable_arp
     }
 
+    /// Gets the static option of the ARP in the target [ArpConfig].
+    pub fn get_static_arp(&self) -> bool {
+        self.static_arp
+    }
+
+    /// Gets the routing table in the target [ArpConfig].
+    pub fn get_routing_table(&self) -> &RoutingTable {
+        &self.routing_table
+    }
+
     /// Sets the time to live for entries of the ARP Cache in the target [ArpConfig].
     fn set_cache_ttl(&mut self, cache_ttl: Duration) {
         self.cache_ttl = cache_ttl
@@ -115,6 +140,16 @@ impl ArpConfig {
     fn set_disable_arp(&mut self, disable_arp: bool) {
         self.disable_arp = disable_arp
     }
+
+    /// Sets the static option of the ARP in the target [ArpConfig].
+    fn set_static_arp(&mut self, static_arp: bool) {
+        self.static_arp = static_arp
+    }
+
+    /// Sets the routing table in the target [ArpConfig].
+    fn set_routing_table(&mut self, routing_table: RoutingTable) {
+        self.routing_table = routing_table
+    }
 }
 
 //==============================================================================
@@ -131,6 +166,8 @@ impl Default for ArpConfig {
             retry_count: 5,
             initial_values: HashMap::new(),
             disable_arp: false,
+            static_arp: false,
+            routing_table: RoutingTable::default(),
         }
     }
 }
@@ -156,5 +193,6 @@ mod tests {
         assert_eq!(config.get_retry_count(), 5);
         assert_eq!(config.get_initial_values(), &HashMap::new());
         assert_eq!(config.get_disable_arp(), false);
+        assert_eq!(config.get_static_arp(), false);
     }
 }
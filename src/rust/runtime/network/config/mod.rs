@@ -2,6 +2,7 @@
 // Licensed under the MIT license.
 
 mod arp;
+mod route;
 mod tcp;
 mod udp;
 
@@ -11,6 +12,10 @@ mod udp;
 
 pub use self::{
     arp::ArpConfig,
+    route::{
+        Route,
+        RoutingTable,
+    },
     tcp::TcpConfig,
     udp::UdpConfig,
 };
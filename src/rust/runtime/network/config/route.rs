@@ -0,0 +1,170 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::fail::Fail;
+use ::std::net::Ipv4Addr;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Route
+///
+/// Sends traffic for a destination prefix through a gateway.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Route {
+    /// Destination prefix.
+    destination: Ipv4Addr,
+    /// Length of the destination prefix (in bits).
+    prefix_len: u8,
+    /// Next hop for the destination prefix. The unspecified address marks an on-link prefix.
+    gateway: Ipv4Addr,
+}
+
+/// Routing Table
+///
+/// Destinations that no route matches are assumed to be on-link.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RoutingTable {
+    /// Routes, sorted by decreasing prefix length.
+    routes: Vec<Route>,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Routes
+impl Route {
+    /// Creates a route.
+    pub fn new(destination: Ipv4Addr, prefix_len: u8, gateway: Ipv4Addr) -> Result<Self, Fail> {
+        if prefix_len > 32 {
+            return Err(Fail::new(libc::EINVAL, "invalid prefix length"));
+        }
+        Ok(Self {
+            destination,
+            prefix_len,
+            gateway,
+        })
+    }
+
+    /// Parses a route from a destination prefix in CIDR notation (e.g. "10.1.0.0/16") and a gateway address.
+    pub fn parse(destination: &str, gateway: &str) -> Result<Self, Fail> {
+        let (addr, prefix_len): (&str, &str) = match destination.split_once('/') {
+            Some(parts) => parts,
+            None => (destination, "32"),
+        };
+        let addr: Ipv4Addr = addr
+            .parse()
+            .map_err(|_| Fail::new(libc::EINVAL, "invalid route destination"))?;
+        let prefix_len: u8 = prefix_len
+            .parse()
+            .map_err(|_| Fail::new(libc::EINVAL, "invalid prefix length"))?;
+        let gateway: Ipv4Addr = gateway
+            .parse()
+            .map_err(|_| Fail::new(libc::EINVAL, "invalid route gateway"))?;
+        Self::new(addr, prefix_len, gateway)
+    }
+
+    /// Checks if an address falls within the destination prefix of the target route.
+    fn matches(&self, ipv4_addr: Ipv4Addr) -> bool {
+        let mask: u32 = match self.prefix_len {
+            0 => 0,
+            len => u32::MAX << (32 - len),
+        };
+        (u32::from(ipv4_addr) & mask) == (u32::from(self.destination) & mask)
+    }
+}
+
+/// Associate Functions for Routing Tables
+impl RoutingTable {
+    /// Creates a routing table.
+    pub fn new(mut routes: Vec<Route>) -> Self {
+        routes.sort_by(|a, b| b.prefix_len.cmp(&a.prefix_len));
+        Self { routes }
+    }
+
+    /// Returns the address of the next hop towards a destination, using the longest matching prefix. This is the
+    /// destination itself if it is on-link.
+    pub fn next_hop(&self, dest: Ipv4Addr) -> Ipv4Addr {
+        match self.routes.iter().find(|route| route.matches(dest)) {
+            Some(route) if !route.gateway.is_unspecified() => route.gateway,
+            _ => dest,
+        }
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Route,
+        RoutingTable,
+    };
+    use ::std::net::Ipv4Addr;
+
+    #[test]
+    fn longest_prefix_match() {
+        let routes: RoutingTable = RoutingTable::new(vec![
+            Route::parse("0.0.0.0/0", "192.168.1.1").unwrap(),
+            Route::parse("10.0.0.0/8", "192.168.1.2").unwrap(),
+            Route::parse("10.1.0.0/16", "192.168.1.3").unwrap(),
+            Route::parse("10.1.2.3", "192.168.1.4").unwrap(),
+        ]);
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(8, 8, 8, 8)),
+            Ipv4Addr::new(192, 168, 1, 1)
+        );
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(10, 2, 0, 1)),
+            Ipv4Addr::new(192, 168, 1, 2)
+        );
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(10, 1, 0, 1)),
+            Ipv4Addr::new(192, 168, 1, 3)
+        );
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(10, 1, 2, 3)),
+            Ipv4Addr::new(192, 168, 1, 4)
+        );
+
+        // On-link prefixes take precedence over shorter ones.
+        let routes: RoutingTable = RoutingTable::new(vec![
+            Route::parse("0.0.0.0/0", "192.168.1.1").unwrap(),
+            Route::parse("192.168.1.0/24", "0.0.0.0").unwrap(),
+        ]);
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(192, 168, 1, 7)),
+            Ipv4Addr::new(192, 168, 1, 7)
+        );
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(192, 168, 2, 7)),
+            Ipv4Addr::new(192, 168, 1, 1)
+        );
+
+        // Without a default route, unmatched destinations are on-link.
+        let routes: RoutingTable = RoutingTable::new(vec![Route::parse("10.0.0.0/8", "192.168.1.2").unwrap()]);
+        assert_eq!(
+            routes.next_hop(Ipv4Addr::new(192, 168, 1, 7)),
+            Ipv4Addr::new(192, 168, 1, 7)
+        );
+        assert_eq!(
+            RoutingTable::default().next_hop(Ipv4Addr::new(10, 0, 0, 1)),
+            Ipv4Addr::new(10, 0, 0, 1)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_route() {
+        assert!(Route::parse("10.0.0.0/33", "192.168.1.1").is_err());
+        assert!(Route::parse("10.0.0/8", "192.168.1.1").is_err());
+        assert!(Route::parse("10.0.0.0/8", "gateway").is_err());
+    }
+}
@@ -7,6 +7,7 @@
 
 use crate::runtime::network::consts::{
     DEFAULT_MSS,
+    DEFAULT_SEND_BUFFER_SIZE,
     MAX_MSS,
     MIN_MSS,
 };
@@ -35,6 +36,10 @@ pub struct TcpConfig {
     rx_checksum_offload: bool,
     /// Offload Checksum to Hardware When Sending?
     tx_checksum_offload: bool,
+    /// Maximum Number of Out-of-Order Segments Held per Connection
+    max_out_of_order: usize,
+    /// Maximum Number of Bytes Queued for Sending per Connection
+    send_buffer_size: usize,
 }
 
 //==============================================================================
@@ -53,6 +58,8 @@ impl TcpConfig {
         ack_delay_timeout: Option<Duration>,
         rx_checksum_offload: Option<bool>,
         tx_checksum_offload: Option<bool>,
+        max_out_of_order: Option<usize>,
+        send_buffer_size: Option<usize>,
     ) -> Self {
         let mut options = Self::default();
 
@@ -80,6 +87,12 @@ impl TcpConfig {
         if let Some(value) = tx_checksum_offload {
             options.tx_checksum_offload = value;
         }
+        if let Some(value) = max_out_of_order {
+            options = options.set_max_out_of_order(value);
+        }
+        if let Some(value) = send_buffer_size {
+            options = options.set_send_buffer_size(value);
+        }
 
         options
     }
@@ -124,6 +137,16 @@ impl TcpConfig {
         self.rx_checksum_offload
     }
 
+    /// Gets the maximum number of out-of-order segments held per connection in the target [TcpConfig].
+    pub fn get_max_out_of_order(&self) -> usize {
+        self.max_out_of_order
+    }
+
+    /// Gets the send buffer size (in bytes) in the target [TcpConfig].
+    pub fn get_send_buffer_size(&self) -> usize {
+        self.send_buffer_size
+    }
+
     /// Sets the advertised maximum segment size in the target [TcpConfig].
     fn set_advertised_mss(mut self, value: usize) -> Self {
         assert!(value >= MIN_MSS);
@@ -165,6 +188,20 @@ impl TcpConfig {
         self.ack_delay_timeout = value;
         self
     }
+
+    /// Sets the maximum number of out-of-order segments held per connection in the target [TcpConfig].
+    fn set_max_out_of_order(mut self, value: usize) -> Self {
+        assert!(value > 0);
+        self.max_out_of_order = value;
+        self
+    }
+
+    /// Sets the send buffer size (in bytes) in the target [TcpConfig].
+    fn set_send_buffer_size(mut self, value: usize) -> Self {
+        assert!(value > 0);
+        self.send_buffer_size = value;
+        self
+    }
 }
 
 //==============================================================================
@@ -184,6 +221,12 @@ impl Default for TcpConfig {
             window_scale: 0,
             rx_checksum_offload: false,
             tx_checksum_offload: false,
+            // ToDo: Review this value. It (16 segments) seems awfully small (would make fast retransmit less useful),
+            // and this mechanism isn't the best way to protect ourselves against deliberate out-of-order segment
+            // attacks. Ideally, we'd limit out-of-order data to that which (along with the unread data) will fit in
+            // the receive window.
+            max_out_of_order: 16,
+            send_buffer_size: DEFAULT_SEND_BUFFER_SIZE,
         }
     }
 }
@@ -196,7 +239,10 @@ impl Default for TcpConfig {
 mod tests {
     use crate::runtime::network::{
         config::TcpConfig,
-        consts::DEFAULT_MSS,
+        consts::{
+            DEFAULT_MSS,
+            DEFAULT_SEND_BUFFER_SIZE,
+        },
     };
     use ::std::time::Duration;
 
@@ -211,5 +257,7 @@ mod tests {
         assert_eq!(config.get_window_scale(), 0);
         assert_eq!(config.get_rx_checksum_offload(), false);
         assert_eq!(config.get_tx_checksum_offload(), false);
+        assert_eq!(config.get_max_out_of_order(), 16);
+        assert_eq!(config.get_send_buffer_size(), DEFAULT_SEND_BUFFER_SIZE);
     }
 }
@@ -19,7 +19,16 @@ pub const MAX_MSS: usize = u16::max_value() as usize;
 /// TODO: Auto-Discovery MTU Size
 pub const DEFAULT_MSS: usize = 1450;
 
-/// Length of a [crate::memory::Buffer] batch.
+/// Default Send Buffer Size for TCP (in bytes)
+pub const DEFAULT_SEND_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Maximum length of a [crate::memory::Buffer] batch.
 ///
 /// TODO: This Should be Generic
-pub const RECEIVE_BATCH_SIZE: usize = 4;
+pub const RECEIVE_BATCH_SIZE: usize = 32;
+
+/// Default number of packets that are received at once.
+pub const DEFAULT_RECEIVE_BATCH_SIZE: usize = 4;
+
+/// Maximum number of packets that may be held back for a single transmit burst.
+pub const MAX_TRANSMIT_BATCH_SIZE: usize = 32;
@@ -0,0 +1,24 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Enumerations
+//==============================================================================
+
+/// Device Event
+///
+/// Reports a change in the state of the network device underneath a LibOS, so that applications notice conditions
+/// that would otherwise show up as stalled operations.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeviceEvent {
+    /// The link came up, at the given speed (in Mbps).
+    LinkUp(u32),
+    /// The link went down. Frames sent until it comes up again are lost.
+    LinkDown,
+    /// The device discarded this many received frames because they were erroneous.
+    RxErrors(u64),
+    /// The device dropped this many received frames because the receive queues ran out of buffers.
+    RxNoBuffers(u64),
+    /// The device failed to transmit this many frames.
+    TxErrors(u64),
+}
@@ -17,6 +17,11 @@ use ::arrayvec::ArrayVec;
 
 pub mod config;
 pub mod consts;
+pub mod event;
+pub mod preamble;
+pub mod sequencer;
+pub mod stats;
+pub mod topology;
 pub mod types;
 
 //==============================================================================
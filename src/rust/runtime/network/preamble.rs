@@ -0,0 +1,165 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::{
+    fail::Fail,
+    memory::Buffer,
+};
+use ::byteorder::{
+    ByteOrder,
+    LittleEndian,
+};
+use ::libc::EBADMSG;
+
+//==============================================================================
+// Constants
+//==============================================================================
+
+/// Size of Request Preambles (in bytes)
+pub const REQUEST_PREAMBLE_SIZE: usize = 32;
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Request Preamble
+///
+/// Fixed-size prefix that benchmark clients prepend to each request. It carries a timestamp (bytes 0 to 8), a flow
+/// identifier (bytes 8 to 16), a per-flow sequence number (bytes 16 to 24) and the length of the whole message,
+/// preamble included (bytes 24 to 28), all in little-endian byte order. A length of zero means that it is unknown. The
+/// remaining bytes are reserved.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RequestPreamble {
+    /// Timestamp taken by the client when the request was issued.
+    timestamp: u64,
+    /// Flow identifier.
+    flow_id: u64,
+    /// Sequence number within the flow.
+    sequence: u64,
+    /// Length of the whole message, preamble included.
+    length: u32,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Request Preambles
+impl RequestPreamble {
+    /// Creates a request preamble.
+    pub fn new(timestamp: u64, flow_id: u64, sequence: u64) -> Self {
+        Self {
+            timestamp,
+            flow_id,
+            sequence,
+            length: 0,
+        }
+    }
+
+    /// Sets the length of the whole message, preamble included, in the target request preamble.
+    pub fn with_length(mut self, length: u32) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Returns the timestamp stored in the target request preamble.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Returns the flow identifier stored in the target request preamble.
+    pub fn flow_id(&self) -> u64 {
+        self.flow_id
+    }
+
+    /// Returns the sequence number stored in the target request preamble.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns the length of the whole message, preamble included, stored in the target request preamble. Zero means
+    /// that it is unknown.
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Returns the size of the target request preamble.
+    pub fn size(&self) -> usize {
+        REQUEST_PREAMBLE_SIZE
+    }
+
+    /// Parses a request preamble out of `buf` and returns the payload that follows it. No data is copied.
+    pub fn parse(mut buf: Buffer) -> Result<(Self, Buffer), Fail> {
+        if buf.len() < REQUEST_PREAMBLE_SIZE {
+            return Err(Fail::new(EBADMSG, "request too small for preamble"));
+        }
+        let timestamp: u64 = LittleEndian::read_u64(&buf[0..8]);
+        let flow_id: u64 = LittleEndian::read_u64(&buf[8..16]);
+        let sequence: u64 = LittleEndian::read_u64(&buf[16..24]);
+        let length: u32 = LittleEndian::read_u32(&buf[24..28]);
+
+        buf.adjust(REQUEST_PREAMBLE_SIZE);
+        Ok((
+            Self {
+                timestamp,
+                flow_id,
+                sequence,
+                length,
+            },
+            buf,
+        ))
+    }
+
+    /// Serializes the target request preamble into the front of `buf`.
+    pub fn serialize(&self, buf: &mut [u8]) {
+        let buf: &mut [u8] = &mut buf[..REQUEST_PREAMBLE_SIZE];
+        LittleEndian::write_u64(&mut buf[0..8], self.timestamp);
+        LittleEndian::write_u64(&mut buf[8..16], self.flow_id);
+        LittleEndian::write_u64(&mut buf[16..24], self.sequence);
+        LittleEndian::write_u32(&mut buf[24..28], self.length);
+        buf[28..].fill(0);
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RequestPreamble,
+        REQUEST_PREAMBLE_SIZE,
+    };
+    use crate::runtime::memory::{
+        Buffer,
+        DataBuffer,
+    };
+
+    #[test]
+    fn parse_preamble() {
+        let preamble: RequestPreamble = RequestPreamble::new(0x0102030405060708, 42, 7).with_length(39);
+        let mut bytes: Vec<u8> = vec![0; REQUEST_PREAMBLE_SIZE];
+        preamble.serialize(&mut bytes);
+        bytes.extend_from_slice(b"payload");
+
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&bytes));
+        let (parsed, payload): (RequestPreamble, Buffer) = RequestPreamble::parse(buf).unwrap();
+        assert_eq!(parsed, preamble);
+        assert_eq!(parsed.timestamp(), 0x0102030405060708);
+        assert_eq!(parsed.flow_id(), 42);
+        assert_eq!(parsed.sequence(), 7);
+        assert_eq!(parsed.length(), 39);
+        assert_eq!(&payload[..], b"payload");
+    }
+
+    #[test]
+    fn parse_preamble_too_small() {
+        let buf: Buffer = Buffer::Heap(DataBuffer::from_slice(&[0; REQUEST_PREAMBLE_SIZE - 1]));
+        assert!(RequestPreamble::parse(buf).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use ::std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Per-Flow Counters
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlowStats {
+    /// Messages delivered in sequence order.
+    pub delivered: u64,
+    /// Messages that arrived ahead of a gap and were held back before being delivered.
+    pub reordered: u64,
+    /// Messages that were given up on, either because the reorder window overflowed or the wait timed out.
+    pub dropped: u64,
+    /// Messages that arrived after they were given up on, or duplicates. These are discarded.
+    pub late: u64,
+}
+
+/// Per-Flow State
+struct Flow<T> {
+    /// Next sequence number to deliver.
+    next: u64,
+    /// Messages that arrived ahead of `next`, along with their arrival time.
+    pending: BTreeMap<u64, (Instant, T)>,
+    /// Counters.
+    stats: FlowStats,
+}
+
+/// Flow Sequencer
+///
+/// Restores per-flow sequence order on top of an unreliable datagram transport. Messages that arrive ahead of a gap
+/// are held in a small reorder buffer until either the gap is filled, the buffer overflows, or the oldest held message
+/// has waited longer than the configured timeout. In the latter two cases the gap is accounted as dropped. This lets
+/// clients that measure loss tell apart lost messages from reordered ones.
+pub struct FlowSequencer<T> {
+    /// Maximum number of messages held back per flow.
+    window: usize,
+    /// Maximum time a message is held back.
+    timeout: Duration,
+    /// Flows.
+    flows: HashMap<u64, Flow<T>>,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Flows
+impl<T> Flow<T> {
+    /// Creates a flow that starts at sequence number zero.
+    fn new() -> Self {
+        Self {
+            next: 0,
+            pending: BTreeMap::new(),
+            stats: FlowStats::default(),
+        }
+    }
+
+    /// Moves messages that are now in sequence from the reorder buffer into `out`.
+    fn drain(&mut self, out: &mut Vec<T>) {
+        while let Some((_, msg)) = self.pending.remove(&self.next) {
+            self.stats.reordered += 1;
+            self.stats.delivered += 1;
+            self.next += 1;
+            out.push(msg);
+        }
+    }
+
+    /// Returns the arrival time of the message that has been held back the longest. This is not necessarily the held
+    /// message with the lowest sequence number, as messages may arrive in any order.
+    fn oldest_arrival(&self) -> Option<Instant> {
+        self.pending.values().map(|(arrival, _)| *arrival).min()
+    }
+
+    /// Gives up on the gap in front of the held message with the lowest sequence number.
+    fn skip_gap(&mut self, out: &mut Vec<T>) {
+        if let Some(&first) = self.pending.keys().next() {
+            self.stats.dropped += first - self.next;
+            self.next = first;
+            self.drain(out);
+        }
+    }
+}
+
+/// Associate Functions for Flow Sequencers
+impl<T> FlowSequencer<T> {
+    /// Creates a flow sequencer.
+    pub fn new(window: usize, timeout: Duration) -> Self {
+        Self {
+            window,
+            timeout,
+            flows: HashMap::new(),
+        }
+    }
+
+    /// Feeds a message into the target flow sequencer. Messages that can be delivered in order are returned.
+    pub fn push(&mut self, flow_id: u64, sequence: u64, msg: T, now: Instant) -> Vec<T> {
+        let window: usize = self.window;
+        let flow: &mut Flow<T> = self.flows.entry(flow_id).or_insert_with(Flow::new);
+        let mut out: Vec<T> = Vec::new();
+
+        if sequence < flow.next || flow.pending.contains_key(&sequence) {
+            flow.stats.late += 1;
+        } else if sequence == flow.next {
+            flow.stats.delivered += 1;
+            flow.next += 1;
+            out.push(msg);
+            flow.drain(&mut out);
+        } else {
+            flow.pending.insert(sequence, (now, msg));
+            if flow.pending.len() > window {
+                flow.skip_gap(&mut out);
+            }
+        }
+
+        out
+    }
+
+    /// Gives up on gaps that have been waited on for longer than the timeout. Messages that become deliverable are
+    /// returned along with their flow identifier.
+    pub fn poll(&mut self, now: Instant) -> Vec<(u64, T)> {
+        let mut out: Vec<(u64, T)> = Vec::new();
+        for (&flow_id, flow) in self.flows.iter_mut() {
+            loop {
+                let expired: bool = match flow.oldest_arrival() {
+                    Some(arrival) => now.duration_since(arrival) >= self.timeout,
+                    None => false,
+                };
+                if !expired {
+                    break;
+                }
+                let mut msgs: Vec<T> = Vec::new();
+                flow.skip_gap(&mut msgs);
+                out.extend(msgs.into_iter().map(|msg| (flow_id, msg)));
+            }
+        }
+        out
+    }
+
+    /// Returns the counters of a flow.
+    pub fn stats(&self, flow_id: u64) -> Option<FlowStats> {
+        self.flows.get(&flow_id).map(|flow| flow.stats)
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FlowSequencer,
+        FlowStats,
+    };
+    use ::std::time::{
+        Duration,
+        Instant,
+    };
+
+    #[test]
+    fn sequencer_in_order() {
+        let now: Instant = Instant::now();
+        let mut seq: FlowSequencer<u64> = FlowSequencer::new(4, Duration::from_millis(10));
+        for i in 0..8 {
+            assert_eq!(seq.push(1, i, i, now), vec![i]);
+        }
+        let stats: FlowStats = seq.stats(1).unwrap();
+        assert_eq!(stats.delivered, 8);
+        assert_eq!(stats.reordered, 0);
+        assert_eq!(stats.dropped, 0);
+    }
+
+    #[test]
+    fn sequencer_reordered() {
+        let now: Instant = Instant::now();
+        let mut seq: FlowSequencer<u64> = FlowSequencer::new(4, Duration::from_millis(10));
+        assert_eq!(seq.push(1, 0, 0, now), vec![0]);
+        assert!(seq.push(1, 2, 2, now).is_empty());
+        assert!(seq.push(1, 3, 3, now).is_empty());
+        assert_eq!(seq.push(1, 1, 1, now), vec![1, 2, 3]);
+
+        // Duplicates are discarded.
+        assert!(seq.push(1, 2, 2, now).is_empty());
+
+        let stats: FlowStats = seq.stats(1).unwrap();
+        assert_eq!(stats.delivered, 4);
+        assert_eq!(stats.reordered, 2);
+        assert_eq!(stats.dropped, 0);
+        assert_eq!(stats.late, 1);
+    }
+
+    #[test]
+    fn sequencer_window_overflow() {
+        let now: Instant = Instant::now();
+        let mut seq: FlowSequencer<u64> = FlowSequencer::new(2, Duration::from_secs(1));
+        assert!(seq.push(7, 1, 1, now).is_empty());
+        assert!(seq.push(7, 2, 2, now).is_empty());
+        assert_eq!(seq.push(7, 3, 3, now), vec![1, 2, 3]);
+
+        // Message zero shows up after it was given up on.
+        assert!(seq.push(7, 0, 0, now).is_empty());
+
+        let stats: FlowStats = seq.stats(7).unwrap();
+        assert_eq!(stats.dropped, 1);
+        assert_eq!(stats.late, 1);
+        assert_eq!(stats.delivered, 3);
+    }
+
+    #[test]
+    fn sequencer_timeout() {
+        let now: Instant = Instant::now();
+        let mut seq: FlowSequencer<u64> = FlowSequencer::new(8, Duration::from_millis(10));
+        assert!(seq.push(3, 2, 2, now).is_empty());
+        assert!(seq.poll(now + Duration::from_millis(5)).is_empty());
+        assert_eq!(seq.poll(now + Duration::from_millis(10)), vec![(3, 2)]);
+
+        let stats: FlowStats = seq.stats(3).unwrap();
+        assert_eq!(stats.dropped, 2);
+        assert_eq!(stats.delivered, 1);
+        assert!(seq.stats(4).is_none());
+    }
+
+    /// Tests that the timeout runs from the arrival of the oldest held message, even if messages with lower sequence
+    /// numbers arrive later.
+    #[test]
+    fn sequencer_timeout_out_of_order_arrival() {
+        let now: Instant = Instant::now();
+        let mut seq: FlowSequencer<u64> = FlowSequencer::new(8, Duration::from_millis(10));
+        assert!(seq.push(5, 3, 3, now).is_empty());
+        assert!(seq.push(5, 2, 2, now + Duration::from_millis(8)).is_empty());
+        assert!(seq.poll(now + Duration::from_millis(9)).is_empty());
+        assert_eq!(seq.poll(now + Duration::from_millis(10)), vec![(5, 2), (5, 3)]);
+
+        let stats: FlowStats = seq.stats(5).unwrap();
+        assert_eq!(stats.dropped, 2);
+        assert_eq!(stats.delivered, 2);
+        assert_eq!(stats.reordered, 2);
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Transmit Copy Statistics
+///
+/// Accounts for payload bytes handed to the device, split by whether they were sent straight out of the buffer that
+/// was pushed by the application or had to be copied first, along with the reason for the copy.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TxStats {
+    /// Number of packets transmitted.
+    pub packets: u64,
+    /// Payload bytes sent without being copied, because they already lived in a DPDK-managed buffer.
+    pub zero_copy_bytes: u64,
+    /// Payload bytes copied into the header mbuf, because they were small enough to be inlined.
+    pub inline_bytes: u64,
+    /// Payload bytes copied into a body mbuf, because they lived in a heap-managed buffer.
+    pub heap_copy_bytes: u64,
+    /// Number of packets dropped, because no mbuf could be allocated for them or the transmit queue was stuck.
+    pub dropped_packets: u64,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Transmit Copy Statistics
+impl TxStats {
+    /// Returns the total number of payload bytes that were copied.
+    pub fn copied_bytes(&self) -> u64 {
+        self.inline_bytes + self.heap_copy_bytes
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//==============================================================================
+// Imports
+//==============================================================================
+
+use crate::runtime::{
+    fail::Fail,
+    network::{
+        config::{
+            TcpConfig,
+            UdpConfig,
+        },
+        types::MacAddress,
+    },
+};
+use ::std::{
+    fs,
+    net::Ipv4Addr,
+};
+
+//==============================================================================
+// Structures
+//==============================================================================
+
+/// Memory Pool Description
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MempoolTopology {
+    /// Name of the memory pool.
+    pub name: String,
+    /// Number of buffers in the memory pool.
+    pub num_buffers: usize,
+    /// Size of each buffer (in bytes).
+    pub buffer_size: usize,
+}
+
+/// Offload Capabilities
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct OffloadTopology {
+    /// Is TCP checksum verification offloaded to the device?
+    pub tcp_rx_checksum: bool,
+    /// Is TCP checksum computation offloaded to the device?
+    pub tcp_tx_checksum: bool,
+    /// Is UDP checksum verification offloaded to the device?
+    pub udp_rx_checksum: bool,
+    /// Is UDP checksum computation offloaded to the device?
+    pub udp_tx_checksum: bool,
+    /// Can the device transmit packets that span several buffers?
+    pub tx_multi_segs: bool,
+}
+
+/// Device Capabilities
+///
+/// Describes what a device supports. The same structure describes what a LibOS requires from a device, in which case
+/// flags that are not set and limits that are zero are not required.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    /// Maximum number of receive queues.
+    pub max_rx_queues: usize,
+    /// Maximum number of transmit queues.
+    pub max_tx_queues: usize,
+    /// Maximum number of descriptors in each receive queue.
+    pub max_rx_ring_size: usize,
+    /// Maximum number of descriptors in each transmit queue.
+    pub max_tx_ring_size: usize,
+    /// Maximum number of buffers that a transmitted packet may span.
+    pub max_tx_segments: usize,
+    /// Maximum transmission unit.
+    pub max_mtu: u16,
+    /// Supported offloads.
+    pub offloads: OffloadTopology,
+    /// Can the device receive jumbo frames?
+    pub jumbo_frames: bool,
+    /// Can the device timestamp received packets?
+    pub rx_timestamp: bool,
+    /// Can the device spread received packets across queues with receive side scaling?
+    pub rss: bool,
+}
+
+/// Datapath Topology
+///
+/// Describes the datapath that was instantiated by a LibOS, so that benchmark and orchestration scripts can record
+/// the exact environment they ran in. Fields that do not apply to a LibOS are left empty.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Topology {
+    /// Name of the LibOS.
+    pub libos: String,
+    /// Device name. This is the PCI address for DPDK devices, and the interface name for raw sockets.
+    pub device: Option<String>,
+    /// Name of the device driver.
+    pub driver: Option<String>,
+    /// Firmware version of the device.
+    pub firmware_version: Option<String>,
+    /// Number of receive queues in use.
+    pub num_rx_queues: usize,
+    /// Number of transmit queues in use.
+    pub num_tx_queues: usize,
+    /// Number of descriptors in each receive queue.
+    pub rx_ring_size: usize,
+    /// Number of descriptors in each transmit queue.
+    pub tx_ring_size: usize,
+    /// Maximum transmission unit.
+    pub mtu: Option<u16>,
+    /// Link address.
+    pub link_addr: Option<MacAddress>,
+    /// IPv4 address.
+    pub ipv4_addr: Option<Ipv4Addr>,
+    /// Size of regular memory pages (in bytes).
+    pub page_size: usize,
+    /// Size of huge memory pages (in bytes), if the system has any.
+    pub hugepage_size: Option<usize>,
+    /// Memory pools.
+    pub mempools: Vec<MempoolTopology>,
+    /// Offload capabilities in use.
+    pub offloads: OffloadTopology,
+    /// Capabilities of the device.
+    pub capabilities: Capabilities,
+}
+
+//==============================================================================
+// Associate Functions
+//==============================================================================
+
+/// Associate Functions for Offload Capabilities
+impl OffloadTopology {
+    /// Creates a description of the checksum offloads enabled in the TCP and UDP configurations.
+    pub fn new(tcp_config: &TcpConfig, udp_config: &UdpConfig) -> Self {
+        Self {
+            tcp_rx_checksum: tcp_config.get_rx_checksum_offload(),
+            tcp_tx_checksum: tcp_config.get_tx_checksum_offload(),
+            udp_rx_checksum: udp_config.get_rx_checksum_offload(),
+            udp_tx_checksum: udp_config.get_tx_checksum_offload(),
+            tx_multi_segs: false,
+        }
+    }
+}
+
+/// Associate Functions for Device Capabilities
+impl Capabilities {
+    /// Checks that the target [Capabilities] cover everything in `required`, and reports the first missing feature
+    /// otherwise.
+    pub fn check(&self, required: &Capabilities) -> Result<(), Fail> {
+        let limits: [(&str, usize, usize); 6] = [
+            ("receive queues", self.max_rx_queues, required.max_rx_queues),
+            ("transmit queues", self.max_tx_queues, required.max_tx_queues),
+            (
+                "receive descriptors per queue",
+                self.max_rx_ring_size,
+                required.max_rx_ring_size,
+            ),
+            (
+                "transmit descriptors per queue",
+                self.max_tx_ring_size,
+                required.max_tx_ring_size,
+            ),
+            (
+                "segments per transmitted packet",
+                self.max_tx_segments,
+                required.max_tx_segments,
+            ),
+            ("bytes of MTU", self.max_mtu as usize, required.max_mtu as usize),
+        ];
+        for (name, supported, wanted) in limits {
+            if wanted > supported {
+                let cause: String = format!("device supports at most {} {} ({} required)", supported, name, wanted);
+                return Err(Fail::new(libc::ENOTSUP, &cause));
+            }
+        }
+
+        let features: [(&str, bool, bool); 8] = [
+            (
+                "TCP receive checksum offload",
+                self.offloads.tcp_rx_checksum,
+                required.offloads.tcp_rx_checksum,
+            ),
+            (
+                "TCP transmit checksum offload",
+                self.offloads.tcp_tx_checksum,
+                required.offloads.tcp_tx_checksum,
+            ),
+            (
+                "UDP receive checksum offload",
+                self.offloads.udp_rx_checksum,
+                required.offloads.udp_rx_checksum,
+            ),
+            (
+                "UDP transmit checksum offload",
+                self.offloads.udp_tx_checksum,
+                required.offloads.udp_tx_checksum,
+            ),
+            (
+                "multi-segment transmission",
+                self.offloads.tx_multi_segs,
+                required.offloads.tx_multi_segs,
+            ),
+            ("jumbo frames", self.jumbo_frames, required.jumbo_frames),
+            ("receive timestamps", self.rx_timestamp, required.rx_timestamp),
+            ("receive side scaling", self.rss, required.rss),
+        ];
+        for (name, supported, wanted) in features {
+            if wanted && !supported {
+                let cause: String = format!("device does not support {}", name);
+                return Err(Fail::new(libc::ENOTSUP, &cause));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Associate Functions for Datapath Topologies
+impl Topology {
+    /// Creates a datapath topology that only carries system-wide information. The caller fills in the rest.
+    pub fn new(libos: &str) -> Self {
+        Self {
+            libos: libos.to_string(),
+            device: None,
+            driver: None,
+            firmware_version: None,
+            num_rx_queues: 0,
+            num_tx_queues: 0,
+            rx_ring_size: 0,
+            tx_ring_size: 0,
+            mtu: None,
+            link_addr: None,
+            ipv4_addr: None,
+            page_size: unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize,
+            hugepage_size: Self::read_hugepage_size(),
+            mempools: Vec::new(),
+            offloads: OffloadTopology::default(),
+            capabilities: Capabilities::default(),
+        }
+    }
+
+    /// Reads the default huge page size from `/proc/meminfo`.
+    fn read_hugepage_size() -> Option<usize> {
+        let meminfo: String = fs::read_to_string("/proc/meminfo").ok()?;
+        Self::parse_hugepage_size(&meminfo)
+    }
+
+    /// Parses the default huge page size out of the contents of `/proc/meminfo`.
+    fn parse_hugepage_size(meminfo: &str) -> Option<usize> {
+        let line: &str = meminfo.lines().find(|line| line.starts_with("Hugepagesize:"))?;
+        let mut fields = line["Hugepagesize:".len()..].split_whitespace();
+        let size: usize = fields.next()?.parse().ok()?;
+        match fields.next() {
+            Some("kB") => Some(size * 1024),
+            _ => None,
+        }
+    }
+}
+
+//==============================================================================
+// Unit Tests
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Capabilities,
+        OffloadTopology,
+        Topology,
+    };
+
+    #[test]
+    fn parse_hugepage_size() {
+        let meminfo: &str = "MemTotal:       16318276 kB\nHugePages_Total:    1024\nHugepagesize:       2048 kB\n";
+        assert_eq!(Topology::parse_hugepage_size(meminfo), Some(2 * 1024 * 1024));
+        assert_eq!(Topology::parse_hugepage_size("MemTotal:       16318276 kB\n"), None);
+    }
+
+    #[test]
+    fn check_capabilities() {
+        let supported: Capabilities = Capabilities {
+            max_rx_queues: 16,
+            max_tx_queues: 16,
+            max_rx_ring_size: 4096,
+            max_tx_ring_size: 4096,
+            max_tx_segments: 40,
+            max_mtu: 9000,
+            offloads: OffloadTopology {
+                tcp_rx_checksum: true,
+                tcp_tx_checksum: true,
+                tx_multi_segs: true,
+                ..Default::default()
+            },
+            jumbo_frames: true,
+            ..Default::default()
+        };
+        assert!(supported.check(&Capabilities::default()).is_ok());
+        assert!(supported.check(&supported).is_ok());
+
+        let required: Capabilities = Capabilities {
+            max_rx_ring_size: 8192,
+            ..Default::default()
+        };
+        assert!(supported.check(&required).is_err());
+        let required: Capabilities = Capabilities {
+            offloads: OffloadTopology {
+                udp_tx_checksum: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(supported.check(&required).is_err());
+        let required: Capabilities = Capabilities {
+            rx_timestamp: true,
+            ..Default::default()
+        };
+        assert!(supported.check(&required).is_err());
+    }
+}
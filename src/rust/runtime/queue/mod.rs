@@ -10,7 +10,9 @@ mod qtype;
 // Imports
 //======================================================================================================================
 
+use crate::runtime::fail::Fail;
 use ::slab::Slab;
+use ::std::any::Any;
 
 //======================================================================================================================
 // Exports
@@ -27,10 +29,17 @@ pub use self::{
 // Structures
 //======================================================================================================================
 
+/// I/O queue descriptors table entry.
+struct IoQueue {
+    // TODO: Store a QType here.
+    qtype: u32,
+    /// Context attached to the I/O queue by the application.
+    context: Option<Box<dyn Any>>,
+}
+
 /// I/O queue descriptors table.
 pub struct IoQueueTable {
-    // TODO: Store a QType in the slab.
-    table: Slab<u32>,
+    table: Slab<IoQueue>,
 }
 
 //======================================================================================================================
@@ -53,20 +62,50 @@ impl IoQueueTable {
 
     /// Allocates a new entry in the target I/O queue descriptors table.
     pub fn alloc(&mut self, qtype: u32) -> QDesc {
-        let idx: usize = self.table.insert(qtype);
+        let idx: usize = self.table.insert(IoQueue { qtype, context: None });
         QDesc::from(idx + Self::BASE_QD)
     }
 
     /// Gets the entry associated with an I/O queue descriptor.
     pub fn get(&self, qd: QDesc) -> Option<u32> {
         let idx: usize = self.get_index(qd)? as usize;
-        self.table.get(idx).cloned()
+        self.table.get(idx).map(|queue| queue.qtype)
     }
 
-    /// Releases the entry associated with an I/O queue descriptor.
+    /// Releases the entry associated with an I/O queue descriptor. The context attached to it, if any, is dropped.
     pub fn free(&mut self, qd: QDesc) -> Option<u32> {
         let idx: usize = self.get_index(qd)?;
-        Some(self.table.remove(idx))
+        Some(self.table.remove(idx).qtype)
+    }
+
+    /// Attaches an application context to an I/O queue descriptor, replacing the previous one.
+    pub fn set_context(&mut self, qd: QDesc, context: Box<dyn Any>) -> Result<(), Fail> {
+        let idx: usize = self.get_valid_index(qd)?;
+        self.table[idx].context = Some(context);
+        Ok(())
+    }
+
+    /// Gets the application context attached to an I/O queue descriptor.
+    pub fn get_context(&self, qd: QDesc) -> Result<Option<&dyn Any>, Fail> {
+        let idx: usize = self.get_valid_index(qd)?;
+        Ok(self.table[idx].context.as_deref())
+    }
+
+    /// Gets a mutable reference to the application context attached to an I/O queue descriptor.
+    pub fn get_context_mut(&mut self, qd: QDesc) -> Result<Option<&mut dyn Any>, Fail> {
+        let idx: usize = self.get_valid_index(qd)?;
+        Ok(self.table[idx].context.as_deref_mut())
+    }
+
+    /// Detaches the application context from an I/O queue descriptor and returns it.
+    pub fn take_context(&mut self, qd: QDesc) -> Result<Option<Box<dyn Any>>, Fail> {
+        let idx: usize = self.get_valid_index(qd)?;
+        Ok(self.table[idx].context.take())
+    }
+
+    /// Same as [Self::get_index], but fails with `EBADF` if the I/O queue descriptor is not valid.
+    fn get_valid_index(&self, qd: QDesc) -> Result<usize, Fail> {
+        self.get_index(qd).ok_or(Fail::new(libc::EBADF, "bad queue descriptor"))
     }
 
     /// Gets the index in the I/O queue descriptors table to which a given I/O queue descriptor refers to.
@@ -94,11 +133,39 @@ mod tests {
         QDesc,
         QType,
     };
+    use ::std::{
+        any::Any,
+        rc::Rc,
+    };
     use ::test::{
         black_box,
         Bencher,
     };
 
+    #[test]
+    fn context_lifecycle() {
+        let mut ioqueue_table: IoQueueTable = IoQueueTable::new();
+        let qd: QDesc = ioqueue_table.alloc(QType::TcpSocket.into());
+        assert!(ioqueue_table.get_context(qd).unwrap().is_none());
+
+        // Attach a context and update it in place.
+        let tracker: Rc<()> = Rc::new(());
+        ioqueue_table
+            .set_context(qd, Box::new((tracker.clone(), 1u64)))
+            .unwrap();
+        let context: &mut dyn Any = ioqueue_table.get_context_mut(qd).unwrap().unwrap();
+        context.downcast_mut::<(Rc<()>, u64)>().unwrap().1 += 1;
+        let context: &dyn Any = ioqueue_table.get_context(qd).unwrap().unwrap();
+        assert_eq!(context.downcast_ref::<(Rc<()>, u64)>().unwrap().1, 2);
+        assert_eq!(Rc::strong_count(&tracker), 2);
+
+        // The context is dropped along with the I/O queue descriptor.
+        ioqueue_table.free(qd);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+        assert!(ioqueue_table.get_context(qd).is_err());
+        assert!(ioqueue_table.set_context(qd, Box::new(())).is_err());
+    }
+
     #[bench]
     fn bench_alloc_free(b: &mut Bencher) {
         let mut ioqueue_table: IoQueueTable = IoQueueTable::new();
@@ -21,6 +21,9 @@ pub trait SchedulerFuture: Any + Future<Output = ()> + Unpin {
     /// Casts the target [SchedulerFuture] into [Any].
     fn as_any(self: Box<Self>) -> Box<dyn Any>;
 
+    /// Casts a reference to the target [SchedulerFuture] into a reference to [Any].
+    fn as_any_ref(&self) -> &dyn Any;
+
     /// Gets the underlying future in the target [SchedulerFuture].
     fn get_future(&self) -> &dyn Future<Output = ()>;
 }
@@ -43,6 +43,11 @@ impl SchedulerHandle {
         self.key.take()
     }
 
+    /// Returns the key stored in the target [SchedulerHandle], if any.
+    pub fn get_key(&self) -> Option<u64> {
+        self.key
+    }
+
     /// Queries whether or not the future associated with the target [SchedulerHandle] has complemented.
     pub fn has_completed(&self) -> bool {
         let subpage_ix: usize = self.key.unwrap() as usize & (WAKER_BIT_LENGTH - 1);
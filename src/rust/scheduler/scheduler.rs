@@ -11,27 +11,35 @@
 // Imports
 //==============================================================================
 
-use crate::scheduler::{
-    page::{
-        WakerPageRef,
-        WakerRef,
+use crate::{
+    runtime::{
+        fail::Fail,
+        QToken,
     },
-    pin_slab::PinSlab,
-    waker64::{
-        WAKER_BIT_LENGTH,
-        WAKER_BIT_LENGTH_SHIFT,
+    scheduler::{
+        page::{
+            WakerPageRef,
+            WakerRef,
+        },
+        pin_slab::PinSlab,
+        waker64::{
+            WAKER_BIT_LENGTH,
+            WAKER_BIT_LENGTH_SHIFT,
+        },
+        SchedulerFuture,
+        SchedulerHandle,
     },
-    SchedulerFuture,
-    SchedulerHandle,
 };
 use ::bit_iter::BitIter;
 use ::std::{
+    any::Any,
     cell::{
         Ref,
         RefCell,
         RefMut,
     },
     future::Future,
+    mem,
     pin::Pin,
     ptr::NonNull,
     rc::Rc,
@@ -40,6 +48,7 @@ use ::std::{
         Poll,
         Waker,
     },
+    time::SystemTime,
 };
 
 //==============================================================================
@@ -99,6 +108,13 @@ impl Scheduler {
         inner.slab.remove_unpin(key as usize).unwrap()
     }
 
+    /// Given a handle representing a future, inspects the future without removing it from the scheduler.
+    pub fn inspect<R, F: FnOnce(&dyn Any) -> R>(&self, handle: &SchedulerHandle, f: F) -> R {
+        let inner: Ref<Inner<Box<dyn SchedulerFuture>>> = self.inner.borrow();
+        let key: u64 = handle.get_key().unwrap();
+        f(inner.slab.get(key as usize).unwrap().as_any_ref())
+    }
+
     /// Given the raw `key` representing this future return a proper handle.
     pub fn from_raw_handle(&self, key: u64) -> Option<SchedulerHandle> {
         let inner: Ref<Inner<Box<dyn SchedulerFuture>>> = self.inner.borrow();
@@ -116,6 +132,82 @@ impl Scheduler {
         Some(SchedulerHandle::new(key, page.clone()))
     }
 
+    /// Waits for up to `max_results` of the operations in `qts` to complete and takes all of them at once, along with
+    /// their offset in `qts`. `poll` is invoked on `state` to make progress, and `take` to extract the result of each
+    /// completed operation. If `abstime` is set and no operation completes before it, ETIMEDOUT is returned. If `qts`
+    /// is empty, `abstime` must be set, or EINVAL is returned. Operations that are still pending are left untouched.
+    pub fn wait_many<S, T, P: FnMut(&mut S), F: FnMut(&mut S, SchedulerHandle) -> T>(
+        &self,
+        state: &mut S,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+        mut poll: P,
+        mut take: F,
+    ) -> Result<Vec<(usize, T)>, Fail> {
+        if max_results == 0 {
+            return Err(Fail::new(libc::EINVAL, "invalid number of results"));
+        }
+        // Nothing would ever complete, so this would wait forever.
+        if qts.is_empty() && abstime.is_none() {
+            return Err(Fail::new(libc::EINVAL, "no operations to wait for"));
+        }
+
+        loop {
+            // Poll first, so as to give pending operations a chance to complete.
+            poll(state);
+
+            // Search for operations that have completed.
+            let mut ready: Vec<usize> = Vec::new();
+            for (i, &qt) in qts.iter().enumerate() {
+                // Retrieve associated schedule handle.
+                let mut handle: SchedulerHandle = match self.from_raw_handle(qt.into()) {
+                    Some(handle) => handle,
+                    None => return Err(Fail::new(libc::EINVAL, "invalid queue token")),
+                };
+
+                if handle.has_completed() && ready.len() < max_results {
+                    ready.push(i);
+                }
+
+                // Return this operation to the scheduling queue by removing the associated key
+                // (which would otherwise cause the operation to be freed).
+                handle.take_key();
+            }
+
+            // Found some, so extract their results and return.
+            if !ready.is_empty() {
+                let mut results: Vec<(usize, T)> = Vec::with_capacity(ready.len());
+                for i in ready {
+                    // Skip queue tokens that show up more than once in the list.
+                    if let Some(handle) = self.from_raw_handle(qts[i].into()) {
+                        results.push((i, take(state, handle)));
+                    }
+                }
+                return Ok(results);
+            }
+
+            if let Some(abstime) = abstime {
+                if SystemTime::now() >= abstime {
+                    return Err(Fail::new(libc::ETIMEDOUT, "timer expired"));
+                }
+            }
+        }
+    }
+
+    /// Removes and drops all futures in the scheduler. This breaks reference cycles between background tasks and the
+    /// state that they share with their owners, so that the state is released. Handles to removed futures become
+    /// invalid.
+    pub fn clear(&self) {
+        let (slab, pages): (PinSlab<Box<dyn SchedulerFuture>>, Vec<WakerPageRef>) = {
+            let mut inner: RefMut<Inner<Box<dyn SchedulerFuture>>> = self.inner.borrow_mut();
+            (mem::take(&mut inner.slab), mem::take(&mut inner.pages))
+        };
+        // Futures may drop handles to other futures, so drop them without holding a borrow on the scheduler.
+        drop(slab);
+        drop(pages);
+    }
+
     /// Poll all futures which are ready to run again. Tasks in our scheduler are notified when
     /// relevant data or events happen. The relevant event have callback function (the waker) which
     /// they can invoke to notify the scheduler that future should be polled again.
@@ -194,10 +286,16 @@ impl Default for Scheduler {
 
 #[cfg(test)]
 mod tests {
-    use crate::scheduler::scheduler::{
-        Scheduler,
-        SchedulerFuture,
-        SchedulerHandle,
+    use crate::{
+        runtime::{
+            fail::Fail,
+            QToken,
+        },
+        scheduler::scheduler::{
+            Scheduler,
+            SchedulerFuture,
+            SchedulerHandle,
+        },
     };
     use ::std::{
         any::Any,
@@ -208,6 +306,7 @@ mod tests {
             Poll,
             Waker,
         },
+        time::SystemTime,
     };
     use ::test::{
         black_box,
@@ -247,6 +346,10 @@ mod tests {
             self
         }
 
+        fn as_any_ref(&self) -> &dyn Any {
+            self
+        }
+
         fn get_future(&self) -> &dyn Future<Output = ()> {
             todo!()
         }
@@ -306,6 +409,82 @@ mod tests {
         assert_eq!(handle.has_completed(), true);
     }
 
+    /// Inserts one future for each value in the scheduler, returning their queue tokens.
+    fn insert_all(scheduler: &Scheduler, vals: &[usize]) -> Vec<QToken> {
+        vals.iter()
+            .map(|val| match scheduler.insert(DummyFuture::new(*val)) {
+                Some(handle) => QToken::from(handle.into_raw()),
+                None => panic!("insert() failed"),
+            })
+            .collect()
+    }
+
+    /// Waits for futures to complete, returning the offsets of those that did.
+    fn wait_many(
+        scheduler: &Scheduler,
+        qts: &[QToken],
+        max_results: usize,
+        abstime: Option<SystemTime>,
+    ) -> Result<Vec<usize>, Fail> {
+        let results: Vec<(usize, Box<dyn SchedulerFuture>)> = scheduler.wait_many(
+            &mut (),
+            qts,
+            max_results,
+            abstime,
+            |_| scheduler.poll(),
+            |_, handle| scheduler.take(handle),
+        )?;
+        Ok(results.into_iter().map(|(i, _)| i).collect())
+    }
+
+    #[test]
+    fn wait_many_several_completions() {
+        let scheduler: Scheduler = Scheduler::default();
+
+        // The first and last futures complete with a single poll, the one in the middle needs two polls.
+        let qts: Vec<QToken> = insert_all(&scheduler, &[0, 1, 2]);
+        assert_eq!(wait_many(&scheduler, &qts, 3, None).unwrap(), [0, 2]);
+        assert_eq!(wait_many(&scheduler, &qts[1..2], 3, None).unwrap(), [0]);
+    }
+
+    #[test]
+    fn wait_many_max_results() {
+        let scheduler: Scheduler = Scheduler::default();
+
+        // All futures complete with a single poll, but only two of them are taken.
+        let qts: Vec<QToken> = insert_all(&scheduler, &[0, 0, 0]);
+        assert_eq!(wait_many(&scheduler, &qts, 2, None).unwrap(), [0, 1]);
+        assert_eq!(wait_many(&scheduler, &qts[2..], 2, None).unwrap(), [0]);
+
+        let e: Fail = wait_many(&scheduler, &qts, 0, None).unwrap_err();
+        assert_eq!(e.errno, libc::EINVAL);
+    }
+
+    #[test]
+    fn wait_many_timeout() {
+        let scheduler: Scheduler = Scheduler::default();
+
+        // The future needs two polls, so it has not completed when the deadline is checked.
+        let qts: Vec<QToken> = insert_all(&scheduler, &[1]);
+        let e: Fail = wait_many(&scheduler, &qts, 1, Some(SystemTime::now())).unwrap_err();
+        assert_eq!(e.errno, libc::ETIMEDOUT);
+
+        // The future was left in the scheduler.
+        assert_eq!(wait_many(&scheduler, &qts, 1, None).unwrap(), [0]);
+    }
+
+    #[test]
+    fn wait_many_nothing() {
+        let scheduler: Scheduler = Scheduler::default();
+
+        // Without a deadline, this would wait forever.
+        let e: Fail = wait_many(&scheduler, &[], 1, None).unwrap_err();
+        assert_eq!(e.errno, libc::EINVAL);
+
+        let e: Fail = wait_many(&scheduler, &[], 1, Some(SystemTime::now())).unwrap_err();
+        assert_eq!(e.errno, libc::ETIMEDOUT);
+    }
+
     #[bench]
     fn bench_scheduler_poll(b: &mut Bencher) {
         let scheduler: Scheduler = Scheduler::default();
@@ -123,6 +123,45 @@ static bool inval_pop(void)
     return (demi_pop(qt, qd) != 0);
 }
 
+/**
+ * @brief Issues an invalid call to demi_peek().
+ */
+static bool inval_peek(void)
+{
+    demi_qtoken_t *qt = NULL;
+    int qd = -1;
+
+    return (demi_peek(qt, qd) != 0);
+}
+
+/**
+ * @brief Issues an invalid call to demi_setsockopt().
+ */
+static bool inval_setsockopt(void)
+{
+    int qd = -1;
+    int level = -1;
+    int optname = -1;
+    const void *optval = NULL;
+    socklen_t optlen = 0;
+
+    return (demi_setsockopt(qd, level, optname, optval, optlen) != 0);
+}
+
+/**
+ * @brief Issues an invalid call to demi_getsockopt().
+ */
+static bool inval_getsockopt(void)
+{
+    int qd = -1;
+    int level = -1;
+    int optname = -1;
+    void *optval = NULL;
+    socklen_t *optlen = NULL;
+
+    return (demi_getsockopt(qd, level, optname, optval, optlen) != 0);
+}
+
 /*===================================================================================================================*
  * System Calls in demi/sga.h                                                                                        *
  *===================================================================================================================*/
@@ -164,6 +203,16 @@ static bool inval_timedwait(void)
     return (demi_timedwait(qr, qt, &abstime) != 0);
 }
 
+/**
+ * @brief Issues an invalid system call to demi_cancel().
+ */
+static bool inval_cancel(void)
+{
+    demi_qtoken_t qt = -1;
+
+    return (demi_cancel(qt) != 0);
+}
+
 /**
  * @brief Issues an invalid system call to demi_wait().
  */
@@ -188,6 +237,21 @@ static bool inval_wait_any(void)
     return (demi_wait_any(qr, ready_offset, qts, num_qts) != 0);
 }
 
+/**
+ * @brief Issues an invalid system call to demi_wait_many().
+ */
+static bool inval_wait_many(void)
+{
+    demi_qresult_t *qrs = NULL;
+    int *ready_offsets = NULL;
+    int *num_ready = NULL;
+    int max_results = 0;
+    demi_qtoken_t *qts = NULL;
+    int num_qts = -1;
+
+    return (demi_wait_many(qrs, ready_offsets, num_ready, max_results, qts, num_qts, NULL) != 0);
+}
+
 /*===================================================================================================================*
  * main()                                                                                                            *
  *===================================================================================================================*/
@@ -208,7 +272,10 @@ static struct test tests_libos[] = {{inval_socket, "invalid demi_socket()"},   {
                                     {inval_bind, "invalid demi_bind()"},       {inval_close, "invalid_demi_close()"},
                                     {inval_connect, "invalid demi_connect()"}, {inval_listen, "invalid demi_listen()"},
                                     {inval_pop, "invalid demi_pop()"},         {inval_push, "invalid demi_push()"},
-                                    {inval_pushto, "invalid demi_pushto()"}};
+                                    {inval_pushto, "invalid demi_pushto()"},
+                                    {inval_peek, "invalid demi_peek()"},
+                                    {inval_setsockopt, "invalid demi_setsockopt()"},
+                                    {inval_getsockopt, "invalid demi_getsockopt()"}};
 
 /**
  * @brief Tests for system calls in demi/sga.h
@@ -220,8 +287,10 @@ static struct test tests_sga[] = {{inval_sgaalloc, "invalid demi_sgaalloc()"},
  * @brief Tests for system calls in demi/wait.h
  */
 static struct test tests_wait[] = {{inval_timedwait, "invalid demi_timedwait()"},
+                                   {inval_cancel, "invalid demi_cancel()"},
                                    {inval_wait, "invalid demi_wait()"},
-                                   {inval_wait_any, "invalid demi_wait_any()"}};
+                                   {inval_wait_any, "invalid demi_wait_any()"},
+                                   {inval_wait_many, "invalid demi_wait_many()"}};
 
 /**
  * @brief Drives the application.
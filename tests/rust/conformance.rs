@@ -0,0 +1,485 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Datapath conformance suite.
+//!
+//! Runs a matrix of operations against whichever LibOS is selected through the `LIBOS` environment variable, using
+//! the configuration file pointed to by `CONFIG_PATH`. Both ends of each connection live in the same LibOS instance
+//! and talk to each other through the address given in `CONFORMANCE_IPV4` (defaults to the loopback address), so that
+//! a single process is enough to exercise a backend.
+//!
+//! All cases share a single LibOS instance, because some backends can only be initialized once per process. Cases
+//! that panic, for instance by hitting an `unimplemented!()` stub, are reported as failures. Cases that fail with
+//! `ENOTSUP` are reported as unsupported and do not fail the suite.
+
+//======================================================================================================================
+// Imports
+//======================================================================================================================
+
+use ::demikernel::{
+    demi_sgarray_t,
+    runtime::{
+        fail::Fail,
+        memory::{
+            stats::{
+                MempoolId,
+                MempoolStats,
+            },
+            Buffer,
+            DataBuffer,
+        },
+        network::{
+            preamble::RequestPreamble,
+            stats::TxStats,
+            topology::{
+                MempoolTopology,
+                Topology,
+            },
+        },
+        types::{
+            demi_opcode_t,
+            demi_qresult_t,
+        },
+    },
+    LibOS,
+    LibOSName,
+    NetworkTransport,
+    QDesc,
+    QToken,
+};
+use ::std::{
+    env,
+    net::{
+        Ipv4Addr,
+        SocketAddrV4,
+    },
+    panic::{
+        self,
+        AssertUnwindSafe,
+    },
+    slice,
+    str::FromStr,
+};
+
+//======================================================================================================================
+// Constants
+//======================================================================================================================
+
+/// Base port number used by conformance cases. Each case uses its own port.
+const PORT_BASE: u16 = 22000;
+
+/// Size of buffers that are pushed around.
+const BUFFER_SIZE: usize = 1024;
+
+//======================================================================================================================
+// Structures
+//======================================================================================================================
+
+/// Conformance Case
+type Case = fn(&mut LibOS, Ipv4Addr, u16) -> Result<(), Fail>;
+
+/// Outcome of a Conformance Case
+enum Outcome {
+    Passed,
+    Unsupported(Fail),
+    Failed(String),
+}
+
+//======================================================================================================================
+// Helper Functions
+//======================================================================================================================
+
+/// Allocates a scatter-gather array and fills it with `value`.
+fn mksga(libos: &mut LibOS, size: usize, value: u8) -> Result<demi_sgarray_t, Fail> {
+    let sga: demi_sgarray_t = libos.sgaalloc(size)?;
+    if sga.sga_segs[0].sgaseg_len as usize != size {
+        libos.sgafree(sga)?;
+        return Err(Fail::new(libc::EIO, "scatter-gather array has the wrong size"));
+    }
+    let ptr: *mut u8 = sga.sga_segs[0].sgaseg_buf as *mut u8;
+    let slice: &mut [u8] = unsafe { slice::from_raw_parts_mut(ptr, size) };
+    slice.fill(value);
+    Ok(sga)
+}
+
+/// Waits for an operation and checks that it completed with the expected opcode.
+fn wait_for(libos: &mut LibOS, qt: QToken, opcode: demi_opcode_t) -> Result<demi_qresult_t, Fail> {
+    let qr: demi_qresult_t = libos.wait(qt)?;
+    if qr.qr_opcode != opcode {
+        return Err(Fail::new(libc::EIO, "operation completed with an unexpected opcode"));
+    }
+    Ok(qr)
+}
+
+/// Opens a TCP connection between two sockets of the same LibOS. The listening socket, the accepted socket, and the
+/// connected socket are returned, in this order.
+fn open_connection(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(QDesc, QDesc, QDesc), Fail> {
+    let local: SocketAddrV4 = SocketAddrV4::new(ipv4, port);
+
+    let listen_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0)?;
+    libos.bind(listen_qd, local)?;
+    libos.listen(listen_qd, 8)?;
+    let accept_qt: QToken = libos.accept(listen_qd)?;
+
+    let client_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0)?;
+    let connect_qt: QToken = libos.connect(client_qd, local)?;
+
+    let qr: demi_qresult_t = wait_for(libos, accept_qt, demi_opcode_t::DEMI_OPC_ACCEPT)?;
+    let server_qd: QDesc = unsafe { qr.qr_value.ares.qd.into() };
+    wait_for(libos, connect_qt, demi_opcode_t::DEMI_OPC_CONNECT)?;
+
+    Ok((listen_qd, server_qd, client_qd))
+}
+
+/// Pops from a socket until `len` bytes have been received, checking that every byte equals `value`.
+fn pop_exact(libos: &mut LibOS, qd: QDesc, len: usize, value: u8) -> Result<(), Fail> {
+    let mut received: usize = 0;
+    while received < len {
+        let qt: QToken = libos.pop(qd)?;
+        let qr: demi_qresult_t = wait_for(libos, qt, demi_opcode_t::DEMI_OPC_POP)?;
+        let sga: demi_sgarray_t = unsafe { qr.qr_value.sga };
+        let ptr: *const u8 = sga.sga_segs[0].sgaseg_buf as *const u8;
+        let nbytes: usize = sga.sga_segs[0].sgaseg_len as usize;
+        let data: &[u8] = unsafe { slice::from_raw_parts(ptr, nbytes) };
+        let matches: bool = data.iter().all(|&b| b == value);
+        libos.sgafree(sga)?;
+        if nbytes == 0 {
+            return Err(Fail::new(libc::ECONNRESET, "connection closed early"));
+        }
+        if !matches {
+            return Err(Fail::new(libc::EIO, "received corrupted data"));
+        }
+        received += nbytes;
+    }
+    if received != len {
+        return Err(Fail::new(libc::EIO, "received more data than expected"));
+    }
+    Ok(())
+}
+
+/// Pops from a socket until `len` bytes have been received, and returns them.
+fn pop_bytes(libos: &mut LibOS, qd: QDesc, len: usize) -> Result<Vec<u8>, Fail> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(len);
+    while bytes.len() < len {
+        let qt: QToken = libos.pop(qd)?;
+        let qr: demi_qresult_t = wait_for(libos, qt, demi_opcode_t::DEMI_OPC_POP)?;
+        let sga: demi_sgarray_t = unsafe { qr.qr_value.sga };
+        let ptr: *const u8 = sga.sga_segs[0].sgaseg_buf as *const u8;
+        let nbytes: usize = sga.sga_segs[0].sgaseg_len as usize;
+        bytes.extend_from_slice(unsafe { slice::from_raw_parts(ptr, nbytes) });
+        libos.sgafree(sga)?;
+        if nbytes == 0 {
+            return Err(Fail::new(libc::ECONNRESET, "connection closed early"));
+        }
+    }
+    if bytes.len() != len {
+        return Err(Fail::new(libc::EIO, "received more data than expected"));
+    }
+    Ok(bytes)
+}
+
+//======================================================================================================================
+// Conformance Cases
+//======================================================================================================================
+
+/// Allocates and releases scatter-gather arrays of several sizes.
+fn sga_alloc_free(libos: &mut LibOS, _: Ipv4Addr, _: u16) -> Result<(), Fail> {
+    for size in [1, 64, BUFFER_SIZE] {
+        let sga: demi_sgarray_t = mksga(libos, size, 0xab)?;
+        libos.sgafree(sga)?;
+    }
+    Ok(())
+}
+
+/// Opens and closes TCP and UDP sockets.
+fn socket_close(libos: &mut LibOS, _: Ipv4Addr, _: u16) -> Result<(), Fail> {
+    let tcp_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0)?;
+    let udp_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_DGRAM, 0)?;
+    libos.close(tcp_qd)?;
+    libos.close(udp_qd)?;
+    Ok(())
+}
+
+/// Binds, listens on, and closes a TCP socket.
+fn bind_listen_close(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_STREAM, 0)?;
+    libos.bind(qd, SocketAddrV4::new(ipv4, port))?;
+    libos.listen(qd, 8)?;
+    libos.close(qd)?;
+    Ok(())
+}
+
+/// Checks that operations on invalid queue descriptors and tokens are rejected.
+fn invalid_arguments(libos: &mut LibOS, _: Ipv4Addr, _: u16) -> Result<(), Fail> {
+    let bad_qd: QDesc = QDesc::from(i32::MAX);
+    if libos.close(bad_qd).is_ok() {
+        return Err(Fail::new(libc::EIO, "close() succeeded on an invalid queue descriptor"));
+    }
+    if libos.pop(bad_qd).is_ok() {
+        return Err(Fail::new(libc::EIO, "pop() succeeded on an invalid queue descriptor"));
+    }
+    let bad_qt: QToken = QToken::from(u64::MAX);
+    if libos.wait(bad_qt).is_ok() {
+        return Err(Fail::new(libc::EIO, "wait() succeeded on an invalid queue token"));
+    }
+    Ok(())
+}
+
+/// Establishes and tears down a TCP connection.
+fn tcp_connect_accept(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(libos, ipv4, port)?;
+    libos.close(client_qd)?;
+    libos.close(server_qd)?;
+    libos.close(listen_qd)?;
+    Ok(())
+}
+
+/// Pushes data in both directions of a TCP connection.
+fn tcp_push_pop(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(libos, ipv4, port)?;
+
+    for (i, (from, to)) in [(client_qd, server_qd), (server_qd, client_qd)].iter().enumerate() {
+        let value: u8 = i as u8 + 1;
+        let sga: demi_sgarray_t = mksga(libos, BUFFER_SIZE, value)?;
+        let qt: QToken = libos.push(*from, &sga)?;
+        wait_for(libos, qt, demi_opcode_t::DEMI_OPC_PUSH)?;
+        libos.sgafree(sga)?;
+        pop_exact(libos, *to, BUFFER_SIZE, value)?;
+    }
+
+    libos.close(client_qd)?;
+    libos.close(server_qd)?;
+    libos.close(listen_qd)?;
+    Ok(())
+}
+
+/// Pushes raw data to a TCP connection.
+fn tcp_push2_pop(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(libos, ipv4, port)?;
+
+    let data: Vec<u8> = vec![0x5a; BUFFER_SIZE];
+    let qt: QToken = libos.push2(client_qd, &data)?;
+    wait_for(libos, qt, demi_opcode_t::DEMI_OPC_PUSH)?;
+    pop_exact(libos, server_qd, BUFFER_SIZE, 0x5a)?;
+
+    libos.close(client_qd)?;
+    libos.close(server_qd)?;
+    libos.close(listen_qd)?;
+    Ok(())
+}
+
+/// Pushes raw data prefixed with a request preamble to a TCP connection.
+fn tcp_push_slice_pop(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(libos, ipv4, port)?;
+
+    let data: Vec<u8> = vec![0x7e; BUFFER_SIZE];
+    let preamble: RequestPreamble = RequestPreamble::new(42, 1, 7);
+    let qt: QToken = libos.push_slice(client_qd, Some(&preamble), &data)?;
+    wait_for(libos, qt, demi_opcode_t::DEMI_OPC_PUSH)?;
+    let bytes: Vec<u8> = pop_bytes(libos, server_qd, preamble.size() + BUFFER_SIZE)?;
+    let (received, body): (RequestPreamble, Buffer) =
+        RequestPreamble::parse(Buffer::Heap(DataBuffer::from_slice(&bytes)))?;
+    if (received.timestamp(), received.flow_id(), received.sequence()) != (42, 1, 7) {
+        return Err(Fail::new(libc::EIO, "received corrupted preamble"));
+    }
+    if body[..] != data[..] {
+        return Err(Fail::new(libc::EIO, "received corrupted data"));
+    }
+
+    libos.close(client_qd)?;
+    libos.close(server_qd)?;
+    libos.close(listen_qd)?;
+    Ok(())
+}
+
+/// Broadcasts a scatter-gather array through the LibOS-agnostic transport interface.
+fn transport_push_broadcast(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(libos, ipv4, port)?;
+
+    let transport: &mut dyn NetworkTransport = libos;
+    if !transport.supports_sga() {
+        return Err(Fail::new(libc::ENOTSUP, "scatter-gather arrays not supported"));
+    }
+    let sga: demi_sgarray_t = transport.sgaalloc(BUFFER_SIZE)?;
+    let ptr: *mut u8 = sga.sga_segs[0].sgaseg_buf as *mut u8;
+    unsafe { slice::from_raw_parts_mut(ptr, BUFFER_SIZE) }.fill(0x6b);
+    let results: Vec<Result<QToken, Fail>> = transport.push_broadcast(&[client_qd, server_qd], &sga)?;
+    transport.sgafree(sga)?;
+    for result in results {
+        let qr: demi_qresult_t = transport.wait(result?)?;
+        if qr.qr_opcode != demi_opcode_t::DEMI_OPC_PUSH {
+            return Err(Fail::new(libc::EIO, "broadcast push completed with the wrong opcode"));
+        }
+    }
+
+    pop_exact(libos, server_qd, BUFFER_SIZE, 0x6b)?;
+    pop_exact(libos, client_qd, BUFFER_SIZE, 0x6b)?;
+
+    libos.close(client_qd)?;
+    libos.close(server_qd)?;
+    libos.close(listen_qd)?;
+    Ok(())
+}
+
+/// Checks that transmit statistics account for pushed data, and that they can be reset.
+fn tx_stats(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let (listen_qd, server_qd, client_qd): (QDesc, QDesc, QDesc) = open_connection(libos, ipv4, port)?;
+    libos.reset_tx_stats()?;
+
+    let sga: demi_sgarray_t = mksga(libos, BUFFER_SIZE, 0x42)?;
+    let qt: QToken = libos.push(client_qd, &sga)?;
+    wait_for(libos, qt, demi_opcode_t::DEMI_OPC_PUSH)?;
+    libos.sgafree(sga)?;
+    pop_exact(libos, server_qd, BUFFER_SIZE, 0x42)?;
+
+    let stats: TxStats = libos.tx_stats()?;
+    if stats.packets == 0 || stats.zero_copy_bytes + stats.copied_bytes() < BUFFER_SIZE as u64 {
+        return Err(Fail::new(
+            libc::EIO,
+            "transmit statistics do not account for pushed data",
+        ));
+    }
+    libos.reset_tx_stats()?;
+    if libos.tx_stats()? != TxStats::default() {
+        return Err(Fail::new(libc::EIO, "transmit statistics were not reset"));
+    }
+
+    libos.close(client_qd)?;
+    libos.close(server_qd)?;
+    libos.close(listen_qd)?;
+    Ok(())
+}
+
+/// Checks that memory pool statistics account for a scatter-gather array that is allocated and then released.
+fn pool_stats(libos: &mut LibOS, _ipv4: Ipv4Addr, _port: u16) -> Result<(), Fail> {
+    let before: MempoolStats = libos.pool_stats(MempoolId::Body)?;
+
+    // Header buffers are sized to hold an inline body, so anything that large is placed in the body pool.
+    let topology: Topology = libos.topology();
+    let pool_size = |name: &str| -> Option<usize> {
+        topology
+            .mempools
+            .iter()
+            .find(|pool: &&MempoolTopology| pool.name == name)
+            .map(|pool: &MempoolTopology| pool.buffer_size)
+    };
+    let size: usize = match (pool_size("header_pool"), pool_size("body_pool")) {
+        (Some(header_size), Some(body_size)) if header_size < body_size => header_size,
+        _ => return Err(Fail::new(libc::EIO, "topology does not describe the memory pools")),
+    };
+
+    let sga: demi_sgarray_t = mksga(libos, size, 0x5a)?;
+    let during: MempoolStats = libos.pool_stats(MempoolId::Body)?;
+    libos.sgafree(sga)?;
+    let after: MempoolStats = libos.pool_stats(MempoolId::Body)?;
+
+    if during.allocated != before.allocated + 1 || during.free + 1 != before.free {
+        return Err(Fail::new(
+            libc::EIO,
+            "memory pool statistics do not account for an allocation",
+        ));
+    }
+    if after != before {
+        return Err(Fail::new(
+            libc::EIO,
+            "memory pool statistics do not account for a release",
+        ));
+    }
+    Ok(())
+}
+
+/// Sends a datagram between two UDP sockets.
+fn udp_pushto_pop(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let local: SocketAddrV4 = SocketAddrV4::new(ipv4, port);
+    let remote: SocketAddrV4 = SocketAddrV4::new(ipv4, port + 1);
+
+    let sender_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_DGRAM, 0)?;
+    libos.bind(sender_qd, local)?;
+    let receiver_qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_DGRAM, 0)?;
+    libos.bind(receiver_qd, remote)?;
+
+    let sga: demi_sgarray_t = mksga(libos, BUFFER_SIZE, 0x3c)?;
+    let qt: QToken = libos.pushto(sender_qd, &sga, remote)?;
+    wait_for(libos, qt, demi_opcode_t::DEMI_OPC_PUSH)?;
+    libos.sgafree(sga)?;
+    pop_exact(libos, receiver_qd, BUFFER_SIZE, 0x3c)?;
+
+    libos.close(sender_qd)?;
+    libos.close(receiver_qd)?;
+    Ok(())
+}
+
+/// Cancels a pop operation that cannot complete.
+fn cancel_pop(libos: &mut LibOS, ipv4: Ipv4Addr, port: u16) -> Result<(), Fail> {
+    let qd: QDesc = libos.socket(libc::AF_INET, libc::SOCK_DGRAM, 0)?;
+    libos.bind(qd, SocketAddrV4::new(ipv4, port))?;
+    let qt: QToken = libos.pop(qd)?;
+    libos.cancel(qt)?;
+    if libos.wait(qt).is_ok() {
+        return Err(Fail::new(libc::EIO, "wait() succeeded on a cancelled operation"));
+    }
+    libos.close(qd)?;
+    Ok(())
+}
+
+//======================================================================================================================
+// Test Driver
+//======================================================================================================================
+
+/// Runs a single conformance case.
+fn run(libos: &mut LibOS, case: Case, ipv4: Ipv4Addr, port: u16) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| case(libos, ipv4, port))) {
+        Ok(Ok(())) => Outcome::Passed,
+        Ok(Err(e)) if e.errno == libc::ENOTSUP => Outcome::Unsupported(e),
+        Ok(Err(e)) => Outcome::Failed(format!("{:?}", e)),
+        Err(_) => Outcome::Failed("panicked".to_string()),
+    }
+}
+
+/// Runs all conformance cases against the LibOS that is selected in the environment.
+#[test]
+fn conformance() {
+    let libos_name: LibOSName = match LibOSName::from_env() {
+        Ok(libos_name) => libos_name.into(),
+        Err(e) => panic!("{:?}", e),
+    };
+    let mut libos: LibOS = match LibOS::new(libos_name) {
+        Ok(libos) => libos,
+        Err(e) => panic!("failed to initialize libos: {:?}", e.cause),
+    };
+    let ipv4: Ipv4Addr = match env::var("CONFORMANCE_IPV4") {
+        Ok(addr) => Ipv4Addr::from_str(&addr).expect("invalid value for CONFORMANCE_IPV4"),
+        Err(_) => Ipv4Addr::LOCALHOST,
+    };
+
+    let cases: [(&str, Case); 13] = [
+        ("sga_alloc_free", sga_alloc_free),
+        ("socket_close", socket_close),
+        ("bind_listen_close", bind_listen_close),
+        ("invalid_arguments", invalid_arguments),
+        ("tcp_connect_accept", tcp_connect_accept),
+        ("tcp_push_pop", tcp_push_pop),
+        ("tcp_push2_pop", tcp_push2_pop),
+        ("tcp_push_slice_pop", tcp_push_slice_pop),
+        ("transport_push_broadcast", transport_push_broadcast),
+        ("tx_stats", tx_stats),
+        ("pool_stats", pool_stats),
+        ("udp_pushto_pop", udp_pushto_pop),
+        ("cancel_pop", cancel_pop),
+    ];
+
+    let mut failed: Vec<&str> = Vec::new();
+    for (i, (name, case)) in cases.iter().enumerate() {
+        let port: u16 = PORT_BASE + 2 * i as u16;
+        match run(&mut libos, *case, ipv4, port) {
+            Outcome::Passed => println!("{:<24} ok", name),
+            Outcome::Unsupported(e) => println!("{:<24} unsupported ({:?})", name, e.cause),
+            Outcome::Failed(cause) => {
+                println!("{:<24} FAILED ({})", name, cause);
+                failed.push(name);
+            },
+        }
+    }
+
+    assert!(failed.is_empty(), "conformance cases failed: {:?}", failed);
+}
@@ -46,6 +46,7 @@ use std::{
         self,
         JoinHandle,
     },
+    time::SystemTime,
 };
 
 //======================================================================================================================
@@ -271,6 +272,124 @@ fn tcp_push_remote() {
     bob.join().unwrap();
 }
 
+/// Tests if several buffers can be pushed with a single operation.
+#[test]
+fn tcp_push_batch() {
+    let (alice_tx, alice_rx): (Sender<DataBuffer>, Receiver<DataBuffer>) = crossbeam_channel::unbounded();
+    let (bob_tx, bob_rx): (Sender<DataBuffer>, Receiver<DataBuffer>) = crossbeam_channel::unbounded();
+
+    let alice: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, alice_tx, bob_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        safe_bind(&mut libos, sockqd, local);
+        safe_listen(&mut libos, sockqd);
+        let qt: QToken = safe_accept(&mut libos, sockqd);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        let qd: QDesc = match qr {
+            OperationResult::Accept(qd) => qd,
+            _ => panic!("accept() has failed"),
+        };
+
+        // Pop data until all buffers have arrived.
+        let mut nbytes: usize = 0;
+        while nbytes < 3 * 32 {
+            let qt: QToken = safe_pop(&mut libos, qd);
+            let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+            match qr {
+                OperationResult::Pop(_, buf) => nbytes += buf.len(),
+                _ => panic!("pop() has has failed {:?}", qr),
+            }
+        }
+        assert_eq!(nbytes, 3 * 32);
+
+        // Close connection.
+        safe_close_active(&mut libos, qd);
+        safe_close_passive(&mut libos, sockqd);
+    });
+
+    let bob: JoinHandle<()> = thread::spawn(move || {
+        let mut libos: InetStack = DummyLibOS::new(BOB_MAC, BOB_IPV4, bob_tx, alice_rx, arp());
+
+        let port: u16 = PORT_BASE;
+        let remote: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+
+        // Open connection.
+        let sockqd: QDesc = safe_socket(&mut libos);
+        let qt: QToken = safe_connect(&mut libos, sockqd, remote);
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Connect => (),
+            _ => panic!("connect() has failed"),
+        }
+
+        // Push several buffers at once.
+        let bufs: Vec<Buffer> = (0..3).map(|_| DummyLibOS::cook_data(32)).collect();
+        let qt: QToken = match libos.push_batch(sockqd, bufs) {
+            Ok(qt) => qt,
+            Err(e) => panic!("failed to push: {:?}", e),
+        };
+        let (_, qr): (QDesc, OperationResult) = safe_wait2(&mut libos, qt);
+        match qr {
+            OperationResult::Push => (),
+            _ => panic!("push_batch() has failed"),
+        }
+
+        // Empty batches are rejected.
+        assert!(libos.push_batch(sockqd, Vec::new()).is_err());
+
+        // Close connection.
+        safe_close_active(&mut libos, sockqd);
+    });
+
+    alice.join().unwrap();
+    bob.join().unwrap();
+}
+
+//======================================================================================================================
+// Cancel Accept
+//======================================================================================================================
+
+/// Tests if pending operations are cancelled, either explicitly or when a timed wait expires.
+#[test]
+fn tcp_cancel_accept() {
+    let (tx, rx): (Sender<DataBuffer>, Receiver<DataBuffer>) = crossbeam_channel::unbounded();
+    let mut libos: InetStack = DummyLibOS::new(ALICE_MAC, ALICE_IPV4, tx, rx, arp());
+
+    let port: u16 = PORT_BASE;
+    let local: SocketAddrV4 = SocketAddrV4::new(ALICE_IPV4, port);
+    let sockqd: QDesc = safe_socket(&mut libos);
+    safe_bind(&mut libos, sockqd, local);
+    safe_listen(&mut libos, sockqd);
+
+    // Expired deadline.
+    let qt: QToken = safe_accept(&mut libos, sockqd);
+    match libos.timedwait2(qt, Some(SystemTime::now())) {
+        Err(e) if e.errno == libc::ETIMEDOUT => (),
+        _ => panic!("timedwait2() should fail with ETIMEDOUT"),
+    };
+    match libos.cancel(qt) {
+        Err(e) if e.errno == libc::EINVAL => (),
+        _ => panic!("queue token should have been invalidated"),
+    };
+
+    // Explicit cancellation.
+    let qt: QToken = safe_accept(&mut libos, sockqd);
+    if let Err(e) = libos.cancel(qt) {
+        panic!("cancel() failed: {:?}", e);
+    }
+    match libos.cancel(qt) {
+        Err(e) if e.errno == libc::EINVAL => (),
+        _ => panic!("queue token should have been invalidated"),
+    };
+
+    safe_close_passive(&mut libos, sockqd);
+}
+
 //======================================================================================================================
 // Bad Socket
 //======================================================================================================================